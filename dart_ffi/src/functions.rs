@@ -17,6 +17,7 @@ pub type DartHandleFinalizer =
 pub type DartWeakPersistentHandle = *mut c_void;
 pub type DartHandle = *mut c_void;
 
+#[allow(unpredictable_function_pointer_comparisons)]
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct DartFunctions {
     pub post_cobject: unsafe extern "C" fn(DartPort, *mut DartCObject) -> bool,
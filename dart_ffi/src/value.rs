@@ -1,7 +1,12 @@
 use std::{
+    cmp::Ordering,
     ffi::CString,
+    fmt::Debug,
+    hash::Hash,
     mem::ManuallyDrop,
+    ops::Deref,
     os::raw::{c_char, c_void},
+    sync::Arc,
 };
 
 use std::ffi::CStr;
@@ -18,6 +23,7 @@ pub enum DartValue {
     Array(Vec<DartValue>),
     I8List(Vec<i8>),
     U8List(Vec<u8>),
+    U8ClampedList(Vec<u8>),
     I16List(Vec<i16>),
     U16List(Vec<u16>),
     I32List(Vec<i32>),
@@ -26,6 +32,8 @@ pub enum DartValue {
     U64List(Vec<u64>),
     F32List(Vec<f32>),
     F64List(Vec<f64>),
+    F32x4List(Vec<[f32; 4]>),
+    External(ExternalBytes),
     SendPort(raw::DartCObjectSendPort),
     Capability(raw::DartCObjectCapability),
     NativePointer(raw::DartCObjectNativePointer),
@@ -55,8 +63,11 @@ impl_from!(DartValue::I16List, Vec<i16>);
 impl_from!(DartValue::U32List, Vec<u32>);
 impl_from!(DartValue::I32List, Vec<i32>);
 impl_from!(DartValue::I64List, Vec<i64>);
+impl_from!(DartValue::U64List, Vec<u64>);
 impl_from!(DartValue::F32List, Vec<f32>);
 impl_from!(DartValue::F64List, Vec<f64>);
+impl_from!(DartValue::F32x4List, Vec<[f32; 4]>);
+impl_from!(DartValue::External, ExternalBytes);
 impl_from!(DartValue::SendPort, raw::DartCObjectSendPort);
 impl_from!(DartValue::Capability, raw::DartCObjectCapability);
 impl_from!(DartValue::NativePointer, raw::DartCObjectNativePointer);
@@ -79,6 +90,64 @@ impl From<String> for DartValue {
     }
 }
 
+/// Byte buffer owned by an arbitrary Rust object (i.e. memory mapped file,
+/// pooled buffer or `bytes::Bytes`). It is sent to Dart as external
+/// `Uint8List` without copying the data. The owner is dropped after Dart
+/// garbage collects the list.
+///
+/// The owner must return the same memory from every `as_ref()` call. Because
+/// the memory may be shared, Dart receives it as an unmodifiable list.
+#[derive(Clone)]
+pub struct ExternalBytes(pub Arc<dyn AsRef<[u8]> + Send + Sync>);
+
+impl ExternalBytes {
+    pub fn new<T: AsRef<[u8]> + Send + Sync + 'static>(owner: T) -> Self {
+        Self(Arc::new(owner))
+    }
+}
+
+impl Deref for ExternalBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        self.0.as_ref().as_ref()
+    }
+}
+
+impl Debug for ExternalBytes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExternalBytes")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+impl PartialEq for ExternalBytes {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl Eq for ExternalBytes {}
+
+impl PartialOrd for ExternalBytes {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ExternalBytes {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (**self).cmp(&**other)
+    }
+}
+
+impl Hash for ExternalBytes {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
+    }
+}
+
 impl DartValue {
     /// Creates a DartValue instance from raw DartCObject. Any data in the
     /// object is copied.
@@ -111,14 +180,14 @@ impl DartValue {
                 let typed_data = &object.value.as_typed_data;
                 Self::from_typed_data(typed_data.ty, typed_data.values, typed_data.length)
             }
-            DartCObjectType::ExternalTypedData => {
+            DartCObjectType::ExternalTypedData | DartCObjectType::UnmodifiableExternalTypedData => {
                 let typed_data = &object.value.as_external_typed_data;
                 Self::from_typed_data(typed_data.ty, typed_data.data, typed_data.length)
             }
             DartCObjectType::SendPort => DartValue::SendPort(object.value.as_send_port),
             DartCObjectType::Capability => DartValue::Capability(object.value.as_capability),
             DartCObjectType::NativePointer => {
-                todo!("Implement NativePointer value");
+                DartValue::NativePointer(object.value.as_native_pointer)
             }
             DartCObjectType::Unsupported => DartValue::Unsupported,
             DartCObjectType::NumberOfTypes => {
@@ -138,7 +207,9 @@ impl DartValue {
             DartTypedDataType::ByteData => DartValue::U8List(Self::vec_from_data(ptr, len)),
             DartTypedDataType::Int8 => DartValue::I8List(Self::vec_from_data(ptr, len)),
             DartTypedDataType::Uint8 => DartValue::U8List(Self::vec_from_data(ptr, len)),
-            DartTypedDataType::Uint8Clamped => todo!("Uint8Clamped typed data is not implemented"),
+            DartTypedDataType::Uint8Clamped => {
+                DartValue::U8ClampedList(Self::vec_from_data(ptr, len))
+            }
             DartTypedDataType::Int16 => DartValue::I16List(Self::vec_from_data(ptr, len)),
             DartTypedDataType::Uint16 => DartValue::U16List(Self::vec_from_data(ptr, len)),
             DartTypedDataType::Int32 => DartValue::I32List(Self::vec_from_data(ptr, len)),
@@ -147,7 +218,7 @@ impl DartValue {
             DartTypedDataType::Uint64 => DartValue::U64List(Self::vec_from_data(ptr, len)),
             DartTypedDataType::Float32 => DartValue::F32List(Self::vec_from_data(ptr, len)),
             DartTypedDataType::Float64 => DartValue::F64List(Self::vec_from_data(ptr, len)),
-            DartTypedDataType::Float32x4 => todo!("Float32x4 typed data is not implemented"),
+            DartTypedDataType::Float32x4 => DartValue::F32x4List(Self::vec_from_data(ptr, len)),
             DartTypedDataType::Invalid => panic!("Invalid TypedDataType"),
         }
     }
@@ -196,7 +267,9 @@ pub mod raw {
         Capability = 10,
         NativePointer = 11,
         Unsupported = 12,
-        NumberOfTypes = 13,
+        // Dart 3.0+
+        UnmodifiableExternalTypedData = 13,
+        NumberOfTypes = 14,
     }
 
     #[repr(C)]
@@ -252,6 +325,7 @@ pub mod raw {
     }
 
     #[repr(C)]
+    #[allow(unpredictable_function_pointer_comparisons)]
     #[derive(Debug, Copy, Clone, PartialEq, Eq)]
     pub struct DartCObjectExternalTypedData {
         pub ty: DartTypedDataType,
@@ -264,6 +338,7 @@ pub mod raw {
     unsafe impl Send for DartCObjectExternalTypedData {}
 
     #[repr(C)]
+    #[allow(unpredictable_function_pointer_comparisons)]
     #[derive(Debug, Copy, Clone, PartialEq, Eq)]
     pub struct DartCObjectNativePointer {
         pub ptr: isize,
@@ -284,7 +359,8 @@ pub mod raw {
                         value.cleanup();
                     }
                 },
-                DartCObjectType::ExternalTypedData => unsafe {
+                DartCObjectType::ExternalTypedData
+                | DartCObjectType::UnmodifiableExternalTypedData => unsafe {
                     let cb = self.value.as_external_typed_data.callback;
                     cb(std::ptr::null_mut(), self.value.as_external_typed_data.peer);
                 },
@@ -463,34 +539,77 @@ impl IntoDartVec<f64> for f64 {
     }
 }
 
-pub struct TypedList<T>(pub T);
+impl IntoDartVec<[f32; 4]> for [f32; 4] {
+    fn dart_type() -> raw::DartTypedDataType {
+        raw::DartTypedDataType::Float32x4
+    }
+}
+
+fn external_typed_data<T>(mut vec: Vec<T>, ty: raw::DartTypedDataType) -> raw::DartCObject {
+    let data = vec.as_mut_ptr();
+    let len = vec.len();
+    // We need to know capacity to free the memory later so we move the
+    // entire vector to heap
+    let vec = Box::into_raw(Box::new(vec));
+    raw::DartCObject {
+        ty: raw::DartCObjectType::ExternalTypedData,
+        value: raw::DartCObjectValue {
+            as_external_typed_data: raw::DartCObjectExternalTypedData {
+                ty,
+                length: len as isize,
+                data: data as *mut u8,
+                peer: vec as *mut c_void,
+                callback: free_vec::<T>,
+            },
+        },
+    }
+}
 
-impl<T> IntoDart for TypedList<Vec<T>>
-where
-    T: IntoDartVec<T>,
-{
+extern "C" fn free_external_bytes(_isolate_callback_data: *mut c_void, peer: *mut c_void) {
+    let _owner: Box<ExternalBytes> = unsafe { Box::from_raw(peer as *mut _) };
+}
+
+impl IntoDart for ExternalBytes {
     fn into_dart(self) -> raw::DartCObject {
-        let mut vec = self.0;
-        let data = vec.as_mut_ptr();
-        let len = vec.len();
-        // We need to know capacity to free the memory later so we move the
-        // entire vector to heap
-        let vec = Box::into_raw(Box::new(vec));
+        let data = self.as_ptr();
+        let len = self.len();
+        // Fat Arc pointer is boxed so that it can be passed as peer
+        let owner = Box::into_raw(Box::new(self));
         raw::DartCObject {
-            ty: raw::DartCObjectType::ExternalTypedData,
+            ty: raw::DartCObjectType::UnmodifiableExternalTypedData,
             value: raw::DartCObjectValue {
                 as_external_typed_data: raw::DartCObjectExternalTypedData {
-                    ty: T::dart_type(),
+                    ty: raw::DartTypedDataType::Uint8,
                     length: len as isize,
                     data: data as *mut u8,
-                    peer: vec as *mut c_void,
-                    callback: free_vec::<T>,
+                    peer: owner as *mut c_void,
+                    callback: free_external_bytes,
                 },
             },
         }
     }
 }
 
+pub struct TypedList<T>(pub T);
+
+impl<T> IntoDart for TypedList<Vec<T>>
+where
+    T: IntoDartVec<T>,
+{
+    fn into_dart(self) -> raw::DartCObject {
+        external_typed_data(self.0, T::dart_type())
+    }
+}
+
+/// Sent to Dart as `Uint8ClampedList`.
+pub struct ClampedList(pub Vec<u8>);
+
+impl IntoDart for ClampedList {
+    fn into_dart(self) -> raw::DartCObject {
+        external_typed_data(self.0, raw::DartTypedDataType::Uint8Clamped)
+    }
+}
+
 impl<T> IntoDart for Vec<T>
 where
     T: IntoDart,
@@ -562,6 +681,7 @@ impl IntoDart for DartValue {
             Self::Array(value) => value.into_dart(),
             Self::I8List(value) => TypedList(value).into_dart(),
             Self::U8List(value) => TypedList(value).into_dart(),
+            Self::U8ClampedList(value) => ClampedList(value).into_dart(),
             Self::I16List(value) => TypedList(value).into_dart(),
             Self::U16List(value) => TypedList(value).into_dart(),
             Self::I32List(value) => TypedList(value).into_dart(),
@@ -570,6 +690,8 @@ impl IntoDart for DartValue {
             Self::U64List(value) => TypedList(value).into_dart(),
             Self::F32List(value) => TypedList(value).into_dart(),
             Self::F64List(value) => TypedList(value).into_dart(),
+            Self::F32x4List(value) => TypedList(value).into_dart(),
+            Self::External(value) => value.into_dart(),
             Self::SendPort(value) => value.into_dart(),
             Self::Capability(value) => value.into_dart(),
             Self::NativePointer(value) => value.into_dart(),
@@ -580,3 +702,76 @@ impl IntoDart for DartValue {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        os::raw::c_void,
+        sync::{
+            atomic::{self, AtomicBool},
+            Arc,
+        },
+    };
+
+    use super::{raw, DartValue, ExternalBytes, IntoDart};
+
+    fn round_trip(value: DartValue) -> DartValue {
+        let mut object = value.into_dart();
+        let res = unsafe { DartValue::from_dart(&object) };
+        object.cleanup();
+        res
+    }
+
+    #[test]
+    fn test_typed_data_round_trip() {
+        let clamped = DartValue::U8ClampedList(vec![0, 128, 255]);
+        assert_eq!(round_trip(clamped.clone()), clamped);
+        let f32x4 = DartValue::F32x4List(vec![[1.0, 2.0, 3.0, 4.0], [5.0, 6.0, 7.0, 8.0]]);
+        assert_eq!(round_trip(f32x4.clone()), f32x4);
+    }
+
+    #[test]
+    fn test_external_bytes() {
+        struct Owner(Vec<u8>, Arc<AtomicBool>);
+        impl AsRef<[u8]> for Owner {
+            fn as_ref(&self) -> &[u8] {
+                &self.0
+            }
+        }
+        impl Drop for Owner {
+            fn drop(&mut self) {
+                self.1.store(true, atomic::Ordering::SeqCst);
+            }
+        }
+        let dropped = Arc::new(AtomicBool::new(false));
+        let bytes = ExternalBytes::new(Owner(vec![1, 2, 3], dropped.clone()));
+        let data = bytes.as_ptr();
+        let mut object = bytes.into_dart();
+        assert!(matches!(
+            object.ty,
+            raw::DartCObjectType::UnmodifiableExternalTypedData
+        ));
+        unsafe {
+            assert_eq!(object.value.as_external_typed_data.data as *const u8, data);
+            assert_eq!(
+                DartValue::from_dart(&object),
+                DartValue::U8List(vec![1, 2, 3])
+            );
+        }
+        assert!(!dropped.load(atomic::Ordering::SeqCst));
+        object.cleanup();
+        assert!(dropped.load(atomic::Ordering::SeqCst));
+    }
+
+    unsafe extern "C" fn noop_finalizer(_isolate_callback_data: *mut c_void, _peer: *mut c_void) {}
+
+    #[test]
+    fn test_native_pointer_round_trip() {
+        let pointer = DartValue::NativePointer(raw::DartCObjectNativePointer {
+            ptr: 0x1000,
+            size: 16,
+            callback: noop_finalizer,
+        });
+        assert_eq!(round_trip(pointer.clone()), pointer);
+    }
+}
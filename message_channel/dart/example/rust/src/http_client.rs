@@ -1,8 +1,7 @@
 use std::mem::ManuallyDrop;
 
-use async_trait::async_trait;
 use irondash_message_channel::{
-    AsyncMethodHandler, IntoValue, MethodCall, PlatformError, PlatformResult, TryFromValue, Value,
+    AsyncMethodHandler, IntoValue, MethodRouter, PlatformError, TryFromValue, Value,
 };
 use log::debug;
 use thiserror::Error;
@@ -18,8 +17,6 @@ struct LoadResponse {
     content_length: i64,
 }
 
-struct HttpClient {}
-
 #[derive(Error, Debug)]
 enum HttpClientError {
     #[error("Reqwest error: {0}")]
@@ -38,33 +35,13 @@ impl From<HttpClientError> for PlatformError {
     }
 }
 
-impl HttpClient {
-    async fn load(&self, request: LoadRequest) -> Result<LoadResponse, HttpClientError> {
-        debug!("Loading request...");
-        let response = reqwest::get(request.url).await?;
-        Ok(LoadResponse {
-            status_code: response.status().as_u16(),
-            content_length: response.content_length().map(|f| f as i64).unwrap_or(-1),
-        })
-    }
-}
-
-#[async_trait(?Send)]
-impl AsyncMethodHandler for HttpClient {
-    async fn on_method_call(&self, call: MethodCall) -> PlatformResult {
-        match call.method.as_str() {
-            "load" => {
-                let request: LoadRequest = call.args.try_into()?;
-                let response = self.load(request).await?;
-                Ok(response.into())
-            }
-            _ => Err(PlatformError {
-                code: "invalid_method".into(),
-                message: Some(format!("Unknown Method: {}", call.method)),
-                detail: Value::Null,
-            }),
-        }
-    }
+async fn load(request: LoadRequest) -> Result<LoadResponse, HttpClientError> {
+    debug!("Loading request...");
+    let response = reqwest::get(request.url).await?;
+    Ok(LoadResponse {
+        status_code: response.status().as_u16(),
+        content_length: response.content_length().map(|f| f as i64).unwrap_or(-1),
+    })
 }
 
 pub(crate) fn init() {
@@ -83,5 +60,9 @@ pub(crate) fn init() {
     // enable tokio runtime for current (platform) thread
     let _tokio_handle = ManuallyDrop::new(tokio_runtime.handle().enter());
 
-    let _ = ManuallyDrop::new(HttpClient {}.register("http_client_channel"));
+    let _ = ManuallyDrop::new(
+        MethodRouter::new()
+            .method("load", load)
+            .register("http_client_channel"),
+    );
 }
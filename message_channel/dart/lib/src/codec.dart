@@ -1,5 +1,6 @@
 import 'dart:convert';
 import 'dart:ffi';
+import 'dart:isolate';
 import 'dart:typed_data';
 
 import 'finalizable_handle.dart';
@@ -33,7 +34,11 @@ const int _valueFinalizableHandle = _valueAttachment - 1;
 
 const int _valueList = 255 - 16;
 const int _valueMap = 255 - 17;
-const int _valueLast = _valueMap;
+const int _valueUint64List = 255 - 18;
+
+// Serialization; Index of object in [Serializer.dartObjects].
+const int _valueDartObject = 255 - 19;
+const int _valueLast = _valueDartObject;
 
 abstract class FinalizableHandleProvider {
   FinalizableHandle? getFinalizableHandle(int id);
@@ -41,10 +46,20 @@ abstract class FinalizableHandleProvider {
 
 /// Similar to StandardMessageCodec, but uses NativeList for typed lists
 class Serializer {
-  Serializer(this._functions);
+  Serializer(this._functions, {this.allowDartObjects = true});
 
   final NativeFunctions _functions;
 
+  /// Whether message may contain [SendPort], [Capability] or
+  /// [TransferableTypedData]. If false serializing such object throws.
+  final bool allowDartObjects;
+
+  /// [SendPort], [Capability] and [TransferableTypedData] instances
+  /// encountered during serialization. These can not be passed through
+  /// native list and must be sent to Rust through native port along with the
+  /// serialized message.
+  final dartObjects = <Object>[];
+
   /// Important: Serialized NativeList must be passed to Rust code and deserialized
   /// otherwise all typed lists will leak.
   NativeList<Uint8> serialize(Object? message) {
@@ -132,6 +147,12 @@ class Serializer {
       v.asTypedList().setAll(0, value);
       _writeNativeList(buffer, v);
       nativeLists.add(v);
+    } else if (value is Uint64List) {
+      buffer.putUint8(_valueUint64List);
+      final v = NativeList.create<Uint64>(_functions, value.length);
+      v.asTypedList().setAll(0, value);
+      _writeNativeList(buffer, v);
+      nativeLists.add(v);
     } else if (value is Float32List) {
       buffer.putUint8(_valueFloat32List);
       final v = NativeList.create<Float>(_functions, value.length);
@@ -144,6 +165,16 @@ class Serializer {
       v.asTypedList().setAll(0, value);
       _writeNativeList(buffer, v);
       nativeLists.add(v);
+    } else if (value is SendPort ||
+        value is Capability ||
+        value is TransferableTypedData) {
+      if (!allowDartObjects) {
+        throw ArgumentError.value(
+            value, 'value', 'Native port not available to send Dart object');
+      }
+      buffer.putUint8(_valueDartObject);
+      _writeSize(buffer, dartObjects.length);
+      dartObjects.add(value);
     } else if (value is Iterable) {
       buffer.putUint8(_valueList);
       _writeSize(buffer, value.length);
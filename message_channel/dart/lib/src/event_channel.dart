@@ -12,16 +12,14 @@ class NativeEventChannel {
   });
 
   Stream<dynamic> receiveBroadcastStream([dynamic arguments]) {
-    final messageChannel = MessageChannel(name, context: context);
-    final methodChannel =
-        NativeMethodChannel.withMessageChannel(messageChannel);
+    final dispatcher = _EventDispatcher.forChannel(name, context);
+    final subscriptionId = _nextSubscriptionId++;
     late StreamController<dynamic> controller;
     controller = StreamController.broadcast(onListen: () async {
-      messageChannel.setHandler((message) {
-        controller.add(message);
-      });
+      dispatcher.controllers[subscriptionId] = controller;
       try {
-        await methodChannel.invokeMethod<void>('listen', arguments);
+        await dispatcher.methodChannel
+            .invokeMethod<void>('listen', [subscriptionId, arguments]);
       } catch (exception, stack) {
         FlutterError.reportError(FlutterErrorDetails(
           exception: exception,
@@ -32,10 +30,11 @@ class NativeEventChannel {
         ));
       }
     }, onCancel: () async {
-      messageChannel.setHandler(null);
+      dispatcher.controllers.remove(subscriptionId);
       controller.close();
       try {
-        await methodChannel.invokeMethod<void>('cancel');
+        await dispatcher.methodChannel
+            .invokeMethod<void>('cancel', subscriptionId);
       } catch (exception, stack) {
         FlutterError.reportError(FlutterErrorDetails(
           exception: exception,
@@ -49,6 +48,34 @@ class NativeEventChannel {
     return controller.stream;
   }
 
+  static int _nextSubscriptionId = 1;
+
   final String name;
   final MessageChannelContext context;
 }
+
+/// Shared by all streams of an event channel within a context. Events are
+/// sent from Rust as `[subscriptionId, event]` and routed to the matching
+/// stream controller.
+class _EventDispatcher {
+  _EventDispatcher(String name, MessageChannelContext context)
+      : messageChannel = MessageChannel(name, context: context) {
+    methodChannel = NativeMethodChannel.withMessageChannel(messageChannel);
+    messageChannel.setHandler((message) {
+      final subscriptionId = message[0] as int;
+      controllers[subscriptionId]?.add(message[1]);
+    });
+  }
+
+  static _EventDispatcher forChannel(
+      String name, MessageChannelContext context) {
+    final dispatchers = _dispatchers[context] ??= {};
+    return dispatchers[name] ??= _EventDispatcher(name, context);
+  }
+
+  static final _dispatchers = Expando<Map<String, _EventDispatcher>>();
+
+  final MessageChannel messageChannel;
+  late final NativeMethodChannel methodChannel;
+  final controllers = <int, StreamController<dynamic>>{};
+}
@@ -14,6 +14,51 @@ class NoSuchChannelException implements Exception {
   final String channel;
 }
 
+class MessageCancelledException implements Exception {
+  const MessageCancelledException();
+
+  @override
+  String toString() => 'Message was cancelled';
+}
+
+/// Thrown when Rust side could not decode message sent through
+/// [MessageChannel.sendMessage].
+class MalformedMessageException implements Exception {
+  const MalformedMessageException();
+
+  @override
+  String toString() => 'Message was rejected as malformed';
+}
+
+/// Token that can be used to cancel in-flight [MessageChannel.sendMessage]
+/// calls. When cancelled, the Rust side is notified and the pending future
+/// fails with [MessageCancelledException].
+class CancellationToken {
+  bool get isCancelled => _cancelled;
+
+  void cancel() {
+    if (!_cancelled) {
+      _cancelled = true;
+      final listeners = List.of(_listeners);
+      _listeners.clear();
+      for (final listener in listeners) {
+        listener();
+      }
+    }
+  }
+
+  void addListener(void Function() listener) {
+    _listeners.add(listener);
+  }
+
+  void removeListener(void Function() listener) {
+    _listeners.remove(listener);
+  }
+
+  bool _cancelled = false;
+  final _listeners = <void Function()>[];
+}
+
 typedef MessageChannelHandler = FutureOr<dynamic> Function(dynamic message);
 
 /// [MessageChannel] can be used to communicate with its counterpart (handler)
@@ -32,8 +77,11 @@ class MessageChannel {
     this.handler = handler;
   }
 
-  Future<dynamic> sendMessage(dynamic message) {
-    return _messageSender(message);
+  Future<dynamic> sendMessage(
+    dynamic message, {
+    CancellationToken? cancellationToken,
+  }) {
+    return _messageSender(message, cancellationToken: cancellationToken);
   }
 
   late MessageSender _messageSender;
@@ -51,7 +99,10 @@ class MessageChannelContextError implements Exception {
   String toString() => message;
 }
 
-typedef MessageSender = Future<dynamic> Function(dynamic message);
+typedef MessageSender = Future<dynamic> Function(
+  dynamic message, {
+  CancellationToken? cancellationToken,
+});
 
 typedef MessageChannelContextInitFunction = Int64 Function(Pointer<Void>);
 
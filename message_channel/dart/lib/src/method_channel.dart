@@ -37,8 +37,27 @@ class NativeMethodChannel {
     }
   }
 
-  Future<T> invokeMethod<T>(String method, [dynamic arguments]) async {
-    final res = await _messageChannel.sendMessage([method, arguments]);
+  Future<T> invokeMethod<T>(String method, [dynamic arguments]) {
+    return _invokeMethod(method, arguments, null);
+  }
+
+  /// Invokes method that can be cancelled through [cancellationToken].
+  /// Cancelling the token cancels the call on Rust side and completes
+  /// the returned future with [MessageCancelledException].
+  Future<T> invokeCancellableMethod<T>(
+    String method, {
+    dynamic arguments,
+    required CancellationToken cancellationToken,
+  }) {
+    return _invokeMethod(method, arguments, cancellationToken);
+  }
+
+  Future<T> _invokeMethod<T>(String method, dynamic arguments,
+      CancellationToken? cancellationToken) async {
+    final res = await _messageChannel.sendMessage(
+      [method, arguments],
+      cancellationToken: cancellationToken,
+    );
     if (res[0] == 'ok') {
       return res[1];
     } else {
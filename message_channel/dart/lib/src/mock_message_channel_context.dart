@@ -38,7 +38,36 @@ class MockMessageChannelContext extends MessageChannelContext {
   @override
   MessageSender registerChannel(String name, MessageChannel channel) {
     _channels[name] = channel;
-    return (message) async => await _sendMessage(name, message);
+    return (message, {CancellationToken? cancellationToken}) async {
+      if (cancellationToken == null) {
+        return await _sendMessage(name, message);
+      }
+      if (cancellationToken.isCancelled) {
+        throw const MessageCancelledException();
+      }
+      final completer = Completer();
+      void onCancel() {
+        if (!completer.isCompleted) {
+          completer.completeError(const MessageCancelledException());
+        }
+      }
+
+      cancellationToken.addListener(onCancel);
+      Future.sync(() => _sendMessage(name, message)).then((value) {
+        if (!completer.isCompleted) {
+          completer.complete(value);
+        }
+      }, onError: (Object error, StackTrace stackTrace) {
+        if (!completer.isCompleted) {
+          completer.completeError(error, stackTrace);
+        }
+      });
+      try {
+        return await completer.future;
+      } finally {
+        cancellationToken.removeListener(onCancel);
+      }
+    };
   }
 
   FutureOr<dynamic> _sendMessage(String channel, dynamic message) {
@@ -87,10 +116,12 @@ extension MockEventChannel on MockMessageChannelContext {
   }) {
     registerMockMethodCallHandler(channel, (call) {
       if (call.method == 'listen') {
+        final subscriptionId = call.arguments[0] as int;
         // onListen is responsible for closing the sink
         // ignore: close_sinks
-        final sink = _Sink((msg) => sendMessage(channel, msg));
-        onListen(sink, call.arguments);
+        final sink =
+            _Sink((msg) => sendMessage(channel, [subscriptionId, msg]));
+        onListen(sink, call.arguments[1]);
       } else if (call.method == 'cancel') {
         onCancel();
       }
@@ -19,6 +19,7 @@ class NativeFunctions {
     required this.vecAllocateInt32,
     required this.vecAllocateUint32,
     required this.vecAllocateInt64,
+    required this.vecAllocateUint64,
     required this.vecAllocateFloat,
     required this.vecAllocateDouble,
     required this.vecFreeInt8,
@@ -28,6 +29,7 @@ class NativeFunctions {
     required this.vecFreeInt32,
     required this.vecFreeUint32,
     required this.vecFreeInt64,
+    required this.vecFreeUint64,
     required this.vecFreeFloat,
     required this.vecFreeDouble,
     required this.vecResizeUint8,
@@ -47,6 +49,7 @@ class NativeFunctions {
   final VecAllocate<Int32> vecAllocateInt32;
   final VecAllocate<Uint32> vecAllocateUint32;
   final VecAllocate<Int64> vecAllocateInt64;
+  final VecAllocate<Uint64> vecAllocateUint64;
   final VecAllocate<Float> vecAllocateFloat;
   final VecAllocate<Double> vecAllocateDouble;
 
@@ -57,6 +60,7 @@ class NativeFunctions {
   final VecFree<Int32> vecFreeInt32;
   final VecFree<Uint32> vecFreeUint32;
   final VecFree<Int64> vecFreeInt64;
+  final VecFree<Uint64> vecFreeUint64;
   final VecFree<Float> vecFreeFloat;
   final VecFree<Double> vecFreeDouble;
 
@@ -121,6 +125,8 @@ class NativeFunctions {
             context.ref.vecAllocateUint32.asFunction<VecAllocate<Uint32>>(),
         vecAllocateInt64:
             context.ref.vecAllocateInt64.asFunction<VecAllocate<Int64>>(),
+        vecAllocateUint64:
+            context.ref.vecAllocateUint64.asFunction<VecAllocate<Uint64>>(),
         vecAllocateFloat:
             context.ref.vecAllocateFloat.asFunction<VecAllocate<Float>>(),
         vecAllocateDouble:
@@ -132,6 +138,7 @@ class NativeFunctions {
         vecFreeInt32: context.ref.vecFreeInt32.asFunction<VecFree<Int32>>(),
         vecFreeUint32: context.ref.vecFreeUint32.asFunction<VecFree<Uint32>>(),
         vecFreeInt64: context.ref.vecFreeInt64.asFunction<VecFree<Int64>>(),
+        vecFreeUint64: context.ref.vecFreeUint64.asFunction<VecFree<Uint64>>(),
         vecFreeFloat: context.ref.vecFreeFloat.asFunction<VecFree<Float>>(),
         vecFreeDouble: context.ref.vecFreeDouble.asFunction<VecFree<Double>>(),
         vecResizeUint8:
@@ -159,6 +166,8 @@ class NativeFunctions {
       return vecAllocateUint32 as VecAllocate<T>;
     } else if (t is List<Int64>) {
       return vecAllocateInt64 as VecAllocate<T>;
+    } else if (t is List<Uint64>) {
+      return vecAllocateUint64 as VecAllocate<T>;
     } else if (t is List<Float>) {
       return vecAllocateFloat as VecAllocate<T>;
     } else if (t is List<Double>) {
@@ -193,6 +202,8 @@ class NativeFunctions {
       return vecFreeUint32 as VecFree<T>;
     } else if (t is List<Int64>) {
       return vecFreeInt64 as VecFree<T>;
+    } else if (t is List<Uint64>) {
+      return vecFreeUint64 as VecFree<T>;
     } else if (t is List<Float>) {
       return vecFreeFloat as VecFree<T>;
     } else if (t is List<Double>) {
@@ -253,6 +264,7 @@ final class _GetFunctions extends Struct {
   external Pointer<NativeFunction<_VecAllocate<Int32>>> vecAllocateInt32;
   external Pointer<NativeFunction<_VecAllocate<Uint32>>> vecAllocateUint32;
   external Pointer<NativeFunction<_VecAllocate<Int64>>> vecAllocateInt64;
+  external Pointer<NativeFunction<_VecAllocate<Uint64>>> vecAllocateUint64;
   external Pointer<NativeFunction<_VecAllocate<Float>>> vecAllocateFloat;
   external Pointer<NativeFunction<_VecAllocate<Double>>> vecAllocateDouble;
   external Pointer<NativeFunction<_VecFree<Int8>>> vecFreeInt8;
@@ -262,6 +274,7 @@ final class _GetFunctions extends Struct {
   external Pointer<NativeFunction<_VecFree<Int32>>> vecFreeInt32;
   external Pointer<NativeFunction<_VecFree<Uint32>>> vecFreeUint32;
   external Pointer<NativeFunction<_VecFree<Int64>>> vecFreeInt64;
+  external Pointer<NativeFunction<_VecFree<Uint64>>> vecFreeUint64;
   external Pointer<NativeFunction<_VecFree<Float>>> vecFreeFloat;
   external Pointer<NativeFunction<_VecFree<Double>>> vecFreeDouble;
   external Pointer<NativeFunction<_VecResize<Uint8>>> vecResizeUint8;
@@ -270,8 +283,11 @@ final class _GetFunctions extends Struct {
 typedef _RegisterIsolate = Int64 Function(Int64, Handle);
 typedef RegisterIsolate = IsolateId Function(int dartPort, Object isolateId);
 
-typedef _PostMessage = Void Function(Int64, Pointer<Uint8>, IntPtr);
-typedef PostMessage = void Function(IsolateId, Pointer<Uint8>, int len);
+/// Returns handle under which objects referenced by the message must be sent
+/// to Rust native port, or 0 if `objectCount` is 0.
+typedef _PostMessage = Int64 Function(Int64, Pointer<Uint8>, IntPtr, IntPtr);
+typedef PostMessage = int Function(
+    IsolateId, Pointer<Uint8>, int len, int objectCount);
 
 typedef _AttachWeakPersistentHandle = Handle Function(
     Handle, IntPtr, Handle, Int64);
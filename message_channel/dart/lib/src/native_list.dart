@@ -34,6 +34,10 @@ extension NativeListInt64 on NativeList<Int64> {
   Int64List asTypedList() => data.asTypedList(length);
 }
 
+extension NativeListUint64 on NativeList<Uint64> {
+  Uint64List asTypedList() => data.asTypedList(length);
+}
+
 extension NativeListFloat32 on NativeList<Float> {
   Float32List asTypedList() => data.asTypedList(length);
 }
@@ -43,7 +47,7 @@ extension NativeListFloat64 on NativeList<Double> {
 }
 
 /// List backed by Rust `Vec<T>`. Supported types are 'Int8, `Uint8`, 'Int16',
-/// 'Uint16', `Int32`, 'Uint32', `Int64`, 'Float' and `Double`.
+/// 'Uint16', `Int32`, 'Uint32', `Int64`, `Uint64`, 'Float' and `Double`.
 ///
 /// Must be freed with `free()` or the `data` and `length` fields
 /// can be passed to Rust, where a `Vec<T>` can be recreated using
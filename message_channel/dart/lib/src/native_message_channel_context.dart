@@ -16,6 +16,11 @@ abstract class NativeMessageChannelDelegate {
 
   void postMessage(IsolateId isolateId, Object? message);
 
+  /// Called with Rust native port before any message is posted. Delegate
+  /// must use it to send [SendPort], [Capability] and [TransferableTypedData]
+  /// objects contained in posted messages.
+  void setNativePort(SendPort port);
+
   int token();
 }
 
@@ -25,6 +30,8 @@ class _NativeMessageChannelDelegate implements NativeMessageChannelDelegate {
 
   final NativeFunctions nativeFunctions;
 
+  SendPort? _nativePort;
+
   @override
   IsolateId registerIsolate(int dartPort, Object isolateId) {
     return nativeFunctions.registerIsolate(dartPort, isolateId);
@@ -39,8 +46,25 @@ class _NativeMessageChannelDelegate implements NativeMessageChannelDelegate {
 
   @override
   void postMessage(IsolateId isolateId, Object? message) {
-    final data = Serializer(nativeFunctions).serialize(message);
-    nativeFunctions.postMessage(isolateId, data.data, data.length);
+    final nativePort = _nativePort;
+    final serializer =
+        Serializer(nativeFunctions, allowDartObjects: nativePort != null);
+    final data = serializer.serialize(message);
+    final objects = serializer.dartObjects;
+    // Message is always posted through FFI to keep ordering. Dart objects can
+    // not be passed through FFI; Rust holds the message (and messages posted
+    // after it) until the objects arrive through native port.
+    final handle = nativeFunctions.postMessage(
+        isolateId, data.data, data.length, objects.length);
+    // Without native port serializer rejects Dart objects.
+    if (nativePort != null && objects.isNotEmpty) {
+      nativePort.send(['dart_objects', isolateId, handle, ...objects]);
+    }
+  }
+
+  @override
+  void setNativePort(SendPort port) {
+    _nativePort = port;
   }
 
   @override
@@ -85,14 +109,33 @@ class NativeMessageChannelContext
   @override
   MessageSender registerChannel(String name, MessageChannel channel) {
     _channels[name] = channel;
-    return (msg) => _sendMessage(name, msg);
+    return (msg, {CancellationToken? cancellationToken}) =>
+        _sendMessage(name, msg, cancellationToken);
   }
 
-  Future<dynamic> _sendMessage(String channel, dynamic message) async {
+  Future<dynamic> _sendMessage(String channel, dynamic message,
+      CancellationToken? cancellationToken) async {
+    if (cancellationToken?.isCancelled == true) {
+      throw const MessageCancelledException();
+    }
     final replyId = _nextReplyId++;
     _postMessage(["message", replyId, channel, message]);
     final completer = Completer();
     _pendingReplies[replyId] = completer;
+    if (cancellationToken != null) {
+      void onCancel() {
+        final completer = _pendingReplies.remove(replyId);
+        if (completer != null) {
+          _postMessage(["cancel_message", replyId]);
+          completer.completeError(const MessageCancelledException());
+        }
+      }
+
+      cancellationToken.addListener(onCancel);
+      return completer.future.whenComplete(
+        () => cancellationToken.removeListener(onCancel),
+      );
+    }
     return completer.future;
   }
 
@@ -109,13 +152,19 @@ class NativeMessageChannelContext
     if (message == "reply") {
       final replyId = data[1] as int;
       final value = data[2];
-      final completer = _pendingReplies.remove(replyId)!;
-      completer.complete(value);
+      // Completer may be missing if the message was cancelled.
+      _pendingReplies.remove(replyId)?.complete(value);
     } else if (message == "reply_no_channel") {
       final replyId = data[1] as int;
       final channel = data[2] as String;
-      final completer = _pendingReplies.remove(replyId)!;
-      completer.completeError(NoSuchChannelException(channel: channel));
+      _pendingReplies
+          .remove(replyId)
+          ?.completeError(NoSuchChannelException(channel: channel));
+    } else if (message == "reply_malformed_message") {
+      final replyId = data[1] as int;
+      _pendingReplies
+          .remove(replyId)
+          ?.completeError(const MalformedMessageException());
     } else if (message == "send_message") {
       final channelName = data[1] as String;
       final replyId = data[2] as int;
@@ -158,6 +207,7 @@ class NativeMessageChannelContext
       // NativeSend port is used to get notification on isolate exit
       Isolate.current
           .addOnExitListener(message, response: ['isolate_exit', isolateId]);
+      delegate.setNativePort(message);
     } else {
       if (message is String && message == "ready") {
         ready();
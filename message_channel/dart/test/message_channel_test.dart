@@ -1,4 +1,5 @@
 import 'dart:async';
+import 'dart:isolate';
 
 import 'package:flutter/services.dart';
 import 'package:flutter_test/flutter_test.dart';
@@ -13,6 +14,8 @@ class MockNativeMessageChannelDelegate extends NativeMessageChannelDelegate {
 
   final messages = [];
 
+  SendPort? nativePort;
+
   @override
   Object? attachWeakPersistentHandle(
       Object handle, int id, Object? nullHandle, IsolateId isolateId) {
@@ -31,6 +34,11 @@ class MockNativeMessageChannelDelegate extends NativeMessageChannelDelegate {
     return isolateId;
   }
 
+  @override
+  void setNativePort(SendPort port) {
+    nativePort = port;
+  }
+
   @override
   int token() {
     return 1;
@@ -67,6 +75,27 @@ void main() {
       }
     });
 
+    test('sendPortOrdering', () {
+      final delegate = MockNativeMessageChannelDelegate(isolateId: 1);
+      final context = NativeMessageChannelContext(delegate);
+      context.ready();
+      final channel = MessageChannel('channel1', context: context);
+      final port = ReceivePort();
+      // Messages with Dart objects go through the same path as other
+      // messages and keep their order.
+      channel.sendMessage('M1');
+      channel.sendMessage(port.sendPort);
+      channel.sendMessage('M2');
+      expect(
+          delegate.messages,
+          equals([
+            ['message', 0, 'channel1', 'M1'],
+            ['message', 1, 'channel1', port.sendPort],
+            ['message', 2, 'channel1', 'M2'],
+          ]));
+      port.close();
+    });
+
     test('callNoChannel', () {
       final delegate = MockNativeMessageChannelDelegate(isolateId: 1);
       final context = NativeMessageChannelContext(delegate);
@@ -84,6 +113,25 @@ void main() {
       }
     });
 
+    test('callCancel', () async {
+      final delegate = MockNativeMessageChannelDelegate(isolateId: 1);
+      final context = NativeMessageChannelContext(delegate);
+      context.ready();
+      final channel = MessageChannel('channel1', context: context);
+      final token = CancellationToken();
+      final future = channel.sendMessage('M1', cancellationToken: token);
+      token.cancel();
+      expect(
+          delegate.messages,
+          equals([
+            ['message', 0, 'channel1', 'M1'],
+            ['cancel_message', 0],
+          ]));
+      expect(future, throwsA(const TypeMatcher<MessageCancelledException>()));
+      // Late reply for cancelled message is ignored.
+      context.handleMessage(['reply', 0, 'RES']);
+    });
+
     test('handler', () async {
       final delegate = MockNativeMessageChannelDelegate(isolateId: 1);
       final context = NativeMessageChannelContext(delegate);
@@ -219,5 +267,41 @@ void main() {
       expect(cancelledCalled, isTrue);
       expect(arguments, equals('arg1'));
     });
+
+    test('mockEventChannelMultipleListeners', () async {
+      final context = MockMessageChannelContext();
+      final channel = NativeEventChannel('channel1', context: context);
+
+      final sinks = <Sink>[];
+      int cancelCount = 0;
+      context.registerMockEventChannel('channel1',
+          onListen: (sink, arguments) {
+        sinks.add(sink);
+      }, onCancel: () {
+        ++cancelCount;
+      });
+
+      final events1 = [];
+      final events2 = [];
+      final subscription1 =
+          channel.receiveBroadcastStream('a').listen(events1.add);
+      final subscription2 =
+          channel.receiveBroadcastStream('b').listen(events2.add);
+      await Future.microtask(() => {});
+      expect(sinks.length, equals(2));
+
+      sinks[0].add('first');
+      sinks[1].add('second');
+      await Future.microtask(() => {});
+      expect(events1, equals(['first']));
+      expect(events2, equals(['second']));
+
+      await subscription1.cancel();
+      expect(cancelCount, equals(1));
+      sinks[1].add('third');
+      await Future.microtask(() => {});
+      expect(events2, equals(['second', 'third']));
+      await subscription2.cancel();
+    });
   });
 }
@@ -1,11 +1,15 @@
-use std::rc::{Rc, Weak};
+use std::{
+    rc::{Rc, Weak},
+    time::Duration,
+};
 
 use async_trait::async_trait;
 use irondash_run_loop::{util::FutureCompleter, RunLoop};
 
 use crate::{
-    unpack_method_call, unpack_result, IsolateId, MessageChannel, MessageChannelDelegate,
-    MethodCall, MethodCallError, MethodCallReply, PlatformError, TryFromError, Value,
+    reply_malformed_call, unpack_method_call, unpack_reply, CancellationToken, IsolateId,
+    MessageChannel, MessageChannelDelegate, MethodCall, MethodCallError, MethodCallReply,
+    PendingReplyId, PlatformError, TryFromError, Value,
 };
 
 pub type PlatformResult = Result<Value, PlatformError>;
@@ -20,6 +24,12 @@ impl<T: Into<Value>, E: Into<PlatformError>> IntoPlatformResult for Result<T, E>
     }
 }
 
+impl<T: Into<Value>> IntoPlatformResult for T {
+    fn into_platform_result(self) -> Result<Value, PlatformError> {
+        Ok(self.into())
+    }
+}
+
 #[async_trait(?Send)]
 pub trait AsyncMethodHandler: Sized + 'static {
     async fn on_method_call(&self, call: MethodCall) -> PlatformResult;
@@ -70,29 +80,95 @@ impl AsyncMethodInvoker {
         }
     }
 
+    /// Calls method on target isolate. If the returned future is dropped
+    /// before completing, the pending reply is discarded.
     pub async fn call_method<V: Into<Value>>(
         &self,
         target_isolate: IsolateId,
         method: &str,
         args: V,
+    ) -> Result<Value, MethodCallError> {
+        self.call_method_impl(target_isolate, method, args.into(), None)
+            .await
+    }
+
+    /// Convenience call method that will attempt to convert the result to specified type.
+    pub async fn call_method_cv_with_timeout<
+        V: Into<Value>,
+        R: TryFrom<Value, Error = E>,
+        E: Into<TryFromError>,
+    >(
+        &self,
+        target_isolate: IsolateId,
+        method: &str,
+        args: V,
+        timeout: Duration,
+    ) -> Result<R, MethodCallError> {
+        let res = self
+            .call_method_with_timeout(target_isolate, method, args, timeout)
+            .await;
+        match res {
+            Ok(value) => value
+                .try_into()
+                .map_err(|e: E| MethodCallError::ConversionError(e.into())),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Calls method on target isolate. Fails with [`SendMessageError::Timeout`]
+    /// if the isolate doesn't respond within given duration.
+    ///
+    /// [`SendMessageError::Timeout`]: crate::SendMessageError::Timeout
+    pub async fn call_method_with_timeout<V: Into<Value>>(
+        &self,
+        target_isolate: IsolateId,
+        method: &str,
+        args: V,
+        timeout: Duration,
+    ) -> Result<Value, MethodCallError> {
+        self.call_method_impl(target_isolate, method, args.into(), Some(timeout))
+            .await
+    }
+
+    async fn call_method_impl(
+        &self,
+        target_isolate: IsolateId,
+        method: &str,
+        args: Value,
+        timeout: Option<Duration>,
     ) -> Result<Value, MethodCallError> {
         let (
             future, //
             completer,
         ) = FutureCompleter::new();
 
-        let args: Value = args.into();
         let call: Value = vec![Value::String(method.into()), args].into();
-        MessageChannel::get().send_message(target_isolate, &self.channel_name, call, move |res| {
-            match res {
-                Ok(value) => {
-                    let result = unpack_result(value).expect("Malformed message");
-                    completer.complete(result);
-                }
-                Err(err) => completer.complete(Err(MethodCallError::SendError(err))),
+        let channel = MessageChannel::get();
+        let track = channel
+            .metrics_registry()
+            .track_outgoing_call(&self.channel_name, method);
+        let reply = move |res| {
+            let result = match res {
+                Ok(value) => unpack_reply(value),
+                Err(err) => Err(MethodCallError::SendError(err)),
+            };
+            track(&result);
+            completer.complete(result);
+        };
+        let id = match timeout {
+            Some(timeout) => channel.send_message_with_timeout(
+                target_isolate,
+                &self.channel_name,
+                call,
+                timeout,
+                reply,
+            ),
+            None => {
+                channel.send_message_cancellable(target_isolate, &self.channel_name, call, reply)
             }
-        });
+        };
 
+        let _guard = CancelReplyOnDrop(id);
         future.await
     }
 
@@ -137,18 +213,33 @@ impl AsyncMethodInvoker {
     {
         let args: Value = args.into();
         let call: Value = vec![Value::String(method.into()), args].into();
-        MessageChannel::get().send_message(target_isolate, &self.channel_name, call, move |res| {
-            match res {
-                Ok(value) => {
-                    let result = unpack_result(value).expect("Malformed message");
-                    reply(result);
-                }
-                Err(err) => reply(Err(MethodCallError::SendError(err))),
-            }
+        let channel = MessageChannel::get();
+        let track = channel
+            .metrics_registry()
+            .track_outgoing_call(&self.channel_name, method);
+        channel.send_message(target_isolate, &self.channel_name, call, move |res| {
+            let result = match res {
+                Ok(value) => unpack_reply(value),
+                Err(err) => Err(MethodCallError::SendError(err)),
+            };
+            track(&result);
+            reply(result);
         });
     }
 }
 
+/// Removes pending reply when method call future is dropped before
+/// receiving reply.
+struct CancelReplyOnDrop(Option<PendingReplyId>);
+
+impl Drop for CancelReplyOnDrop {
+    fn drop(&mut self) {
+        if let Some(id) = self.0 {
+            MessageChannel::get().cancel_reply(id);
+        }
+    }
+}
+
 pub struct RegisteredAsyncMethodHandler<T: AsyncMethodHandler> {
     inner: Rc<RegisteredAsyncMethodHandlerInner<T>>,
 }
@@ -205,14 +296,33 @@ impl<T: AsyncMethodHandler> MessageChannelDelegate for RegisteredAsyncMethodHand
         message: Value,
         reply: Box<dyn FnOnce(Value) -> bool + Send>,
     ) {
-        if let Some(call) = unpack_method_call(message, isolate) {
+        self.on_message_cancellable(isolate, message, reply, CancellationToken::new())
+    }
+
+    fn on_message_cancellable(
+        &self,
+        isolate: IsolateId,
+        message: Value,
+        reply: Box<dyn FnOnce(Value) -> bool + Send>,
+        cancellation_token: CancellationToken,
+    ) {
+        if let Some(call) = unpack_method_call(message, isolate, cancellation_token.clone()) {
+            let reply = MessageChannel::get()
+                .metrics_registry()
+                .track_incoming_call(&self.channel, &call.method, reply);
             let handler = self.handler.clone();
             RunLoop::current().spawn(async move {
-                let result = handler.on_method_call(call).await;
-                MethodCallReply { reply }.send(result);
+                // Abort the handler future when the call is cancelled. Nobody
+                // is waiting for the reply in that case.
+                let result = cancellation_token
+                    .run_until_cancelled(handler.on_method_call(call))
+                    .await;
+                if let Some(result) = result {
+                    MethodCallReply { reply }.send(result);
+                }
             });
         } else {
-            panic!("malformed method call message");
+            reply_malformed_call(&self.channel, reply);
         }
     }
 
@@ -0,0 +1,103 @@
+use std::{
+    fmt::Debug,
+    future::{poll_fn, Future},
+    pin::{pin, Pin},
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+};
+
+struct State {
+    cancelled: bool,
+    wakers: Vec<Waker>,
+}
+
+/// Token passed along with incoming messages. It is cancelled when Dart
+/// cancels the message or when the isolate that sent the message exits.
+///
+/// The token can be cloned and sent across threads.
+#[derive(Clone)]
+pub struct CancellationToken {
+    state: Arc<Mutex<State>>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(State {
+                cancelled: false,
+                wakers: Vec::new(),
+            })),
+        }
+    }
+
+    /// Cancels the token and wakes all futures waiting for cancellation.
+    pub fn cancel(&self) {
+        let wakers = {
+            let mut state = self.state.lock().unwrap();
+            state.cancelled = true;
+            std::mem::take(&mut state.wakers)
+        };
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.state.lock().unwrap().cancelled
+    }
+
+    /// Returns future that completes when the token is cancelled.
+    pub fn cancelled(&self) -> Cancelled {
+        Cancelled {
+            token: self.clone(),
+        }
+    }
+
+    /// Runs the future until completion or until the token is cancelled,
+    /// whichever comes first. Returns `None` if the token was cancelled.
+    pub async fn run_until_cancelled<F: Future>(&self, future: F) -> Option<F::Output> {
+        let mut future = pin!(future);
+        let mut cancelled = self.cancelled();
+        poll_fn(|cx| {
+            if Pin::new(&mut cancelled).poll(cx).is_ready() {
+                Poll::Ready(None)
+            } else {
+                future.as_mut().poll(cx).map(Some)
+            }
+        })
+        .await
+    }
+
+    pub(crate) fn ptr_eq(&self, other: &CancellationToken) -> bool {
+        Arc::ptr_eq(&self.state, &other.state)
+    }
+}
+
+impl Debug for CancellationToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CancellationToken")
+            .field("cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
+/// Future returned by [`CancellationToken::cancelled`].
+pub struct Cancelled {
+    token: CancellationToken,
+}
+
+impl Future for Cancelled {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.token.state.lock().unwrap();
+        if state.cancelled {
+            Poll::Ready(())
+        } else {
+            if !state.wakers.iter().any(|w| w.will_wake(cx.waker())) {
+                state.wakers.push(cx.waker().clone());
+            }
+            Poll::Pending
+        }
+    }
+}
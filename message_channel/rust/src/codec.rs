@@ -1,6 +1,8 @@
+use std::fmt::Display;
+
 use irondash_dart_ffi::DartValue;
 
-use crate::value::Value;
+use crate::value::{DartObject, Value};
 
 const VALUE_NULL: u8 = 255 - 0;
 const VALUE_TRUE: u8 = 255 - 1;
@@ -27,79 +29,226 @@ const VALUE_FINALIZABLE_HANDLE: u8 = VALUE_ATTACHMENT - 1;
 
 const VALUE_LIST: u8 = 255 - 16;
 const VALUE_MAP: u8 = 255 - 17;
-const VALUE_LAST: u8 = VALUE_MAP;
+const VALUE_UINT64LIST: u8 = 255 - 18;
+
+// Deserialization; Index of object (SendPort, Capability or
+// TransferableTypedData) sent through native port along with the message.
+pub(crate) const VALUE_DART_OBJECT: u8 = 255 - 19;
+const VALUE_LAST: u8 = VALUE_DART_OBJECT;
+
+/// Error decoding message received from Dart.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CodecError {
+    UnexpectedEnd,
+    UnknownTag(u8),
+    InvalidUtf8,
+    NestingTooDeep,
+    LengthTooLarge(usize),
+    InvalidAttachment,
+}
+
+impl Display for CodecError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CodecError::UnexpectedEnd => write!(f, "unexpected end of message"),
+            CodecError::UnknownTag(tag) => write!(f, "unknown value tag {tag}"),
+            CodecError::InvalidUtf8 => write!(f, "string is not valid UTF-8"),
+            CodecError::NestingTooDeep => {
+                write!(f, "value nesting exceeds {MAX_DEPTH} levels")
+            }
+            CodecError::LengthTooLarge(len) => write!(f, "length {len} exceeds message size"),
+            CodecError::InvalidAttachment => write!(f, "invalid attachment"),
+        }
+    }
+}
 
-pub(super) struct Deserializer {}
+impl std::error::Error for CodecError {}
+
+/// Deserializes message in the format used by Dart side of the channel.
+/// Messages with attachments (external typed data or Dart objects) are
+/// rejected, which makes this safe to call with arbitrary input, i.e. for
+/// fuzzing the decoder.
+pub fn deserialize_message(buf: &[u8]) -> Result<Value, CodecError> {
+    Deserializer::deserialize_without_attachments(buf)
+}
+
+// Maximum nesting of lists and maps.
+const MAX_DEPTH: usize = 512;
+
+pub(super) struct Deserializer {
+    depth: usize,
+    allow_attachments: bool,
+    dart_objects: Vec<Option<DartValue>>,
+    // Used when discarding message; Dart objects are replaced by null.
+    missing_objects_as_null: bool,
+}
 
 impl Deserializer {
-    pub unsafe fn deserialize(buf: &[u8]) -> Value {
-        let mut reader = Reader::new(buf);
-        Self::read_value(&mut reader)
+    /// Deserializes message received from Dart.
+    ///
+    /// # Safety
+    ///
+    /// Attachments in the message are pointers to vectors allocated through
+    /// `allocate_vec_*` functions. The caller must ensure that the message
+    /// comes from Dart and that these pointers are valid.
+    ///
+    /// `dart_objects` are objects that Dart sent through native port along
+    /// with the message. Each can only be referenced once.
+    pub unsafe fn deserialize(
+        buf: &[u8],
+        dart_objects: Vec<DartValue>,
+    ) -> Result<Value, CodecError> {
+        let mut deserializer = Deserializer {
+            depth: 0,
+            allow_attachments: true,
+            dart_objects: dart_objects.into_iter().map(Some).collect(),
+            missing_objects_as_null: false,
+        };
+        deserializer.read_value(&mut Reader::new(buf))
     }
 
-    unsafe fn read_value(reader: &mut Reader) -> Value {
-        if reader.ended() {
-            panic!("Malformed stream");
-        }
-        let t = reader.read_u8();
+    /// Releases attachments of message that will not be delivered, i.e.
+    /// because Dart objects it references never arrived.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`Deserializer::deserialize`].
+    pub unsafe fn discard(buf: &[u8]) {
+        let mut deserializer = Deserializer {
+            depth: 0,
+            allow_attachments: true,
+            dart_objects: Vec::new(),
+            missing_objects_as_null: true,
+        };
+        let _ = deserializer.read_value(&mut Reader::new(buf));
+    }
+
+    /// Deserializes message that may not contain attachments. Safe to call
+    /// with arbitrary input.
+    pub fn deserialize_without_attachments(buf: &[u8]) -> Result<Value, CodecError> {
+        let mut deserializer = Deserializer {
+            depth: 0,
+            allow_attachments: false,
+            dart_objects: Vec::new(),
+            missing_objects_as_null: false,
+        };
+        unsafe { deserializer.read_value(&mut Reader::new(buf)) }
+    }
+
+    unsafe fn read_value(&mut self, reader: &mut Reader) -> Result<Value, CodecError> {
+        let t = reader.read_u8()?;
         if t < VALUE_LAST {
-            return Value::I64(t as i64);
+            return Ok(Value::I64(t as i64));
         }
-        match t {
+        let value = match t {
             VALUE_NULL => Value::Null,
             VALUE_FALSE => Value::Bool(false),
             VALUE_TRUE => Value::Bool(true),
-            VALUE_INT64 => Value::I64(reader.read_i64()),
+            VALUE_INT64 => Value::I64(reader.read_i64()?),
             VALUE_FLOAT64 => {
                 reader.align_to(8);
-                Value::F64(reader.read_f64())
+                Value::F64(reader.read_f64()?)
             }
             VALUE_SMALL_STRING => {
-                let len = reader.read_size();
-                Value::String(reader.read_string(len))
+                let len = reader.read_size()?;
+                Value::String(reader.read_string(len)?)
             }
             VALUE_STRING => {
-                let vec = Self::read_vec::<u8>(reader);
-                Value::String(String::from_utf8_unchecked(vec))
+                let vec = self.read_vec::<u8>(reader)?;
+                Value::String(String::from_utf8(vec).map_err(|_| CodecError::InvalidUtf8)?)
             }
-            VALUE_INT8LIST => Value::I8List(Self::read_vec::<i8>(reader)),
-            VALUE_UINT8LIST => Value::U8List(Self::read_vec::<u8>(reader)),
-            VALUE_INT16LIST => Value::I16List(Self::read_vec::<i16>(reader)),
-            VALUE_UINT16LIST => Value::U16List(Self::read_vec::<u16>(reader)),
-            VALUE_INT32LIST => Value::I32List(Self::read_vec::<i32>(reader)),
-            VALUE_UINT32LIST => Value::U32List(Self::read_vec::<u32>(reader)),
-            VALUE_INT64LIST => Value::I64List(Self::read_vec::<i64>(reader)),
-            VALUE_FLOAT32LIST => Value::F32List(Self::read_vec::<f32>(reader)),
-            VALUE_FLOAT64LIST => Value::F64List(Self::read_vec::<f64>(reader)),
+            VALUE_INT8LIST => Value::I8List(self.read_vec::<i8>(reader)?),
+            VALUE_UINT8LIST => Value::U8List(self.read_vec::<u8>(reader)?),
+            VALUE_INT16LIST => Value::I16List(self.read_vec::<i16>(reader)?),
+            VALUE_UINT16LIST => Value::U16List(self.read_vec::<u16>(reader)?),
+            VALUE_INT32LIST => Value::I32List(self.read_vec::<i32>(reader)?),
+            VALUE_UINT32LIST => Value::U32List(self.read_vec::<u32>(reader)?),
+            VALUE_INT64LIST => Value::I64List(self.read_vec::<i64>(reader)?),
+            VALUE_UINT64LIST => Value::U64List(self.read_vec::<u64>(reader)?),
+            VALUE_FLOAT32LIST => Value::F32List(self.read_vec::<f32>(reader)?),
+            VALUE_FLOAT64LIST => Value::F64List(self.read_vec::<f64>(reader)?),
             VALUE_LIST => {
-                let len = reader.read_size();
+                // Every value takes at least one byte
+                let len = reader.read_size()?;
+                reader.check_remaining(len)?;
+                self.enter()?;
                 let mut list = Vec::with_capacity(len);
                 for _ in 0..len {
-                    let value = Self::read_value(reader);
+                    let value = self.read_value(reader)?;
                     list.push(value);
                 }
+                self.leave();
                 Value::List(list)
             }
             VALUE_MAP => {
-                let len = reader.read_size();
-                let mut map = Vec::<(Value, Value)>::new();
+                let len = reader.read_size()?;
+                reader.check_remaining(len.saturating_mul(2))?;
+                self.enter()?;
+                let mut map = Vec::<(Value, Value)>::with_capacity(len);
                 for _ in 0..len {
-                    let k = Self::read_value(reader);
-                    let v = Self::read_value(reader);
+                    let k = self.read_value(reader)?;
+                    let v = self.read_value(reader)?;
                     map.push((k, v));
                 }
+                self.leave();
                 Value::Map(map.into())
             }
-            _ => {
-                panic!("Unsupported value type: {t}");
+            VALUE_DART_OBJECT if self.missing_objects_as_null => {
+                reader.read_size()?;
+                Value::Null
+            }
+            VALUE_DART_OBJECT => {
+                let index = reader.read_size()?;
+                let object = self
+                    .dart_objects
+                    .get_mut(index)
+                    .and_then(Option::take)
+                    .ok_or(CodecError::InvalidAttachment)?;
+                Self::dart_object_to_value(object)?
             }
+            _ => return Err(CodecError::UnknownTag(t)),
+        };
+        Ok(value)
+    }
+
+    fn dart_object_to_value(object: DartValue) -> Result<Value, CodecError> {
+        match object {
+            DartValue::SendPort(port) => Ok(Value::Dart(DartObject::SendPort(port))),
+            DartValue::Capability(capability) => {
+                Ok(Value::Dart(DartObject::Capability(capability)))
+            }
+            // TransferableTypedData is received as bytes.
+            DartValue::U8List(bytes) => Ok(Value::U8List(bytes)),
+            _ => Err(CodecError::InvalidAttachment),
+        }
+    }
+
+    fn enter(&mut self) -> Result<(), CodecError> {
+        if self.depth >= MAX_DEPTH {
+            return Err(CodecError::NestingTooDeep);
         }
+        self.depth += 1;
+        Ok(())
     }
 
-    unsafe fn read_vec<T>(reader: &mut Reader) -> Vec<T> {
-        let ptr = reader.read_u64();
-        let size = reader.read_size() as u64;
-        Vec::<T>::from_raw_parts(ptr as *mut T, size as usize, size as usize)
+    fn leave(&mut self) {
+        self.depth -= 1;
+    }
+
+    unsafe fn read_vec<T>(&mut self, reader: &mut Reader) -> Result<Vec<T>, CodecError> {
+        let ptr = reader.read_u64()? as usize;
+        let size = reader.read_size()?;
+        if !self.allow_attachments {
+            return Err(CodecError::InvalidAttachment);
+        }
+        // Alignment is always power of two.
+        if ptr == 0 || ptr & (std::mem::align_of::<T>() - 1) != 0 {
+            return Err(CodecError::InvalidAttachment);
+        }
+        if size > isize::MAX as usize / std::mem::size_of::<T>().max(1) {
+            return Err(CodecError::LengthTooLarge(size));
+        }
+        Ok(Vec::<T>::from_raw_parts(ptr as *mut T, size, size))
     }
 }
 
@@ -112,61 +261,62 @@ impl<'a> Reader<'a> {
     pub fn new(buf: &'a [u8]) -> Self {
         Reader { buf, pos: 0 }
     }
-    fn read_u8(&mut self) -> u8 {
-        let n = self.buf[self.pos];
-        self.pos += 1;
-        n
-    }
-    fn read_u16(&mut self) -> u16 {
-        self.pos += 2;
-        let s = &self.buf[self.pos - 2..self.pos];
-        u16::from_ne_bytes(clone_into_array(s))
-    }
-    fn read_u32(&mut self) -> u32 {
-        self.pos += 4;
-        let s = &self.buf[self.pos - 4..self.pos];
-        u32::from_ne_bytes(clone_into_array(s))
-    }
-    fn read_u64(&mut self) -> u64 {
-        self.pos += 8;
-        let s = &self.buf[self.pos - 8..self.pos];
-        u64::from_ne_bytes(clone_into_array(s))
-    }
-    fn read_i64(&mut self) -> i64 {
-        self.pos += 8;
-        let s = &self.buf[self.pos - 8..self.pos];
-        i64::from_ne_bytes(clone_into_array(s))
-    }
-    fn read_f64(&mut self) -> f64 {
-        let n = self.read_u64();
-        f64::from_bits(n)
-    }
-    fn read_size(&mut self) -> usize {
-        let n = self.read_u8();
-        match n {
-            254 => self.read_u16() as usize,
-            255 => self.read_u32() as usize,
-            _ => n as usize,
+    fn read_slice(&mut self, len: usize) -> Result<&'a [u8], CodecError> {
+        if len > self.buf.len().saturating_sub(self.pos) {
+            return Err(CodecError::UnexpectedEnd);
         }
+        let s = &self.buf[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(s)
     }
-    fn read_string(&mut self, len: usize) -> String {
-        if len == 0 {
-            String::from("")
+    // Validates length read from the input before allocating for it.
+    fn check_remaining(&self, len: usize) -> Result<(), CodecError> {
+        if len <= self.buf.len().saturating_sub(self.pos) {
+            Ok(())
+        } else if len > self.buf.len() {
+            Err(CodecError::LengthTooLarge(len))
         } else {
-            let v = &self.buf[self.pos..self.pos + len];
-            self.pos += len;
-            String::from_utf8_lossy(v).to_string()
+            Err(CodecError::UnexpectedEnd)
         }
     }
+    fn read_u8(&mut self) -> Result<u8, CodecError> {
+        Ok(self.read_slice(1)?[0])
+    }
+    fn read_u16(&mut self) -> Result<u16, CodecError> {
+        Ok(u16::from_ne_bytes(clone_into_array(self.read_slice(2)?)))
+    }
+    fn read_u32(&mut self) -> Result<u32, CodecError> {
+        Ok(u32::from_ne_bytes(clone_into_array(self.read_slice(4)?)))
+    }
+    fn read_u64(&mut self) -> Result<u64, CodecError> {
+        Ok(u64::from_ne_bytes(clone_into_array(self.read_slice(8)?)))
+    }
+    fn read_i64(&mut self) -> Result<i64, CodecError> {
+        Ok(i64::from_ne_bytes(clone_into_array(self.read_slice(8)?)))
+    }
+    fn read_f64(&mut self) -> Result<f64, CodecError> {
+        let n = self.read_u64()?;
+        Ok(f64::from_bits(n))
+    }
+    fn read_size(&mut self) -> Result<usize, CodecError> {
+        let n = self.read_u8()?;
+        Ok(match n {
+            254 => self.read_u16()? as usize,
+            255 => self.read_u32()? as usize,
+            _ => n as usize,
+        })
+    }
+    fn read_string(&mut self, len: usize) -> Result<String, CodecError> {
+        self.check_remaining(len)?;
+        let v = self.read_slice(len)?;
+        String::from_utf8(v.to_vec()).map_err(|_| CodecError::InvalidUtf8)
+    }
     fn align_to(&mut self, align: usize) {
         let m = self.pos % align;
         if m > 0 {
             self.pos += align - m;
         }
     }
-    fn ended(&self) -> bool {
-        self.pos >= self.buf.len()
-    }
 }
 
 pub(super) struct Serializer {}
@@ -232,6 +382,9 @@ impl Serializer {
             Value::I64List(v) => {
                 Self::write_attachment(writer, v, attachments);
             }
+            Value::U64List(v) => {
+                Self::write_attachment(writer, v, attachments);
+            }
             Value::F32List(v) => {
                 Self::write_attachment(writer, v, attachments);
             }
@@ -253,6 +406,9 @@ impl Serializer {
                     Self::write_value(writer, v.1, attachments);
                 });
             }
+            Value::External(v) => {
+                Self::write_attachment(writer, v, attachments);
+            }
             Value::Dart(v) => {
                 Self::write_attachment(writer, v, attachments);
             }
@@ -340,3 +496,177 @@ where
     a.as_mut().clone_from_slice(slice);
     a
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ExternalBytes;
+
+    fn deserialize(buf: &[u8]) -> Result<Value, CodecError> {
+        Deserializer::deserialize_without_attachments(buf)
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let mut buf = vec![VALUE_LIST, 4, 10, VALUE_NULL, VALUE_TRUE];
+        buf.extend_from_slice(&[VALUE_SMALL_STRING, 2, b'h', b'i']);
+        assert_eq!(
+            deserialize(&buf),
+            Ok(Value::List(vec![
+                Value::I64(10),
+                Value::Null,
+                Value::Bool(true),
+                Value::String("hi".into()),
+            ]))
+        );
+        let mut buf = vec![VALUE_INT64];
+        buf.extend_from_slice(&1000i64.to_ne_bytes());
+        assert_eq!(deserialize(&buf), Ok(Value::I64(1000)));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(deserialize(&[]), Err(CodecError::UnexpectedEnd));
+        assert_eq!(
+            deserialize(&[VALUE_INT64, 1, 2]),
+            Err(CodecError::UnexpectedEnd)
+        );
+        assert_eq!(
+            deserialize(&[VALUE_LIST, 2, VALUE_NULL]),
+            Err(CodecError::UnexpectedEnd)
+        );
+        // Bytes below VALUE_LAST encode small integers
+        assert_eq!(deserialize(&[VALUE_LAST - 1]), Ok(Value::I64(235)));
+        assert_eq!(
+            deserialize(&[VALUE_SMALL_STRING, 2, 0xC3, 0x28]),
+            Err(CodecError::InvalidUtf8)
+        );
+        assert_eq!(
+            deserialize(&[VALUE_LIST, 255, 255, 255, 255, 255]),
+            Err(CodecError::LengthTooLarge(u32::MAX as usize))
+        );
+        assert_eq!(
+            deserialize(&[VALUE_MAP, 200, 1, 2, 3]),
+            Err(CodecError::LengthTooLarge(400))
+        );
+        let mut nested: Vec<u8> = [VALUE_LIST, 1].repeat(MAX_DEPTH + 1);
+        nested.push(VALUE_NULL);
+        assert_eq!(deserialize(&nested), Err(CodecError::NestingTooDeep));
+
+        let mut attachment = vec![VALUE_UINT8LIST];
+        attachment.extend_from_slice(&8u64.to_ne_bytes());
+        attachment.push(1);
+        assert_eq!(deserialize(&attachment), Err(CodecError::InvalidAttachment));
+    }
+
+    #[test]
+    fn test_serialize_external() {
+        let bytes = ExternalBytes::new(vec![1u8, 2, 3]);
+        let data = bytes.as_ptr();
+        let res = Serializer::serialize(Value::List(vec![bytes.into()]));
+        assert_eq!(res.len(), 2);
+        match &res[0] {
+            DartValue::External(bytes) => assert_eq!(bytes.as_ptr(), data),
+            other => panic!("unexpected attachment {other:?}"),
+        }
+        assert_eq!(
+            res[1],
+            DartValue::U8List(vec![VALUE_LIST, 1, VALUE_ATTACHMENT, 0])
+        );
+    }
+
+    #[test]
+    fn test_deserialize_dart_objects() {
+        use irondash_dart_ffi::raw::{DartCObjectCapability, DartCObjectSendPort};
+
+        let port = DartCObjectSendPort {
+            id: 10,
+            origin_id: 20,
+        };
+        let capability = DartCObjectCapability { id: 30 };
+        let objects = vec![
+            DartValue::SendPort(port),
+            DartValue::Capability(capability),
+            DartValue::U8List(vec![1, 2]),
+        ];
+        let buf = [
+            VALUE_LIST,
+            3,
+            VALUE_DART_OBJECT,
+            2,
+            VALUE_DART_OBJECT,
+            0,
+            VALUE_DART_OBJECT,
+            1,
+        ];
+        let value = unsafe { Deserializer::deserialize(&buf, objects.clone()) };
+        assert_eq!(
+            value,
+            Ok(Value::List(vec![
+                Value::U8List(vec![1, 2]),
+                Value::Dart(DartObject::SendPort(port)),
+                Value::Dart(DartObject::Capability(capability)),
+            ]))
+        );
+        let send_port = match &value.unwrap().as_list().unwrap()[1] {
+            Value::Dart(object) => object.send_port().unwrap(),
+            other => panic!("unexpected value {other:?}"),
+        };
+        assert_eq!(send_port.port, 10);
+
+        // Each object can only be used once.
+        let buf = [VALUE_LIST, 2, VALUE_DART_OBJECT, 0, VALUE_DART_OBJECT, 0];
+        assert_eq!(
+            unsafe { Deserializer::deserialize(&buf, objects.clone()) },
+            Err(CodecError::InvalidAttachment)
+        );
+        assert_eq!(
+            unsafe { Deserializer::deserialize(&[VALUE_DART_OBJECT, 3], objects) },
+            Err(CodecError::InvalidAttachment)
+        );
+        assert_eq!(
+            deserialize(&[VALUE_DART_OBJECT, 0]),
+            Err(CodecError::InvalidAttachment)
+        );
+    }
+
+    #[test]
+    fn test_serialize_u64_list() {
+        let res = Serializer::serialize(Value::U64List(vec![u64::MAX]));
+        assert_eq!(
+            res,
+            vec![
+                DartValue::U64List(vec![u64::MAX]),
+                DartValue::U8List(vec![VALUE_ATTACHMENT, 0]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_arbitrary_input() {
+        // Simple xorshift so that the test is deterministic
+        let mut state = 0x2545F4914F6CDD1Du64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        for _ in 0..10000 {
+            let len = (next() % 64) as usize;
+            let buf: Vec<u8> = (0..len)
+                .map(|_| {
+                    // Bias towards valid tags
+                    let n = next();
+                    if n % 2 == 0 {
+                        VALUE_LAST + (n >> 8) as u8 % (255 - VALUE_LAST + 1)
+                    } else {
+                        (n >> 8) as u8
+                    }
+                })
+                .collect();
+            // Must not panic
+            let _ = deserialize(&buf);
+        }
+    }
+}
@@ -7,14 +7,30 @@ use std::{
     result::Result,
 };
 
-use crate::{TryFromError, Value};
+use crate::{PathSegment, PlatformError, TryFromError, Value};
+
+pub use async_trait::async_trait;
 
 pub struct WrapMut<'a, T>(pub &'a mut T);
 
 pub struct Wrap<'a, T>(pub &'a T);
 
+/// Adds path segment to conversion error. Flattened fields don't have a
+/// segment of their own.
+fn at(error: TryFromError, segment: Option<PathSegment>) -> TryFromError {
+    match segment {
+        Some(segment) => error.at(segment),
+        None => error,
+    }
+}
+
 pub trait Assign {
-    fn assign(&mut self, value: Value, skip_if_empty: bool) -> Result<(), TryFromError>;
+    fn assign(
+        &mut self,
+        value: Value,
+        skip_if_empty: bool,
+        segment: Option<PathSegment>,
+    ) -> Result<(), TryFromError>;
     fn set_optional_to_none(&mut self);
 }
 
@@ -22,8 +38,14 @@ impl<T: TryFrom<Value, Error = E>, E> Assign for WrapMut<'_, Option<T>>
 where
     E: Into<TryFromError>,
 {
-    fn assign(&mut self, value: Value, _skip_if_empty: bool) -> Result<(), TryFromError> {
-        self.0.replace(value.try_into().map_err(|e: E| e.into())?);
+    fn assign(
+        &mut self,
+        value: Value,
+        _skip_if_empty: bool,
+        segment: Option<PathSegment>,
+    ) -> Result<(), TryFromError> {
+        self.0
+            .replace(value.try_into().map_err(|e: E| at(e.into(), segment))?);
         Ok(())
     }
     fn set_optional_to_none(&mut self) {}
@@ -33,10 +55,17 @@ impl<T: TryFrom<Value, Error = E>, E> Assign for &mut WrapMut<'_, Option<Option<
 where
     E: Into<TryFromError>,
 {
-    fn assign(&mut self, value: Value, _skip_if_empty: bool) -> Result<(), TryFromError> {
+    fn assign(
+        &mut self,
+        value: Value,
+        _skip_if_empty: bool,
+        segment: Option<PathSegment>,
+    ) -> Result<(), TryFromError> {
         match value {
             Value::Null => self.0.replace(Option::<T>::None),
-            v => self.0.replace(Some(v.try_into().map_err(|e: E| e.into())?)),
+            v => self
+                .0
+                .replace(Some(v.try_into().map_err(|e: E| at(e.into(), segment))?)),
         };
         Ok(())
     }
@@ -48,7 +77,12 @@ where
 }
 
 impl Assign for &mut &mut WrapMut<'_, Option<Option<Value>>> {
-    fn assign(&mut self, value: Value, skip_if_empty: bool) -> Result<(), TryFromError> {
+    fn assign(
+        &mut self,
+        value: Value,
+        skip_if_empty: bool,
+        _segment: Option<PathSegment>,
+    ) -> Result<(), TryFromError> {
         if skip_if_empty {
             self.0.replace(Some(value));
         } else {
@@ -66,6 +100,33 @@ impl Assign for &mut &mut WrapMut<'_, Option<Option<Value>>> {
     }
 }
 
+/// `Option<Option<T>>` fields distinguish missing value (`None`) from
+/// `null` (`Some(None)`).
+impl<T: TryFrom<Value, Error = E>, E> Assign for &mut &mut WrapMut<'_, Option<Option<Option<T>>>>
+where
+    E: Into<TryFromError>,
+{
+    fn assign(
+        &mut self,
+        value: Value,
+        _skip_if_empty: bool,
+        segment: Option<PathSegment>,
+    ) -> Result<(), TryFromError> {
+        match value {
+            Value::Null => self.0.replace(Some(None)),
+            v => self.0.replace(Some(Some(
+                v.try_into().map_err(|e: E| at(e.into(), segment))?,
+            ))),
+        };
+        Ok(())
+    }
+    fn set_optional_to_none(&mut self) {
+        if self.0.is_none() {
+            self.0.replace(None);
+        }
+    }
+}
+
 pub trait IsNone {
     fn is_none(&self) -> bool;
 }
@@ -81,3 +142,81 @@ impl<T> IsNone for &Wrap<'_, Option<T>> {
         self.0.is_none()
     }
 }
+
+/// Converts method call arguments for `#[handler]` methods.
+pub fn convert_args<T: TryFrom<Value, Error = E>, E: Into<TryFromError>>(
+    args: Value,
+) -> Result<T, PlatformError> {
+    args.try_into()
+        .map_err(|e: E| PlatformError::from(e.into()))
+}
+
+/// Merges entries of flattened field value into the parent map. Flattened
+/// `None` contributes no entries. Flattened value of any other kind than map
+/// is a programming error.
+pub fn flatten_into(target: &mut Vec<(Value, Value)>, value: Value) {
+    match value {
+        Value::Map(map) => target.extend(map),
+        Value::Null => {}
+        other => panic!(
+            "flattened field must convert to Value::Map, got {}",
+            other.kind()
+        ),
+    }
+}
+
+/// Assigns flattened field from map entries not matched by other fields.
+pub trait AssignFlatten {
+    fn assign_flatten(&mut self, rest: Vec<(Value, Value)>) -> Result<(), TryFromError>;
+}
+
+impl<T: TryFrom<Value, Error = E>, E> AssignFlatten for WrapMut<'_, Option<T>>
+where
+    E: Into<TryFromError>,
+{
+    fn assign_flatten(&mut self, rest: Vec<(Value, Value)>) -> Result<(), TryFromError> {
+        self.0.replace(
+            Value::Map(rest.into())
+                .try_into()
+                .map_err(|e: E| e.into())?,
+        );
+        Ok(())
+    }
+}
+
+/// Flattened `Option` is `None` when the entries can not be converted to the
+/// inner type.
+impl<T: TryFrom<Value>> AssignFlatten for &mut WrapMut<'_, Option<Option<T>>> {
+    fn assign_flatten(&mut self, rest: Vec<(Value, Value)>) -> Result<(), TryFromError> {
+        self.0.replace(T::try_from(Value::Map(rest.into())).ok());
+        Ok(())
+    }
+}
+
+/// Assigns field with `with` attribute.
+pub fn assign_with<T, E: Into<TryFromError>>(
+    target: &mut Option<T>,
+    value: Result<T, E>,
+    segment: Option<PathSegment>,
+) -> Result<(), TryFromError> {
+    target.replace(value.map_err(|e| at(e.into(), segment))?);
+    Ok(())
+}
+
+/// Like [`Assign::set_optional_to_none`] but for fields with `with`
+/// attribute, which are not required to implement `TryFrom<Value>`.
+pub trait SetMissingToNone {
+    fn set_missing_to_none(&mut self);
+}
+
+impl<T> SetMissingToNone for WrapMut<'_, Option<T>> {
+    fn set_missing_to_none(&mut self) {}
+}
+
+impl<T> SetMissingToNone for &mut WrapMut<'_, Option<Option<T>>> {
+    fn set_missing_to_none(&mut self) {
+        if self.0.is_none() {
+            self.0.replace(None);
+        }
+    }
+}
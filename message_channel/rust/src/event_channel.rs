@@ -1,17 +1,26 @@
 use std::{
     cell::{Ref, RefCell, RefMut},
-    collections::HashMap,
+    collections::{HashMap, VecDeque},
+    num::NonZeroUsize,
+    pin::pin,
     rc::{Rc, Weak},
 };
 
+use futures::{Stream, StreamExt};
+use irondash_run_loop::{util::FutureCompleter, JoinHandle, RunLoop};
+
 use crate::{
-    IsolateId, MessageChannel, MethodHandler, PostMessageError, RegisteredMethodHandler, Value,
+    CancellationToken, IsolateId, MessageChannel, MethodHandler, PostMessageError,
+    RegisteredMethodHandler, Value,
 };
 
 pub struct EventSink {
     id: i64,
+    // Identifies the Dart stream subscription; Sent along with every event.
+    subscription_id: i64,
     channel_name: String,
     isolate_id: IsolateId,
+    cancellation_token: CancellationToken,
 }
 
 impl EventSink {
@@ -21,7 +30,97 @@ impl EventSink {
 
     pub fn post_message<V: Into<Value>>(&self, message: V) -> Result<(), PostMessageError> {
         let channel = MessageChannel::get();
-        channel.post_message(self.isolate_id, &self.channel_name, message.into())
+        channel.post_message(self.isolate_id, &self.channel_name, self.event(message))
+    }
+
+    fn event<V: Into<Value>>(&self, message: V) -> Value {
+        Value::List(vec![self.subscription_id.into(), message.into()])
+    }
+
+    /// Returns whether the sink has been unregistered, either by Dart
+    /// cancelling the stream subscription or by isolate exit.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation_token.is_cancelled()
+    }
+
+    /// Posts all items from the stream to this sink. The stream is consumed on
+    /// current run loop and stops when the sink is unregistered, the isolate
+    /// exits or the stream ends.
+    pub fn pipe<S>(&self, stream: S) -> JoinHandle<()>
+    where
+        S: Stream + 'static,
+        S::Item: Into<Value>,
+    {
+        let sink = self.clone_sink();
+        RunLoop::current().spawn(async move {
+            let mut stream = Box::pin(stream);
+            let token = sink.cancellation_token.clone();
+            while let Some(Some(item)) = token.run_until_cancelled(stream.next()).await {
+                if sink.post_message(item).is_err() {
+                    break;
+                }
+            }
+        })
+    }
+
+    /// Same as [`EventSink::pipe`], but each item is acknowledged by Dart
+    /// and at most `window` items may be waiting for acknowledgement. Once
+    /// the window is full, the stream is not polled until Dart catches up.
+    ///
+    /// Note that an item is acknowledged as soon as the Dart isolate receives
+    /// it and hands it to the stream controller, not when the listener has
+    /// processed it. This bounds the number of items queued between the
+    /// isolates, but a slow listener may still accumulate events buffered
+    /// by the Dart stream (e.g. while the subscription is paused).
+    pub fn pipe_with_window<S>(&self, stream: S, window: NonZeroUsize) -> JoinHandle<()>
+    where
+        S: Stream + 'static,
+        S::Item: Into<Value>,
+    {
+        let sink = self.clone_sink();
+        RunLoop::current().spawn(async move {
+            let mut stream = pin!(stream);
+            let token = sink.cancellation_token.clone();
+            let channel = MessageChannel::get();
+            let mut in_flight = VecDeque::new();
+            loop {
+                if in_flight.len() >= window.get() {
+                    let (_, ack) = in_flight.pop_front().unwrap();
+                    match token.run_until_cancelled(ack).await {
+                        Some(Ok(_)) => {}
+                        _ => break,
+                    }
+                }
+                let item = match token.run_until_cancelled(stream.next()).await {
+                    Some(Some(item)) => item,
+                    _ => break,
+                };
+                let (ack, completer) = FutureCompleter::new();
+                let id = channel.send_message_cancellable(
+                    sink.isolate_id,
+                    &sink.channel_name,
+                    sink.event(item),
+                    move |res| completer.complete(res),
+                );
+                match id {
+                    Some(id) => in_flight.push_back((id, ack)),
+                    None => break,
+                }
+            }
+            for (id, _) in in_flight {
+                channel.cancel_reply(id);
+            }
+        })
+    }
+
+    fn clone_sink(&self) -> EventSink {
+        EventSink {
+            id: self.id,
+            subscription_id: self.subscription_id,
+            channel_name: self.channel_name.clone(),
+            isolate_id: self.isolate_id,
+            cancellation_token: self.cancellation_token.clone(),
+        }
     }
 }
 
@@ -63,7 +162,7 @@ impl<T: EventHandler> RegisteredEventChannel<T> {
                 channel_name: channel.into(),
                 inner: RefCell::new(Inner {
                     next_sink_id: 1,
-                    isolate_to_sink: HashMap::new(),
+                    sinks: HashMap::new(),
                 }),
             }
             .register(channel),
@@ -71,18 +170,38 @@ impl<T: EventHandler> RegisteredEventChannel<T> {
         }
     }
 
-    pub fn borrow(&self) -> Ref<T> {
+    pub fn borrow(&self) -> Ref<'_, T> {
         self.handler.borrow()
     }
 
-    pub fn borrow_mut(&self) -> RefMut<T> {
+    pub fn borrow_mut(&self) -> RefMut<'_, T> {
         self.handler.borrow_mut()
     }
 }
 
+struct SinkEntry {
+    id: i64,
+    cancellation_token: CancellationToken,
+}
+
 struct Inner {
     next_sink_id: i64,
-    isolate_to_sink: HashMap<IsolateId, i64>,
+    // Keyed by isolate and Dart subscription id.
+    sinks: HashMap<(IsolateId, i64), SinkEntry>,
+}
+
+impl SinkEntry {
+    fn unregister<T: EventHandler>(self, handler: &RefCell<T>) {
+        self.cancellation_token.cancel();
+        handler.borrow_mut().unregister_event_sink(self.id);
+    }
+}
+
+fn unpack_listen_args(args: Value) -> Option<(i64, Value)> {
+    let args: Vec<Value> = args.try_into().ok()?;
+    let mut iter = args.into_iter();
+    let subscription_id = iter.next()?.try_into().ok()?;
+    Some((subscription_id, iter.next()?))
 }
 
 struct EventChannelInternal<T: EventHandler> {
@@ -95,24 +214,43 @@ impl<T: EventHandler> MethodHandler for EventChannelInternal<T> {
     fn on_method_call(&self, call: crate::MethodCall, reply: crate::MethodCallReply) {
         match call.method.as_str() {
             "listen" => {
+                let Some((subscription_id, listen_argument)) = unpack_listen_args(call.args) else {
+                    reply.send_error("invalid_arguments".into(), None, Value::Null);
+                    return;
+                };
                 let mut inner = self.inner.borrow_mut();
                 let sink_id = inner.next_sink_id;
                 inner.next_sink_id += 1;
+                let cancellation_token = CancellationToken::new();
                 let sink = EventSink {
                     id: sink_id,
+                    subscription_id,
                     channel_name: self.channel_name.clone(),
                     isolate_id: call.isolate,
+                    cancellation_token: cancellation_token.clone(),
                 };
-                inner.isolate_to_sink.insert(call.isolate, sink_id);
+                let previous = inner.sinks.insert(
+                    (call.isolate, subscription_id),
+                    SinkEntry {
+                        id: sink_id,
+                        cancellation_token,
+                    },
+                );
+                if let Some(previous) = previous {
+                    previous.unregister(&self.handler);
+                }
                 self.handler
                     .borrow_mut()
-                    .register_event_sink(sink, call.args);
+                    .register_event_sink(sink, listen_argument);
                 reply.send_ok(Value::Null);
             }
             "cancel" => {
                 let mut inner = self.inner.borrow_mut();
-                if let Some(sink_id) = inner.isolate_to_sink.remove(&call.isolate) {
-                    self.handler.borrow_mut().unregister_event_sink(sink_id);
+                let sink = i64::try_from(call.args).ok().and_then(|subscription_id| {
+                    inner.sinks.remove(&(call.isolate, subscription_id))
+                });
+                if let Some(sink) = sink {
+                    sink.unregister(&self.handler);
                 }
                 reply.send_ok(Value::Null);
             }
@@ -122,8 +260,16 @@ impl<T: EventHandler> MethodHandler for EventChannelInternal<T> {
 
     fn on_isolate_destroyed(&self, isolate: IsolateId) {
         let mut inner = self.inner.borrow_mut();
-        if let Some(sink_id) = inner.isolate_to_sink.remove(&isolate) {
-            self.handler.borrow_mut().unregister_event_sink(sink_id);
+        let subscriptions: Vec<_> = inner
+            .sinks
+            .keys()
+            .filter(|(sink_isolate, _)| *sink_isolate == isolate)
+            .cloned()
+            .collect();
+        for key in subscriptions {
+            if let Some(sink) = inner.sinks.remove(&key) {
+                sink.unregister(&self.handler);
+            }
         }
     }
 }
@@ -92,10 +92,7 @@ impl FinalizableHandle {
         let mut object = state.objects.remove(&self.id);
         if let Some(mut object) = object.take() {
             if let Some(mut finalizer) = object.finalizer.take() {
-                let sender = RUN_LOOP_SENDER
-                    .get()
-                    .expect("MessageChannel was not initialized!");
-                sender.send(move || {
+                object.run_loop_sender.send(move || {
                     let finalizer = finalizer.take().unwrap();
                     finalizer();
                 });
@@ -189,6 +186,7 @@ unsafe impl<T> Send for Movable<T> {}
 struct FinalizableObjectState {
     handle: Option<Movable<DartWeakPersistentHandle>>,
     isolate_id: IsolateId,
+    #[cfg_attr(feature = "mock", allow(dead_code))]
     external_size: isize,
     run_loop_sender: RunLoopSender,
     finalizer: Option<Capsule<Box<dyn FnOnce()>>>,
@@ -0,0 +1,171 @@
+use std::rc::{Rc, Weak};
+
+use async_trait::async_trait;
+use futures::{channel::oneshot, future::LocalBoxFuture};
+use irondash_run_loop::RunLoop;
+
+use crate::{
+    unpack_reply, AsyncMethodHandler, AsyncMethodInvoker, IsolateId, MethodCall, MethodCallError,
+    MethodCallReply, MethodHandler, MethodInvoker, PlatformError, PlatformResult, Value,
+};
+
+/// Interceptor wraps method handler and can be used to implement cross-cutting
+/// concerns such as logging, authorization or timing. Interceptor can inspect
+/// or modify the [`MethodCall`], short-circuit the call by returning error
+/// without calling [`Next::run`], or post-process the result.
+///
+/// Interceptors are attached to handlers using [`Intercepted::new`] and
+/// [`Intercepted::with`]. When multiple interceptors are attached, the last
+/// one attached is the outermost one.
+///
+/// ```no_run
+/// use irondash_message_channel::{
+///     AsyncMethodHandler, Intercepted, MethodCall, MethodInterceptor, MethodRouter, Next,
+///     PlatformResult,
+/// };
+///
+/// struct Logging {}
+///
+/// #[async_trait::async_trait(?Send)]
+/// impl MethodInterceptor for Logging {
+///     async fn intercept(&self, call: MethodCall, next: Next<'_>) -> PlatformResult {
+///         println!("calling {}", call.method);
+///         next.run(call).await
+///     }
+/// }
+///
+/// let router = MethodRouter::new().method("ping", |_: ()| async move { "pong" });
+/// let _handler = Intercepted::new(router, Logging {}).register("ping_channel");
+/// ```
+#[async_trait(?Send)]
+pub trait MethodInterceptor: 'static {
+    async fn intercept(&self, call: MethodCall, next: Next<'_>) -> PlatformResult;
+}
+
+/// Remainder of the interceptor chain.
+pub struct Next<'a> {
+    next: Box<dyn FnOnce(MethodCall) -> LocalBoxFuture<'a, PlatformResult> + 'a>,
+}
+
+impl<'a> Next<'a> {
+    fn new<F>(next: F) -> Self
+    where
+        F: FnOnce(MethodCall) -> LocalBoxFuture<'a, PlatformResult> + 'a,
+    {
+        Self {
+            next: Box::new(next),
+        }
+    }
+
+    /// Passes the call to next interceptor or to the handler.
+    pub async fn run(self, call: MethodCall) -> PlatformResult {
+        (self.next)(call).await
+    }
+}
+
+/// Method handler with interceptor attached. Implements [`AsyncMethodHandler`]
+/// or [`MethodHandler`], depending on the wrapped handler.
+///
+/// Note that a wrapped [`MethodHandler`] is not called synchronously when
+/// the message is received. Because interceptors are asynchronous, the call
+/// is dispatched from a task spawned on the current run loop.
+pub struct Intercepted<H, I> {
+    handler: Rc<H>,
+    interceptor: Rc<I>,
+}
+
+impl<H, I> Intercepted<H, I> {
+    pub fn new(handler: H, interceptor: I) -> Self {
+        Self {
+            handler: Rc::new(handler),
+            interceptor: Rc::new(interceptor),
+        }
+    }
+
+    /// Wraps self with another interceptor, which becomes the outermost one.
+    pub fn with<J: MethodInterceptor>(self, interceptor: J) -> Intercepted<Self, J> {
+        Intercepted::new(self, interceptor)
+    }
+
+    /// Returns the wrapped handler.
+    pub fn handler(&self) -> &Rc<H> {
+        &self.handler
+    }
+
+    pub fn interceptor(&self) -> &Rc<I> {
+        &self.interceptor
+    }
+}
+
+#[async_trait(?Send)]
+impl<H: AsyncMethodHandler, I: MethodInterceptor> AsyncMethodHandler for Intercepted<H, I> {
+    async fn on_method_call(&self, call: MethodCall) -> PlatformResult {
+        let handler = &self.handler;
+        let next = Next::new(move |call| handler.on_method_call(call));
+        self.interceptor.intercept(call, next).await
+    }
+
+    fn assign_weak_self(&self, _weak_self: Weak<Self>) {
+        self.handler.assign_weak_self(Rc::downgrade(&self.handler));
+    }
+
+    fn assign_invoker(&self, invoker: AsyncMethodInvoker) {
+        self.handler.assign_invoker(invoker);
+    }
+
+    fn on_isolate_destroyed(&self, isolate: IsolateId) {
+        self.handler.on_isolate_destroyed(isolate);
+    }
+}
+
+impl<H: MethodHandler, I: MethodInterceptor> MethodHandler for Intercepted<H, I> {
+    fn on_method_call(&self, call: MethodCall, reply: MethodCallReply) {
+        let handler = self.handler.clone();
+        let interceptor = self.interceptor.clone();
+        RunLoop::current().spawn(async move {
+            let next = Next::new(move |call| {
+                Box::pin(async move {
+                    let (sender, receiver) = oneshot::channel();
+                    let inner_reply = MethodCallReply {
+                        reply: Box::new(move |value| sender.send(value).is_ok()),
+                    };
+                    handler.on_method_call(call, inner_reply);
+                    match receiver.await {
+                        Ok(value) => into_platform_result(value),
+                        Err(_) => Err(PlatformError {
+                            code: "reply_dropped".into(),
+                            message: Some("method handler dropped the reply".into()),
+                            detail: Value::Null,
+                        }),
+                    }
+                })
+            });
+            let result = interceptor.intercept(call, next).await;
+            reply.send(result);
+        });
+    }
+
+    fn assign_weak_self(&self, _weak_self: Weak<Self>) {
+        self.handler.assign_weak_self(Rc::downgrade(&self.handler));
+    }
+
+    fn assign_invoker(&self, invoker: MethodInvoker) {
+        self.handler.assign_invoker(invoker);
+    }
+
+    fn on_isolate_destroyed(&self, isolate: IsolateId) {
+        self.handler.on_isolate_destroyed(isolate);
+    }
+}
+
+fn into_platform_result(reply: Value) -> PlatformResult {
+    match unpack_reply(reply) {
+        Ok(value) => Ok(value),
+        Err(MethodCallError::PlatformError(err)) => Err(err),
+        Err(err) => Err(PlatformError {
+            code: "invalid_reply".into(),
+            message: Some(err.to_string()),
+            detail: Value::Null,
+        }),
+    }
+}
@@ -6,16 +6,30 @@
 #![allow(clippy::derivable_impls)]
 
 mod async_method_handler;
+mod cancellation_token;
+#[cfg_attr(feature = "mock", allow(dead_code))]
 mod codec;
 mod event_channel;
 mod finalizable_handle;
+mod interceptor;
 mod late;
 mod message_channel;
 mod message_channel_inner;
 mod message_transport;
 mod method_handler;
+mod method_router;
+mod metrics;
+#[cfg_attr(feature = "mock", allow(dead_code))]
 mod native_vector;
+mod observer;
+mod schema;
+mod schema_dart;
 mod value;
+#[cfg(feature = "json")]
+mod value_json;
+mod value_macro;
+#[cfg(feature = "serde")]
+mod value_serde;
 
 mod ffi {
     pub type IsolateId = i64;
@@ -28,27 +42,36 @@ pub struct IsolateId(pub ffi::IsolateId);
 use std::ffi::c_void;
 
 pub use async_method_handler::*;
+pub use cancellation_token::*;
+pub use codec::{deserialize_message, CodecError};
 pub use event_channel::*;
 pub use finalizable_handle::*;
+pub use interceptor::*;
 pub use late::*;
-use log::error;
 pub use message_channel::*;
 pub use method_handler::*;
+pub use method_router::*;
+pub use metrics::{
+    CallMetrics, ChannelMetrics, LatencyHistogram, MetricsSnapshot, LATENCY_BUCKETS, OTHER_KEY,
+};
+pub use observer::*;
+pub use schema::*;
 pub use value::*;
+#[cfg(feature = "serde")]
+pub use value_serde::*;
+
+#[cfg(feature = "mock_transport")]
+pub use message_transport::mock::*;
 
 #[cfg(any(target_os = "ios", target_os = "macos"))]
 pub mod value_darwin;
 
-use irondash_dart_ffi::irondash_init_ffi;
-
 #[cfg(feature = "irondash_message_channel_derive")]
 pub mod derive_internal;
 
 #[cfg(feature = "irondash_message_channel_derive")]
 pub use irondash_message_channel_derive::*;
 
-use crate::message_transport::native::{post_message, register_isolate};
-
 #[repr(u64)]
 pub enum FunctionResult {
     NoError = 0,
@@ -75,6 +98,7 @@ pub extern "C" fn irondash_init_message_channel_context(_data: *mut c_void) -> F
             allocate_vec_i32: *mut c_void,
             allocate_vec_u32: *mut c_void,
             allocate_vec_i64: *mut c_void,
+            allocate_vec_u64: *mut c_void,
             allocate_vec_f32: *mut c_void,
             allocate_vec_f64: *mut c_void,
             free_vec_i8: *mut c_void,
@@ -84,6 +108,7 @@ pub extern "C" fn irondash_init_message_channel_context(_data: *mut c_void) -> F
             free_vec_i32: *mut c_void,
             free_vec_u32: *mut c_void,
             free_vec_i64: *mut c_void,
+            free_vec_u64: *mut c_void,
             free_vec_f32: *mut c_void,
             free_vec_f64: *mut c_void,
             resize_vec_u8: *mut c_void,
@@ -91,6 +116,9 @@ pub extern "C" fn irondash_init_message_channel_context(_data: *mut c_void) -> F
 
         use self::native_vector::*;
         use crate::finalizable_handle_native::attach_weak_persistent_handle;
+        use crate::message_transport::native::{post_message, register_isolate};
+        use irondash_dart_ffi::irondash_init_ffi;
+        use log::error;
 
         let context = _data as *mut MessageChannelContext;
         let context = unsafe { &mut *context };
@@ -109,10 +137,11 @@ pub extern "C" fn irondash_init_message_channel_context(_data: *mut c_void) -> F
         context.allocate_vec_i8 = allocate_vec_i8 as *mut _;
         context.allocate_vec_u8 = allocate_vec_u8 as *mut _;
         context.allocate_vec_i16 = allocate_vec_i16 as *mut _;
-        context.allocate_vec_i16 = allocate_vec_u16 as *mut _;
+        context.allocate_vec_u16 = allocate_vec_u16 as *mut _;
         context.allocate_vec_i32 = allocate_vec_i32 as *mut _;
         context.allocate_vec_u32 = allocate_vec_u32 as *mut _;
         context.allocate_vec_i64 = allocate_vec_i64 as *mut _;
+        context.allocate_vec_u64 = allocate_vec_u64 as *mut _;
         context.allocate_vec_f32 = allocate_vec_f32 as *mut _;
         context.allocate_vec_f64 = allocate_vec_f64 as *mut _;
         context.free_vec_i8 = free_vec_i8 as *mut _;
@@ -122,6 +151,7 @@ pub extern "C" fn irondash_init_message_channel_context(_data: *mut c_void) -> F
         context.free_vec_i32 = free_vec_i32 as *mut _;
         context.free_vec_u32 = free_vec_u32 as *mut _;
         context.free_vec_i64 = free_vec_i64 as *mut _;
+        context.free_vec_u64 = free_vec_u64 as *mut _;
         context.free_vec_f32 = free_vec_f32 as *mut _;
         context.free_vec_f64 = free_vec_f64 as *mut _;
         context.resize_vec_u8 = resize_vec_u8 as *mut _;
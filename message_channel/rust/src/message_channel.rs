@@ -2,21 +2,29 @@ use std::{
     fmt::Display,
     rc::Rc,
     sync::{Arc, Mutex},
+    time::Duration,
 };
 
 use once_cell::sync::OnceCell;
 
 use crate::{
-    message_channel_inner::MessageChannelInner,
-    message_transport::{native, MessageTransport},
-    IsolateId, Value,
+    message_channel_inner::MessageChannelInner, message_transport::MessageTransport,
+    metrics::MetricsRegistry, CancellationToken, IsolateId, MessageChannelObserver,
+    MetricsSnapshot, Value,
 };
 
+#[cfg(feature = "mock_transport")]
+use crate::message_transport::mock::MockMessageTransport;
+#[cfg(not(feature = "mock_transport"))]
+use crate::message_transport::native::NativeMessageTransport;
+
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum SendMessageError {
     InvalidIsolate,
     MessageRefused,
     IsolateShutDown,
+    Timeout,
     ChannelNotFound { channel: String },
     HandlerNotRegistered { channel: String },
 }
@@ -35,6 +43,7 @@ impl Display for SendMessageError {
             Self::IsolateShutDown => {
                 write!(f, "target isolate was shut down while waiting for response")
             }
+            Self::Timeout => write!(f, "timed out while waiting for response"),
             Self::ChannelNotFound { channel } => {
                 write!(f, "message channel \"{channel}\" not found")
             }
@@ -60,29 +69,58 @@ impl Display for PostMessageError {
 impl std::error::Error for SendMessageError {}
 impl std::error::Error for PostMessageError {}
 
+/// Identifies message sent through [`MessageChannelBase::send_message_cancellable`] that
+/// is waiting for reply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PendingReplyId(pub i64);
+
 pub trait MessageChannelDelegate {
     fn on_isolate_joined(&self, isolate: IsolateId);
+
     fn on_message(
         &self,
         isolate: IsolateId,
         message: Value,
         reply: Box<dyn FnOnce(Value) -> bool + Send>,
     );
+
+    /// Called for incoming message. The `cancellation_token` is cancelled when
+    /// Dart cancels the message or the isolate exits before reply is sent.
+    /// Default implementation ignores the token and calls
+    /// [`MessageChannelDelegate::on_message`].
+    fn on_message_cancellable(
+        &self,
+        isolate: IsolateId,
+        message: Value,
+        reply: Box<dyn FnOnce(Value) -> bool + Send>,
+        _cancellation_token: CancellationToken,
+    ) {
+        self.on_message(isolate, message, reply)
+    }
     fn on_isolate_exited(&self, isolate: IsolateId);
 }
 
-pub type MessageChannel = MessageChannelBase<native::NativeMessageTransport>;
+#[cfg(not(feature = "mock_transport"))]
+pub type MessageChannel = MessageChannelBase<NativeMessageTransport>;
+
+/// With the `mock_transport` feature enabled messages are routed through
+/// [`MockMessageTransport`], which allows testing handlers without Dart.
+#[cfg(feature = "mock_transport")]
+pub type MessageChannel = MessageChannelBase<MockMessageTransport>;
 
 static MESSAGE_CHANNEL: OnceCell<MessageChannel> = OnceCell::new();
 
 pub struct MessageChannelBase<Transport: MessageTransport> {
     inner: Arc<Mutex<MessageChannelInner<Transport>>>,
+    metrics: Arc<MetricsRegistry>,
 }
 
 impl MessageChannel {
     fn new() -> Self {
+        let metrics = Arc::new(MetricsRegistry::default());
         Self {
-            inner: MessageChannelInner::new(),
+            inner: MessageChannelInner::new(metrics.clone()),
+            metrics,
         }
     }
 
@@ -98,11 +136,59 @@ impl MessageChannel {
         reply: F,
     ) where
         F: FnOnce(Result<Value, SendMessageError>) + 'static,
+    {
+        self.send_message_cancellable(target_isolate, channel, message, reply);
+    }
+
+    /// Same as [`MessageChannelBase::send_message`], but returns identifier
+    /// that can be used to cancel the pending reply through
+    /// [`MessageChannelBase::cancel_reply`]. Returns `None` if the message
+    /// could not be sent, in which case `reply` has already been called
+    /// with the error.
+    pub fn send_message_cancellable<F>(
+        &self,
+        target_isolate: IsolateId,
+        channel: &str,
+        message: Value,
+        reply: F,
+    ) -> Option<PendingReplyId>
+    where
+        F: FnOnce(Result<Value, SendMessageError>) + 'static,
     {
         self.inner
             .lock()
             .unwrap()
-            .send_message(target_isolate, channel, message, reply)
+            .send_message(target_isolate, channel, message, None, reply)
+    }
+
+    /// Same as [`MessageChannelBase::send_message_cancellable`], but `reply`
+    /// will be called with [`SendMessageError::Timeout`] if target isolate doesn't respond
+    /// within given duration.
+    pub fn send_message_with_timeout<F>(
+        &self,
+        target_isolate: IsolateId,
+        channel: &str,
+        message: Value,
+        timeout: Duration,
+        reply: F,
+    ) -> Option<PendingReplyId>
+    where
+        F: FnOnce(Result<Value, SendMessageError>) + 'static,
+    {
+        self.inner.lock().unwrap().send_message(
+            target_isolate,
+            channel,
+            message,
+            Some(timeout),
+            reply,
+        )
+    }
+
+    /// Removes pending reply; The reply callback will be dropped without
+    /// being called. Returns `false` if the reply has already been received
+    /// or the message timed out.
+    pub fn cancel_reply(&self, id: PendingReplyId) -> bool {
+        self.inner.lock().unwrap().cancel_reply(id)
     }
 
     pub fn post_message(
@@ -130,4 +216,27 @@ impl MessageChannel {
     pub fn unregister_delegate(&self, channel: &str) {
         self.inner.lock().unwrap().unregister_delegate(channel)
     }
+
+    /// Sets observer that will be notified about every frame sent or received
+    /// by this channel. Pass `None` to remove the observer.
+    pub fn set_observer(&self, observer: Option<Arc<dyn MessageChannelObserver>>) {
+        self.inner.lock().unwrap().set_observer(observer)
+    }
+
+    /// Enables or disables collecting metrics. Metrics are disabled by
+    /// default.
+    pub fn set_metrics_enabled(&self, enabled: bool) {
+        self.metrics.set_enabled(enabled)
+    }
+
+    /// Returns snapshot of per-channel and per-method metrics collected while
+    /// metrics were enabled. The snapshot can be converted to [`Value`]
+    /// and sent to Dart.
+    pub fn metrics(&self) -> MetricsSnapshot {
+        self.metrics.snapshot()
+    }
+
+    pub(crate) fn metrics_registry(&'static self) -> &'static MetricsRegistry {
+        &self.metrics
+    }
 }
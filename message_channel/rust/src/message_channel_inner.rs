@@ -1,15 +1,20 @@
 use std::{
     collections::{HashMap, HashSet},
     rc::Rc,
-    sync::{Arc, Mutex},
+    sync::{Arc, Mutex, Weak},
+    time::{Duration, Instant},
 };
 
-use irondash_run_loop::{util::Capsule, RunLoop, RunLoopSender};
+use irondash_run_loop::{util::Capsule, Handle, RunLoop, RunLoopSender};
+use log::error;
 
 use crate::{
     message_transport::{MessageTransport, MessageTransportDelegate},
-    FinalizableHandleState, IsolateId, MessageChannelDelegate, PostMessageError, SendMessageError,
-    Value,
+    metrics::MetricsRegistry,
+    observer::approximate_size,
+    CancellationToken, FinalizableHandleState, Frame, FrameDirection, FrameKind, IsolateId,
+    MessageChannelDelegate, MessageChannelObserver, PendingReplyId, PostMessageError,
+    SendMessageError, Value,
 };
 
 struct Delegate {
@@ -20,26 +25,78 @@ struct Delegate {
 struct PendingReply {
     reply: Capsule<Box<dyn FnOnce(Result<Value, SendMessageError>)>>,
     isolate_id: IsolateId,
+    channel: String,
+    sent_at: Instant,
     sender: RunLoopSender,
+    // Unschedules the timeout when pending reply is removed.
+    _timeout: Option<Capsule<Handle>>,
+}
+
+// Removes the cancellation token of incoming message once the reply has been
+// sent or dropped.
+struct IncomingMessageGuard<Transport: MessageTransport> {
+    inner: Weak<Mutex<MessageChannelInner<Transport>>>,
+    isolate_id: IsolateId,
+    reply_id: i64,
+    token: CancellationToken,
+}
+
+impl<Transport: MessageTransport> Drop for IncomingMessageGuard<Transport> {
+    fn drop(&mut self) {
+        if let Some(inner) = self.inner.upgrade() {
+            let mut inner = inner.lock().unwrap();
+            let key = (self.isolate_id, self.reply_id);
+            if matches!(inner.incoming_messages.get(&key), Some(m) if m.token.ptr_eq(&self.token)) {
+                inner.incoming_messages.remove(&key);
+            }
+        }
+    }
+}
+
+struct IncomingMessage {
+    token: CancellationToken,
+    channel: String,
 }
 
 pub(crate) struct MessageChannelInner<Transport: MessageTransport> {
+    weak_self: Weak<Mutex<Self>>,
     transport: Option<Arc<Transport>>,
     delegates: HashMap<String, Delegate>,
     known_isolates: HashSet<IsolateId>,
     pending_replies: HashMap<i64, PendingReply>,
+    // Cancellation tokens for incoming messages waiting for reply.
+    incoming_messages: HashMap<(IsolateId, i64), IncomingMessage>,
     next_message_id: i64,
+    observer: Option<Arc<dyn MessageChannelObserver>>,
+    metrics: Arc<MetricsRegistry>,
+}
+
+fn notify(
+    observer: &Option<Arc<dyn MessageChannelObserver>>,
+    metrics: &MetricsRegistry,
+    frame: &Frame,
+) {
+    metrics.record_frame(frame);
+    if let Some(observer) = observer {
+        observer.on_frame(frame);
+    }
 }
 
 impl<Transport: MessageTransport> MessageChannelInner<Transport> {
-    pub fn new() -> Arc<Mutex<Self>> {
-        let res = Arc::new(Mutex::new(Self {
-            transport: None,
-            delegates: HashMap::new(),
-            known_isolates: HashSet::new(),
-            pending_replies: HashMap::new(),
-            next_message_id: 1,
-        }));
+    pub fn new(metrics: Arc<MetricsRegistry>) -> Arc<Mutex<Self>> {
+        let res = Arc::new_cyclic(|weak_self| {
+            Mutex::new(Self {
+                weak_self: weak_self.clone(),
+                transport: None,
+                delegates: HashMap::new(),
+                known_isolates: HashSet::new(),
+                pending_replies: HashMap::new(),
+                incoming_messages: HashMap::new(),
+                next_message_id: 1,
+                observer: None,
+                metrics,
+            })
+        });
         let res_clone = res.clone();
         res.lock()
             .unwrap()
@@ -52,28 +109,65 @@ impl<Transport: MessageTransport> MessageChannelInner<Transport> {
         self.transport.as_ref().unwrap()
     }
 
+    fn notify(&self, frame: &Frame) {
+        notify(&self.observer, &self.metrics, frame);
+    }
+
+    pub fn set_observer(&mut self, observer: Option<Arc<dyn MessageChannelObserver>>) {
+        self.observer = observer;
+    }
+
     pub fn send_message<F>(
         &mut self,
         target_isolate: IsolateId,
         channel: &str,
         message: Value,
+        timeout: Option<Duration>,
         reply: F,
-    ) where
+    ) -> Option<PendingReplyId>
+    where
         F: FnOnce(Result<Value, SendMessageError>) + 'static,
     {
         if self.known_isolates.contains(&target_isolate) {
             let id = self.next_message_id;
             self.next_message_id = id + 1;
 
+            let sender = RunLoop::current().new_sender();
+            let timeout = timeout.map(|timeout| {
+                let weak_self = self.weak_self.clone();
+                let handle = RunLoop::current().schedule(timeout, move || {
+                    if let Some(inner) = weak_self.upgrade() {
+                        inner
+                            .lock()
+                            .unwrap()
+                            .send_result(id, Err(SendMessageError::Timeout));
+                    }
+                });
+                Capsule::new_with_sender(handle, sender.clone())
+            });
+
             self.pending_replies.insert(
                 id,
                 PendingReply {
-                    sender: RunLoop::current().new_sender(),
-                    reply: Capsule::new(Box::new(reply)),
+                    reply: Capsule::new_with_sender(Box::new(reply), sender.clone()),
                     isolate_id: target_isolate,
+                    channel: channel.into(),
+                    sent_at: Instant::now(),
+                    sender,
+                    _timeout: timeout,
                 },
             );
 
+            self.notify(&Frame {
+                direction: FrameDirection::Outgoing,
+                kind: FrameKind::Message,
+                isolate: target_isolate,
+                channel: Some(channel),
+                reply_id: Some(id),
+                payload_size: approximate_size(&message),
+                latency: None,
+            });
+
             let v = vec![
                 Value::String("send_message".into()),
                 channel.into(),
@@ -86,12 +180,22 @@ impl<Transport: MessageTransport> MessageChannelInner<Transport> {
                 if let Some(mut reply) = reply {
                     (reply.reply.take().unwrap())(Err(SendMessageError::MessageRefused));
                 }
+                None
+            } else {
+                Some(PendingReplyId(id))
             }
         } else {
             reply(Err(SendMessageError::InvalidIsolate));
+            None
         }
     }
 
+    /// Removes pending reply without invoking the reply callback. Returns
+    /// `false` if there is no such pending reply.
+    pub fn cancel_reply(&mut self, id: PendingReplyId) -> bool {
+        self.pending_replies.remove(&id.0).is_some()
+    }
+
     pub fn post_message(
         &mut self,
         target_isolate: IsolateId,
@@ -99,6 +203,15 @@ impl<Transport: MessageTransport> MessageChannelInner<Transport> {
         message: Value,
     ) -> Result<(), PostMessageError> {
         if self.known_isolates.contains(&target_isolate) {
+            self.notify(&Frame {
+                direction: FrameDirection::Outgoing,
+                kind: FrameKind::PostMessage,
+                isolate: target_isolate,
+                channel: Some(channel),
+                reply_id: None,
+                payload_size: approximate_size(&message),
+                latency: None,
+            });
             let v = vec![
                 Value::String("post_message".into()),
                 channel.into(),
@@ -133,6 +246,32 @@ impl<Transport: MessageTransport> MessageChannelInner<Transport> {
 
     fn send_result(&mut self, reply_id: i64, result: Result<Value, SendMessageError>) {
         if let Some(reply) = self.pending_replies.remove(&reply_id) {
+            let (direction, kind, payload_size) = match &result {
+                Ok(value) => (
+                    FrameDirection::Incoming,
+                    FrameKind::Reply,
+                    approximate_size(value),
+                ),
+                Err(
+                    SendMessageError::ChannelNotFound { .. }
+                    | SendMessageError::HandlerNotRegistered { .. },
+                ) => (FrameDirection::Incoming, FrameKind::NoChannel, 0),
+                Err(SendMessageError::Timeout) => (FrameDirection::Local, FrameKind::Timeout, 0),
+                Err(
+                    SendMessageError::IsolateShutDown
+                    | SendMessageError::InvalidIsolate
+                    | SendMessageError::MessageRefused,
+                ) => (FrameDirection::Local, FrameKind::IsolateExit, 0),
+            };
+            self.notify(&Frame {
+                direction,
+                kind,
+                isolate: reply.isolate_id,
+                channel: Some(&reply.channel),
+                reply_id: Some(reply_id),
+                payload_size,
+                latency: Some(reply.sent_at.elapsed()),
+            });
             let mut r = reply.reply;
             reply.sender.send(move || {
                 let reply = r.take().unwrap();
@@ -142,27 +281,76 @@ impl<Transport: MessageTransport> MessageChannelInner<Transport> {
     }
 
     fn handle_send_message(
-        &self,
+        &mut self,
         isolate_id: IsolateId,
         channel: String,
         reply_id: i64,
         message: Value,
     ) {
+        self.notify(&Frame {
+            direction: FrameDirection::Incoming,
+            kind: FrameKind::Message,
+            isolate: isolate_id,
+            channel: Some(&channel),
+            reply_id: Some(reply_id),
+            payload_size: approximate_size(&message),
+            latency: None,
+        });
         let delegate = self.delegates.get(&channel);
         match delegate {
             Some(d) => {
                 let delegate = d.delegate.clone();
                 let transport = self.transport().clone();
+                let observer = self.observer.clone();
+                let metrics = self.metrics.clone();
+                let received_at = Instant::now();
+                let token = CancellationToken::new();
+                self.incoming_messages.insert(
+                    (isolate_id, reply_id),
+                    IncomingMessage {
+                        token: token.clone(),
+                        channel: channel.clone(),
+                    },
+                );
+                let guard = IncomingMessageGuard {
+                    inner: self.weak_self.clone(),
+                    isolate_id,
+                    reply_id,
+                    token: token.clone(),
+                };
                 d.sender.send(move || {
                     let delegate = delegate.get_ref().cloned().unwrap();
                     let reply = Box::new(move |value: Value| {
+                        let _guard = guard;
+                        notify(
+                            &observer,
+                            &metrics,
+                            &Frame {
+                                direction: FrameDirection::Outgoing,
+                                kind: FrameKind::Reply,
+                                isolate: isolate_id,
+                                channel: Some(&channel),
+                                reply_id: Some(reply_id),
+                                payload_size: approximate_size(&value),
+                                latency: Some(received_at.elapsed()),
+                            },
+                        );
                         let v = vec![Value::String("reply".into()), reply_id.into(), value].into();
                         transport.send(isolate_id, v)
                     });
-                    delegate.on_message(isolate_id, message, reply);
+                    delegate.on_message_cancellable(isolate_id, message, reply, token);
                 });
             }
             None => {
+                self.notify(&Frame {
+                    direction: FrameDirection::Outgoing,
+                    kind: FrameKind::NoChannel,
+                    isolate: isolate_id,
+                    channel: Some(&channel),
+                    reply_id: Some(reply_id),
+                    payload_size: 0,
+                    latency: None,
+                });
                 self.transport().send(
                     isolate_id,
                     vec![
@@ -202,9 +390,41 @@ impl<Transport: MessageTransport> MessageChannelInner<Transport> {
             }
             "message" => {
                 let reply_id: i64 = iter.next()?.try_into().ok()?;
-                let channel: String = iter.next()?.try_into().ok()?;
-                let message = iter.next()?;
-                self.handle_send_message(isolate_id, channel, reply_id, message);
+                let channel = iter.next().and_then(|c| String::try_from(c).ok());
+                match (channel, iter.next()) {
+                    (Some(channel), Some(message)) => {
+                        self.handle_send_message(isolate_id, channel, reply_id, message);
+                    }
+                    _ => {
+                        // Let the sender know so that it doesn't wait for reply
+                        // forever.
+                        self.transport().send(
+                            isolate_id,
+                            vec![
+                                Value::String("reply_malformed_message".into()),
+                                reply_id.into(),
+                            ]
+                            .into(),
+                        );
+                        return None;
+                    }
+                }
+            }
+            "cancel_message" => {
+                let reply_id: i64 = iter.next()?.try_into().ok()?;
+                let message = self.incoming_messages.remove(&(isolate_id, reply_id));
+                self.notify(&Frame {
+                    direction: FrameDirection::Incoming,
+                    kind: FrameKind::Cancel,
+                    isolate: isolate_id,
+                    channel: message.as_ref().map(|m| m.channel.as_str()),
+                    reply_id: Some(reply_id),
+                    payload_size: 0,
+                    latency: None,
+                });
+                if let Some(message) = message {
+                    message.token.cancel();
+                }
             }
             _ => {}
         }
@@ -215,7 +435,10 @@ impl<Transport: MessageTransport> MessageChannelInner<Transport> {
 impl<Transport: MessageTransport> MessageTransportDelegate for MessageChannelInner<Transport> {
     fn on_message(&mut self, isolate_id: IsolateId, message: Value) {
         if self.handle_message(isolate_id, message).is_none() {
-            panic!("MessageChannel: Malformed message");
+            error!(
+                "MessageChannel: Malformed message from isolate {}",
+                isolate_id.0
+            );
         }
     }
 
@@ -253,15 +476,19 @@ impl<Transport: MessageTransport> MessageTransportDelegate for MessageChannelInn
             })
             .collect();
         for reply in replies_to_remove {
-            if let Some(reply) = self.pending_replies.remove(&reply) {
-                let mut r = reply.reply;
-                reply.sender.send(move || {
-                    let reply = r.take().unwrap();
-                    reply(Err(SendMessageError::IsolateShutDown));
-                });
-            }
+            self.send_result(reply, Err(SendMessageError::IsolateShutDown));
         }
 
+        // Cancel messages from the isolate that are still being processed.
+        self.incoming_messages.retain(|(id, _), message| {
+            if *id == isolate_id {
+                message.token.cancel();
+                false
+            } else {
+                true
+            }
+        });
+
         // Make sure to execute all finalizers that didn't have chance to register
         // with the isolate.
         FinalizableHandleState::get().finalize_all(isolate_id);
@@ -13,15 +13,17 @@ pub trait MessageTransportDelegate {
     fn on_isolate_exited(&mut self, isolate_id: IsolateId);
 }
 
+#[cfg_attr(feature = "mock", allow(dead_code))]
 pub mod native {
     use std::{
-        collections::HashMap,
+        collections::{HashMap, VecDeque},
         ffi::{c_void, CString},
         fmt::Debug,
         sync::{Arc, Mutex},
     };
 
     use irondash_dart_ffi::{raw, DartPort, DartValue, NativePort};
+    use log::error;
     use once_cell::sync::OnceCell;
 
     use crate::{
@@ -35,6 +37,99 @@ pub mod native {
         delegate: Arc<Mutex<dyn MessageTransportDelegate + Send>>,
         isolate_ports: Arc<Mutex<HashMap<IsolateId, DartPort>>>,
         native_port: Mutex<Option<NativePort>>,
+        incoming: Mutex<IncomingMessages>,
+    }
+
+    struct QueuedMessage {
+        data: Vec<u8>,
+        // Handle under which Dart sends objects referenced by the message.
+        handle: Option<i64>,
+        dart_objects: Option<Vec<DartValue>>,
+    }
+
+    impl QueuedMessage {
+        fn is_ready(&self) -> bool {
+            self.handle.is_none() || self.dart_objects.is_some()
+        }
+    }
+
+    /// Messages received through [`post_message`]. `SendPort`, `Capability`
+    /// and `TransferableTypedData` can not be passed through FFI, so Dart
+    /// sends these through the native port under handle returned from
+    /// [`post_message`]. Messages from an isolate are delivered in the order
+    /// they were posted; Message waiting for Dart objects holds back messages
+    /// posted after it.
+    #[derive(Default)]
+    struct IncomingMessages {
+        queues: HashMap<IsolateId, VecDeque<QueuedMessage>>,
+        next_handle: i64,
+    }
+
+    impl IncomingMessages {
+        /// Queues message and returns handle for Dart objects referenced by
+        /// it, or 0 if there are none.
+        fn post(&mut self, isolate_id: IsolateId, data: Vec<u8>, object_count: usize) -> i64 {
+            let handle = if object_count > 0 {
+                self.next_handle += 1;
+                Some(self.next_handle)
+            } else {
+                None
+            };
+            self.queues
+                .entry(isolate_id)
+                .or_default()
+                .push_back(QueuedMessage {
+                    data,
+                    handle,
+                    dart_objects: None,
+                });
+            handle.unwrap_or(0)
+        }
+
+        /// Stores objects for queued message. Returns false if there is no
+        /// message from the isolate waiting for objects with this handle.
+        fn add_dart_objects(
+            &mut self,
+            isolate_id: IsolateId,
+            handle: i64,
+            objects: Vec<DartValue>,
+        ) -> bool {
+            let message = self.queues.get_mut(&isolate_id).and_then(|queue| {
+                queue
+                    .iter_mut()
+                    .find(|m| m.handle == Some(handle) && m.dart_objects.is_none())
+            });
+            match message {
+                Some(message) => {
+                    message.dart_objects = Some(objects);
+                    true
+                }
+                None => false,
+            }
+        }
+
+        /// Removes messages that can be delivered, in order.
+        fn take_ready(&mut self, isolate_id: IsolateId) -> Vec<(Vec<u8>, Vec<DartValue>)> {
+            let mut res = Vec::new();
+            if let Some(queue) = self.queues.get_mut(&isolate_id) {
+                while queue.front().map(QueuedMessage::is_ready) == Some(true) {
+                    let message = queue.pop_front().unwrap();
+                    res.push((message.data, message.dart_objects.unwrap_or_default()));
+                }
+                if queue.is_empty() {
+                    self.queues.remove(&isolate_id);
+                }
+            }
+            res
+        }
+
+        /// Removes all messages of exited isolate.
+        fn remove_isolate(&mut self, isolate_id: IsolateId) -> Vec<Vec<u8>> {
+            self.queues
+                .remove(&isolate_id)
+                .map(|queue| queue.into_iter().map(|m| m.data).collect())
+                .unwrap_or_default()
+        }
     }
 
     impl Debug for NativeMessageTransport {
@@ -66,24 +161,91 @@ pub mod native {
             delegate.on_message(isolate_id, message);
         }
 
+        /// Queues message and delivers it unless it waits for Dart objects.
+        /// Returns handle for the Dart objects.
+        ///
+        /// # Safety
+        ///
+        /// Message must be serialized by Dart. See [`Deserializer::deserialize`].
+        unsafe fn receive_message(
+            &self,
+            isolate_id: IsolateId,
+            message: Vec<u8>,
+            object_count: usize,
+        ) -> i64 {
+            let mut incoming = self.incoming.lock().unwrap();
+            let handle = incoming.post(isolate_id, message, object_count);
+            self.deliver_ready(&mut incoming, isolate_id);
+            handle
+        }
+
+        fn receive_dart_objects(
+            &self,
+            isolate_id: IsolateId,
+            handle: i64,
+            objects: Vec<DartValue>,
+        ) {
+            let mut incoming = self.incoming.lock().unwrap();
+            if incoming.add_dart_objects(isolate_id, handle, objects) {
+                self.deliver_ready(&mut incoming, isolate_id);
+            } else {
+                error!(
+                    "Unexpected Dart objects handle {handle} from isolate {}",
+                    isolate_id.0
+                );
+            }
+        }
+
+        // Called with incoming messages locked so that messages can not be
+        // reordered between FFI and native port threads.
+        fn deliver_ready(&self, incoming: &mut IncomingMessages, isolate_id: IsolateId) {
+            for (message, dart_objects) in incoming.take_ready(isolate_id) {
+                // Only messages from post_message are queued.
+                match unsafe { Deserializer::deserialize(&message, dart_objects) } {
+                    Ok(value) => self.handle_message(isolate_id, value),
+                    Err(err) => error!("Malformed message from isolate {}: {err}", isolate_id.0),
+                }
+            }
+        }
+
         fn on_nativeport_value_received(&self, v: DartValue) {
+            fn int_value(value: DartValue) -> Option<i64> {
+                match value {
+                    DartValue::I32(v) => Some(v as i64),
+                    DartValue::I64(v) => Some(v),
+                    _ => None,
+                }
+            }
+
             if let DartValue::Array(value) = v {
                 let mut iter = value.into_iter();
                 let first = iter.next();
                 let second = iter.next();
 
                 if let (Some(DartValue::String(message)), Some(isolate_id)) = (first, second) {
-                    let isolate_id = match isolate_id {
+                    let isolate_id = IsolateId(match isolate_id {
                         DartValue::I32(id) => id as i64,
                         DartValue::I64(id) => id,
                         id => panic!("invalid isolate id {id:?}"),
-                    };
+                    });
                     let message = message.to_string_lossy();
                     if message == "isolate_exit" {
-                        let isolate_id = IsolateId(isolate_id);
                         self.isolate_ports.lock().unwrap().remove(&isolate_id);
+                        let pending = self.incoming.lock().unwrap().remove_isolate(isolate_id);
+                        for message in pending {
+                            unsafe { Deserializer::discard(&message) };
+                        }
                         let mut delegate = self.delegate.lock().unwrap();
                         delegate.on_isolate_exited(isolate_id);
+                    } else if message == "dart_objects" {
+                        // Objects referenced by message sent through
+                        // post_message, following the handle it returned.
+                        match iter.next().and_then(int_value) {
+                            Some(handle) => {
+                                self.receive_dart_objects(isolate_id, handle, iter.collect())
+                            }
+                            None => error!("Missing Dart objects handle"),
+                        }
                     }
                 }
             }
@@ -116,6 +278,7 @@ pub mod native {
                 delegate,
                 native_port: Mutex::new(None),
                 isolate_ports: Arc::new(Mutex::new(HashMap::new())),
+                incoming: Mutex::new(IncomingMessages::default()),
             };
             let res = Arc::new(res);
             NATIVE_MESSAGE_TRANSPORT
@@ -150,16 +313,535 @@ pub mod native {
         isolate_id
     }
 
+    // Returns handle under which Dart sends `object_count` objects referenced
+    // by the message through the native port.
     pub(crate) extern "C" fn post_message(
         isolate_id: crate::ffi::IsolateId,
         message: *mut u8,
         len: usize,
-    ) {
+        object_count: usize,
+    ) -> i64 {
         let vec = unsafe { Vec::from_raw_parts(message, len, len) };
-        if let Some(transport) = NativeMessageTransport::get() {
-            let isolate_id = IsolateId(isolate_id);
-            let value = unsafe { Deserializer::deserialize(&vec) };
-            transport.handle_message(isolate_id, value);
+        match NativeMessageTransport::get() {
+            Some(transport) => unsafe {
+                transport.receive_message(IsolateId(isolate_id), vec, object_count)
+            },
+            None => 0,
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use irondash_dart_ffi::{
+            raw::{DartCObjectCapability, DartCObjectSendPort},
+            DartValue,
+        };
+
+        use super::IncomingMessages;
+        use crate::{
+            codec::{Deserializer, VALUE_DART_OBJECT},
+            value::DartObject,
+            IsolateId, Value,
+        };
+
+        const ISOLATE: IsolateId = IsolateId(1);
+
+        fn deliver(incoming: &mut IncomingMessages) -> Vec<Value> {
+            incoming
+                .take_ready(ISOLATE)
+                .into_iter()
+                .map(|(data, objects)| {
+                    unsafe { Deserializer::deserialize(&data, objects) }.unwrap()
+                })
+                .collect()
+        }
+
+        #[test]
+        fn test_ordering() {
+            let port = DartCObjectSendPort {
+                id: 10,
+                origin_id: 20,
+            };
+            let mut incoming = IncomingMessages::default();
+            assert_eq!(incoming.post(ISOLATE, vec![1], 0), 0);
+            let handle = incoming.post(ISOLATE, vec![VALUE_DART_OBJECT, 0], 1);
+            assert_ne!(handle, 0);
+            assert_eq!(incoming.post(ISOLATE, vec![3], 0), 0);
+            assert_eq!(deliver(&mut incoming), vec![Value::I64(1)]);
+
+            // Message waiting for objects holds back messages after it.
+            assert_eq!(incoming.post(ISOLATE, vec![4], 0), 0);
+            assert_eq!(deliver(&mut incoming), vec![]);
+
+            // Handle is checked against isolate and can only be used once.
+            assert!(!incoming.add_dart_objects(IsolateId(2), handle, vec![]));
+            assert!(!incoming.add_dart_objects(ISOLATE, handle + 1, vec![]));
+            assert!(incoming.add_dart_objects(ISOLATE, handle, vec![DartValue::SendPort(port)]));
+            assert!(!incoming.add_dart_objects(ISOLATE, handle, vec![]));
+            assert_eq!(
+                deliver(&mut incoming),
+                vec![
+                    Value::Dart(DartObject::SendPort(port)),
+                    Value::I64(3),
+                    Value::I64(4)
+                ]
+            );
+            assert!(incoming.queues.is_empty());
+        }
+
+        #[test]
+        fn test_isolate_exit() {
+            let capability = DartCObjectCapability { id: 1 };
+            let mut incoming = IncomingMessages::default();
+            let handle = incoming.post(ISOLATE, vec![VALUE_DART_OBJECT, 0], 1);
+            incoming.post(ISOLATE, vec![1], 0);
+            let pending = incoming.remove_isolate(ISOLATE);
+            assert_eq!(pending.len(), 2);
+            for message in pending {
+                unsafe { Deserializer::discard(&message) };
+            }
+            assert!(!incoming.add_dart_objects(
+                ISOLATE,
+                handle,
+                vec![DartValue::Capability(capability)]
+            ));
+            assert_eq!(deliver(&mut incoming), vec![]);
+        }
+    }
+}
+
+/// In-process transport that stands in for Dart isolates. Enabled with the
+/// `mock_transport` feature, in which case [`crate::MessageChannel`] uses it
+/// instead of the native transport.
+#[cfg(feature = "mock_transport")]
+pub mod mock {
+    use std::{
+        collections::HashMap,
+        fmt::Debug,
+        future::Future,
+        rc::Rc,
+        sync::{
+            atomic::{AtomicI64, Ordering},
+            Arc, Mutex,
+        },
+    };
+
+    use irondash_run_loop::{
+        util::{Capsule, FutureCompleter},
+        RunLoop, RunLoopSender,
+    };
+    use log::error;
+    use once_cell::sync::OnceCell;
+
+    use crate::{
+        unpack_reply, IsolateId, MessageChannel, MethodCallError, SendMessageError, Value,
+    };
+
+    use super::{MessageTransport, MessageTransportDelegate};
+
+    /// Message sent from Rust to a [`MockIsolate`] that was not answered by
+    /// a handler registered with [`MockIsolate::set_handler`].
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum MockMessage {
+        /// Message that expects reply. Use [`MockIsolate::reply`] to respond.
+        SendMessage {
+            channel: String,
+            reply_id: i64,
+            message: Value,
+        },
+        PostMessage {
+            channel: String,
+            message: Value,
+        },
+    }
+
+    type PendingReply = Capsule<FutureCompleter<Result<Value, SendMessageError>>>;
+
+    struct IsolateState {
+        next_reply_id: i64,
+        pending_replies: HashMap<i64, PendingReply>,
+        handlers: HashMap<String, Capsule<Rc<dyn Fn(Value) -> Value>>>,
+        messages: Vec<MockMessage>,
+    }
+
+    struct Isolate {
+        id: IsolateId,
+        // Sender for thread on which the isolate was created. All calls
+        // back into MessageChannel are done on this thread.
+        sender: RunLoopSender,
+        state: Mutex<IsolateState>,
+    }
+
+    pub struct MockMessageTransport {
+        delegate: Arc<Mutex<dyn MessageTransportDelegate + Send>>,
+        isolates: Mutex<HashMap<IsolateId, Arc<Isolate>>>,
+        next_isolate_id: AtomicI64,
+    }
+
+    impl Debug for MockMessageTransport {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("MockMessageTransport").finish()
         }
     }
+
+    static MOCK_MESSAGE_TRANSPORT: OnceCell<Arc<MockMessageTransport>> = OnceCell::new();
+
+    impl MockMessageTransport {
+        /// Returns transport used by [`MessageChannel`].
+        pub fn get() -> Arc<Self> {
+            // Transport is created together with message channel.
+            MessageChannel::get();
+            MOCK_MESSAGE_TRANSPORT
+                .get()
+                .cloned()
+                .expect("MockMessageTransport not initialized")
+        }
+
+        /// Simulates new isolate joining the message channel. Must be called
+        /// on the thread that runs the RunLoop driving the test; All replies
+        /// and handler invocations for this isolate are delivered there.
+        ///
+        /// The isolate exits when the returned [`MockIsolate`] is dropped.
+        pub fn join_isolate(self: &Arc<Self>) -> MockIsolate {
+            let id = IsolateId(self.next_isolate_id.fetch_add(1, Ordering::SeqCst));
+            let isolate = Arc::new(Isolate {
+                id,
+                sender: RunLoop::current().new_sender(),
+                state: Mutex::new(IsolateState {
+                    next_reply_id: 1,
+                    pending_replies: HashMap::new(),
+                    handlers: HashMap::new(),
+                    messages: Vec::new(),
+                }),
+            });
+            self.isolates.lock().unwrap().insert(id, isolate.clone());
+            self.delegate.lock().unwrap().on_isolate_joined(id);
+            MockIsolate {
+                transport: self.clone(),
+                isolate,
+            }
+        }
+
+        fn deliver(&self, isolate_id: IsolateId, message: Value) {
+            self.delegate
+                .lock()
+                .unwrap()
+                .on_message(isolate_id, message);
+        }
+
+        fn handle_message(self: &Arc<Self>, isolate: &Arc<Isolate>, value: Value) -> Option<()> {
+            let value: Vec<Value> = value.try_into().ok()?;
+            let mut iter = value.into_iter();
+            let message: String = iter.next()?.try_into().ok()?;
+            match message.as_str() {
+                "send_message" => {
+                    let channel: String = iter.next()?.try_into().ok()?;
+                    let reply_id: i64 = iter.next()?.try_into().ok()?;
+                    let message = iter.next()?;
+                    self.handle_incoming(isolate, channel, Some(reply_id), message);
+                }
+                "post_message" => {
+                    let channel: String = iter.next()?.try_into().ok()?;
+                    let message = iter.next()?;
+                    self.handle_incoming(isolate, channel, None, message);
+                }
+                "reply" => {
+                    let reply_id: i64 = iter.next()?.try_into().ok()?;
+                    let value = iter.next()?;
+                    isolate.complete(reply_id, Ok(value));
+                }
+                "reply_no_channel" => {
+                    let reply_id: i64 = iter.next()?.try_into().ok()?;
+                    let channel: String = iter.next()?.try_into().ok()?;
+                    isolate.complete(reply_id, Err(SendMessageError::ChannelNotFound { channel }));
+                }
+                "reply_malformed_message" => {
+                    let reply_id: i64 = iter.next()?.try_into().ok()?;
+                    isolate.complete(reply_id, Err(SendMessageError::MessageRefused));
+                }
+                _ => return None,
+            }
+            Some(())
+        }
+
+        fn handle_incoming(
+            self: &Arc<Self>,
+            isolate: &Arc<Isolate>,
+            channel: String,
+            reply_id: Option<i64>,
+            message: Value,
+        ) {
+            let mut state = isolate.state.lock().unwrap();
+            if state.handlers.contains_key(&channel) {
+                // This may be called while MessageChannel is locked, the handler
+                // must be invoked later.
+                let transport = self.clone();
+                let isolate_clone = isolate.clone();
+                isolate.sender.send(move || {
+                    let handler = {
+                        let state = isolate_clone.state.lock().unwrap();
+                        state
+                            .handlers
+                            .get(&channel)
+                            .and_then(|h| h.get_ref().ok().cloned())
+                    };
+                    if let Some(handler) = handler {
+                        let result = handler(message);
+                        if let Some(reply_id) = reply_id {
+                            let reply =
+                                vec![Value::String("reply".into()), reply_id.into(), result];
+                            transport.deliver(isolate_clone.id, reply.into());
+                        }
+                    }
+                });
+            } else {
+                state.messages.push(match reply_id {
+                    Some(reply_id) => MockMessage::SendMessage {
+                        channel,
+                        reply_id,
+                        message,
+                    },
+                    None => MockMessage::PostMessage { channel, message },
+                });
+            }
+        }
+    }
+
+    impl Isolate {
+        fn complete(&self, reply_id: i64, result: Result<Value, SendMessageError>) {
+            let reply = self.state.lock().unwrap().pending_replies.remove(&reply_id);
+            if let Some(mut reply) = reply {
+                self.sender.send(move || {
+                    reply.take().unwrap().complete(result);
+                });
+            }
+        }
+    }
+
+    impl MessageTransport for MockMessageTransport {
+        fn new(delegate: Arc<Mutex<dyn MessageTransportDelegate + Send>>) -> Arc<Self> {
+            let res = Arc::new(Self {
+                delegate,
+                isolates: Mutex::new(HashMap::new()),
+                next_isolate_id: AtomicI64::new(1),
+            });
+            MOCK_MESSAGE_TRANSPORT
+                .set(res.clone())
+                .expect("MockMessageTransport already initialized");
+            res
+        }
+
+        fn send(&self, isolate_id: IsolateId, value: Value) -> bool {
+            let isolate = self.isolates.lock().unwrap().get(&isolate_id).cloned();
+            match isolate {
+                Some(isolate) => {
+                    let transport = MOCK_MESSAGE_TRANSPORT.get().unwrap();
+                    let res = transport.handle_message(&isolate, value).is_some();
+                    if !res {
+                        error!("MockMessageTransport: Malformed message");
+                    }
+                    res
+                }
+                None => false,
+            }
+        }
+    }
+
+    /// Simulated Dart isolate connected to [`MessageChannel`].
+    pub struct MockIsolate {
+        transport: Arc<MockMessageTransport>,
+        isolate: Arc<Isolate>,
+    }
+
+    impl MockIsolate {
+        pub fn id(&self) -> IsolateId {
+            self.isolate.id
+        }
+
+        /// Sends message to Rust channel and returns future that completes
+        /// with the reply.
+        pub fn send_message(
+            &self,
+            channel: &str,
+            message: Value,
+        ) -> impl Future<Output = Result<Value, SendMessageError>> {
+            self.send_cancellable_message(channel, message).1
+        }
+
+        /// Same as [`MockIsolate::send_message`], but also returns the reply
+        /// id that can be passed to [`MockIsolate::cancel_message`].
+        pub fn send_cancellable_message(
+            &self,
+            channel: &str,
+            message: Value,
+        ) -> (i64, impl Future<Output = Result<Value, SendMessageError>>) {
+            let (future, completer) = FutureCompleter::new();
+            let reply_id = self.next_reply_id();
+            self.isolate.state.lock().unwrap().pending_replies.insert(
+                reply_id,
+                Capsule::new_with_sender(completer, self.isolate.sender.clone()),
+            );
+            self.deliver_message(reply_id, channel, message);
+            (reply_id, future)
+        }
+
+        /// Same as [`MockIsolate::send_message`], but `fields` are sent after
+        /// the reply id instead of channel name and message. Allows testing
+        /// how malformed messages are handled.
+        pub fn send_raw_message(
+            &self,
+            fields: Vec<Value>,
+        ) -> impl Future<Output = Result<Value, SendMessageError>> {
+            let (future, completer) = FutureCompleter::new();
+            let reply_id = self.next_reply_id();
+            self.isolate.state.lock().unwrap().pending_replies.insert(
+                reply_id,
+                Capsule::new_with_sender(completer, self.isolate.sender.clone()),
+            );
+            let mut message = vec![Value::String("message".into()), reply_id.into()];
+            message.extend(fields);
+            self.transport.deliver(self.isolate.id, message.into());
+            future
+        }
+
+        /// Cancels message sent with [`MockIsolate::send_cancellable_message`].
+        /// The future for the message will never complete.
+        pub fn cancel_message(&self, reply_id: i64) {
+            self.isolate
+                .state
+                .lock()
+                .unwrap()
+                .pending_replies
+                .remove(&reply_id);
+            let message = vec![Value::String("cancel_message".into()), reply_id.into()];
+            self.transport.deliver(self.isolate.id, message.into());
+        }
+
+        /// Sends message to Rust channel ignoring the reply.
+        pub fn post_message(&self, channel: &str, message: Value) {
+            let reply_id = self.next_reply_id();
+            self.deliver_message(reply_id, channel, message);
+        }
+
+        fn next_reply_id(&self) -> i64 {
+            let mut state = self.isolate.state.lock().unwrap();
+            let reply_id = state.next_reply_id;
+            state.next_reply_id += 1;
+            reply_id
+        }
+
+        fn deliver_message(&self, reply_id: i64, channel: &str, message: Value) {
+            let message = vec![
+                Value::String("message".into()),
+                reply_id.into(),
+                channel.into(),
+                message,
+            ];
+            self.transport.deliver(self.isolate.id, message.into());
+        }
+
+        /// Invokes method on Rust method channel (i.e. one registered through
+        /// [`crate::MethodHandler`] or [`crate::AsyncMethodHandler`]).
+        pub async fn invoke_method<V: Into<Value>>(
+            &self,
+            channel: &str,
+            method: &str,
+            args: V,
+        ) -> Result<Value, MethodCallError> {
+            let call: Value = vec![Value::String(method.into()), args.into()].into();
+            match self.send_message(channel, call).await {
+                Ok(value) => unpack_reply(value),
+                Err(err) => Err(MethodCallError::SendError(err)),
+            }
+        }
+
+        /// Registers Dart side handler for given channel. The handler will
+        /// receive all messages sent or posted from Rust to this channel.
+        /// For messages sent with reply the returned value is used as reply.
+        pub fn set_handler<F>(&self, channel: &str, handler: F)
+        where
+            F: Fn(Value) -> Value + 'static,
+        {
+            let handler: Rc<dyn Fn(Value) -> Value> = Rc::new(handler);
+            let mut state = self.isolate.state.lock().unwrap();
+            state.handlers.insert(
+                channel.into(),
+                Capsule::new_with_sender(handler, self.isolate.sender.clone()),
+            );
+        }
+
+        /// Sets handler that replies to method calls from
+        /// [`crate::MethodInvoker`] and [`crate::AsyncMethodInvoker`].
+        pub fn set_method_handler<F>(&self, channel: &str, handler: F)
+        where
+            F: Fn(&str, Value) -> Result<Value, crate::PlatformError> + 'static,
+        {
+            self.set_handler(channel, move |value| {
+                let call: Vec<Value> = value.try_into().expect("Malformed method call");
+                let mut iter = call.into_iter();
+                let method: String = iter
+                    .next()
+                    .and_then(|m| m.try_into().ok())
+                    .expect("Malformed method call");
+                let args = iter.next().unwrap_or_default();
+                match handler(&method, args) {
+                    Ok(value) => Value::List(vec!["ok".into(), value]),
+                    Err(err) => Value::List(vec![
+                        "err".into(),
+                        err.code.into(),
+                        err.message.into(),
+                        err.detail,
+                    ]),
+                }
+            });
+        }
+
+        /// Returns messages sent from Rust that were not handled by handler
+        /// registered with [`MockIsolate::set_handler`].
+        pub fn take_messages(&self) -> Vec<MockMessage> {
+            std::mem::take(&mut self.isolate.state.lock().unwrap().messages)
+        }
+
+        /// Replies to message received as [`MockMessage::SendMessage`].
+        pub fn reply(&self, reply_id: i64, value: Value) {
+            let reply = vec![Value::String("reply".into()), reply_id.into(), value];
+            self.transport.deliver(self.isolate.id, reply.into());
+        }
+
+        /// Simulates isolate shutdown. Same as dropping the isolate.
+        pub fn exit(self) {}
+    }
+
+    impl Drop for MockIsolate {
+        fn drop(&mut self) {
+            let id = self.isolate.id;
+            self.transport.isolates.lock().unwrap().remove(&id);
+            let pending: Vec<_> = {
+                let mut state = self.isolate.state.lock().unwrap();
+                state.pending_replies.drain().collect()
+            };
+            for (_, mut reply) in pending {
+                if let Ok(completer) = reply.take() {
+                    completer.complete(Err(SendMessageError::IsolateShutDown));
+                }
+            }
+            if let Ok(mut delegate) = self.transport.delegate.lock() {
+                delegate.on_isolate_exited(id);
+            }
+        }
+    }
+
+    /// Runs current thread RunLoop until the future completes.
+    pub fn run_until_complete<T: 'static>(future: impl Future<Output = T> + 'static) -> T {
+        let run_loop = RunLoop::current();
+        let result = Rc::new(std::cell::RefCell::new(None));
+        let result_clone = result.clone();
+        run_loop.spawn(async move {
+            result_clone.replace(Some(future.await));
+            RunLoop::current().stop();
+        });
+        run_loop.run();
+        let res = result.borrow_mut().take();
+        res.expect("RunLoop stopped before future completed")
+    }
 }
@@ -1,12 +1,14 @@
-use core::panic;
 use std::{
     fmt::Display,
     rc::{Rc, Weak},
+    time::Duration,
 };
 
-use crate::{value::Value, MessageChannel, TryFromError};
+use log::error;
 
-use super::{IsolateId, MessageChannelDelegate, SendMessageError};
+use crate::{value::Value, CancellationToken, MessageChannel, TryFromError};
+
+use super::{IsolateId, MessageChannelDelegate, PendingReplyId, SendMessageError};
 
 #[derive(Debug)]
 pub enum MethodCallError {
@@ -36,10 +38,15 @@ pub struct PlatformError {
 
 impl From<TryFromError> for PlatformError {
     fn from(err: TryFromError) -> Self {
+        // Path of the value that failed to convert, i.e. `items[3].price`.
+        let detail = match err.path() {
+            Some(path) => Value::String(path.to_string()),
+            None => Value::Null,
+        };
         PlatformError {
             code: "try_from_error".into(),
             message: Some(err.to_string()),
-            detail: Value::Null,
+            detail,
         }
     }
 }
@@ -57,10 +64,24 @@ impl Display for PlatformError {
 impl std::error::Error for PlatformError {}
 
 #[derive(Debug)]
+#[non_exhaustive]
 pub struct MethodCall {
     pub method: String,
     pub args: Value,
     pub isolate: IsolateId,
+    /// Cancelled when Dart cancels the call or the isolate exits.
+    pub cancellation_token: CancellationToken,
+}
+
+impl MethodCall {
+    pub fn new(method: impl Into<String>, args: Value, isolate: IsolateId) -> Self {
+        Self {
+            method: method.into(),
+            args,
+            isolate,
+            cancellation_token: CancellationToken::new(),
+        }
+    }
 }
 
 pub trait MethodHandler: Sized + 'static {
@@ -123,17 +144,63 @@ impl MethodInvoker {
     ) where
         F: FnOnce(Result<Value, MethodCallError>) + 'static,
     {
-        let args: Value = args.into();
+        self.call_method_impl(target_isolate, method, args.into(), None, reply);
+    }
+
+    /// Calls method on target isolate. The `reply` will be called with
+    /// [`SendMessageError::Timeout`] if the isolate doesn't respond within
+    /// given duration. Returned identifier can be used to cancel the pending
+    /// reply through [`MessageChannel::cancel_reply`].
+    pub fn call_method_with_timeout<V: Into<Value>, F>(
+        &self,
+        target_isolate: IsolateId,
+        method: &str,
+        args: V,
+        timeout: Duration,
+        reply: F,
+    ) -> Option<PendingReplyId>
+    where
+        F: FnOnce(Result<Value, MethodCallError>) + 'static,
+    {
+        self.call_method_impl(target_isolate, method, args.into(), Some(timeout), reply)
+    }
+
+    fn call_method_impl<F>(
+        &self,
+        target_isolate: IsolateId,
+        method: &str,
+        args: Value,
+        timeout: Option<Duration>,
+        reply: F,
+    ) -> Option<PendingReplyId>
+    where
+        F: FnOnce(Result<Value, MethodCallError>) + 'static,
+    {
         let call: Value = vec![Value::String(method.into()), args].into();
-        MessageChannel::get().send_message(target_isolate, &self.channel_name, call, move |res| {
-            match res {
-                Ok(value) => {
-                    let result = unpack_result(value).expect("Malformed message");
-                    reply(result);
-                }
-                Err(err) => reply(Err(MethodCallError::SendError(err))),
+        let channel = MessageChannel::get();
+        let track = channel
+            .metrics_registry()
+            .track_outgoing_call(&self.channel_name, method);
+        let reply = move |res| {
+            let result = match res {
+                Ok(value) => unpack_reply(value),
+                Err(err) => Err(MethodCallError::SendError(err)),
+            };
+            track(&result);
+            reply(result);
+        };
+        match timeout {
+            Some(timeout) => channel.send_message_with_timeout(
+                target_isolate,
+                &self.channel_name,
+                call,
+                timeout,
+                reply,
+            ),
+            None => {
+                channel.send_message_cancellable(target_isolate, &self.channel_name, call, reply)
             }
-        });
+        }
     }
 }
 
@@ -228,11 +295,24 @@ impl<T: MethodHandler> MessageChannelDelegate for RegisteredMethodHandlerInner<T
         message: Value,
         reply: Box<dyn FnOnce(Value) -> bool + Send>,
     ) {
-        if let Some(call) = unpack_method_call(message, isolate) {
+        self.on_message_cancellable(isolate, message, reply, CancellationToken::new())
+    }
+
+    fn on_message_cancellable(
+        &self,
+        isolate: IsolateId,
+        message: Value,
+        reply: Box<dyn FnOnce(Value) -> bool + Send>,
+        cancellation_token: CancellationToken,
+    ) {
+        if let Some(call) = unpack_method_call(message, isolate, cancellation_token) {
+            let reply = MessageChannel::get()
+                .metrics_registry()
+                .track_incoming_call(&self.channel, &call.method, reply);
             let reply = MethodCallReply { reply };
             self.handler.on_method_call(call, reply);
         } else {
-            panic!("malformed method call message");
+            reply_malformed_call(&self.channel, reply);
         }
     }
 
@@ -241,6 +321,29 @@ impl<T: MethodHandler> MessageChannelDelegate for RegisteredMethodHandlerInner<T
     }
 }
 
+/// Same as [`unpack_result`], but malformed reply is reported as
+/// `invalid_reply` platform error.
+pub(crate) fn unpack_reply(value: Value) -> Result<Value, MethodCallError> {
+    unpack_result(value).unwrap_or_else(|| {
+        Err(MethodCallError::PlatformError(PlatformError {
+            code: "invalid_reply".into(),
+            message: Some("method handler sent malformed reply".into()),
+            detail: Value::Null,
+        }))
+    })
+}
+
+/// Replies with `malformed_message` platform error to message that is not
+/// a `[method, arguments]` list.
+pub(crate) fn reply_malformed_call(channel: &str, reply: Box<dyn FnOnce(Value) -> bool + Send>) {
+    error!("Malformed method call message on channel {channel}");
+    MethodCallReply { reply }.send_error(
+        "malformed_message".into(),
+        Some("method call must be [method, arguments] list".into()),
+        Value::Null,
+    );
+}
+
 pub(crate) fn unpack_result(value: Value) -> Option<Result<Value, MethodCallError>> {
     let vec: Vec<Value> = value.try_into().ok()?;
     let mut iter = vec.into_iter();
@@ -264,12 +367,17 @@ pub(crate) fn unpack_result(value: Value) -> Option<Result<Value, MethodCallErro
     }
 }
 
-pub(crate) fn unpack_method_call(value: Value, isolate: IsolateId) -> Option<MethodCall> {
+pub(crate) fn unpack_method_call(
+    value: Value,
+    isolate: IsolateId,
+    cancellation_token: CancellationToken,
+) -> Option<MethodCall> {
     let vec: Vec<Value> = value.try_into().ok()?;
     let mut iter = vec.into_iter();
     Some(MethodCall {
         method: iter.next()?.try_into().ok()?,
         args: iter.next()?,
         isolate,
+        cancellation_token,
     })
 }
@@ -0,0 +1,77 @@
+use std::{collections::HashMap, future::Future};
+
+use async_trait::async_trait;
+use futures::future::LocalBoxFuture;
+
+use crate::{
+    AsyncMethodHandler, IntoPlatformResult, MethodCall, PlatformError, PlatformResult,
+    TryFromError, Value,
+};
+
+type Route = Box<dyn Fn(Value) -> LocalBoxFuture<'static, PlatformResult>>;
+
+/// Method handler that dispatches calls by method name. Arguments are
+/// converted to handler argument type and handler result is converted
+/// to [`Value`].
+///
+/// ```no_run
+/// use irondash_message_channel::{AsyncMethodHandler, MethodRouter};
+///
+/// let _handler = MethodRouter::new()
+///     .method("add", |args: Vec<f64>| async move { args.iter().sum::<f64>() })
+///     .register("addition_channel");
+/// ```
+#[derive(Default)]
+pub struct MethodRouter {
+    routes: HashMap<String, Route>,
+}
+
+impl MethodRouter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds handler for given method. Argument type can be anything that
+    /// implements `TryFrom<Value>`; If the conversion fails the call
+    /// fails with [`TryFromError`] converted to [`PlatformError`]. Handler
+    /// can return any `Into<Value>` or `Result<Into<Value>, Into<PlatformError>>`.
+    ///
+    /// Adding handler for the same method twice replaces the previous handler.
+    pub fn method<A, E, R, Fut, F>(mut self, method: &str, handler: F) -> Self
+    where
+        A: TryFrom<Value, Error = E> + 'static,
+        E: Into<TryFromError>,
+        R: IntoPlatformResult,
+        Fut: Future<Output = R> + 'static,
+        F: Fn(A) -> Fut + 'static,
+    {
+        let route = move |args: Value| -> LocalBoxFuture<'static, PlatformResult> {
+            match A::try_from(args) {
+                Ok(args) => {
+                    let future = handler(args);
+                    Box::pin(async move { future.await.into_platform_result() })
+                }
+                Err(err) => {
+                    let err: TryFromError = err.into();
+                    Box::pin(futures::future::ready(Err(err.into())))
+                }
+            }
+        };
+        self.routes.insert(method.into(), Box::new(route));
+        self
+    }
+}
+
+#[async_trait(?Send)]
+impl AsyncMethodHandler for MethodRouter {
+    async fn on_method_call(&self, call: MethodCall) -> PlatformResult {
+        match self.routes.get(&call.method) {
+            Some(route) => route(call.args).await,
+            None => Err(PlatformError {
+                code: "invalid_method".into(),
+                message: Some(format!("Unknown Method: {}", call.method)),
+                detail: Value::Null,
+            }),
+        }
+    }
+}
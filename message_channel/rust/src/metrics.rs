@@ -0,0 +1,335 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use crate::{Frame, FrameDirection, MethodCallError, SendMessageError, Value};
+
+/// Upper bounds of latency histogram buckets. Last bucket of
+/// [`LatencyHistogram::buckets`] counts everything above the last bound.
+pub const LATENCY_BUCKETS: [Duration; 10] = [
+    Duration::from_millis(1),
+    Duration::from_millis(2),
+    Duration::from_millis(5),
+    Duration::from_millis(10),
+    Duration::from_millis(20),
+    Duration::from_millis(50),
+    Duration::from_millis(100),
+    Duration::from_millis(500),
+    Duration::from_secs(1),
+    Duration::from_secs(5),
+];
+
+/// Channel and method names come from Dart, so number of distinct keys is
+/// limited. Anything over the limit is aggregated under [`OTHER_KEY`].
+const MAX_CHANNELS: usize = 64;
+const MAX_METHODS: usize = 64;
+
+/// Key under which metrics for channels or methods over the limit are
+/// aggregated.
+pub const OTHER_KEY: &str = "<other>";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LatencyHistogram {
+    pub count: u64,
+    pub total: Duration,
+    /// Number of samples per bucket; see [`LATENCY_BUCKETS`].
+    pub buckets: [u64; LATENCY_BUCKETS.len() + 1],
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            total: Duration::ZERO,
+            buckets: [0; LATENCY_BUCKETS.len() + 1],
+        }
+    }
+}
+
+impl LatencyHistogram {
+    fn record(&mut self, latency: Duration) {
+        self.count += 1;
+        self.total += latency;
+        let bucket = LATENCY_BUCKETS
+            .iter()
+            .position(|bound| latency <= *bound)
+            .unwrap_or(LATENCY_BUCKETS.len());
+        self.buckets[bucket] += 1;
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CallMetrics {
+    /// Rust to Dart calls made through method invokers.
+    pub outgoing_calls: u64,
+    /// Dart to Rust calls received by method handlers.
+    pub incoming_calls: u64,
+    /// Outgoing calls that Dart replied to with an error.
+    pub outgoing_platform_errors: u64,
+    /// Incoming calls that the handler replied to with an error.
+    pub incoming_platform_errors: u64,
+    /// Outgoing calls that failed with [`SendMessageError`], keyed by the
+    /// variant name.
+    pub send_errors: HashMap<String, u64>,
+    /// Time from sending the call to receiving the reply.
+    pub outgoing_latency: LatencyHistogram,
+    /// Time from receiving the call to handler sending the reply.
+    pub handler_latency: LatencyHistogram,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChannelMetrics {
+    /// Estimated size of all payloads sent to Dart on this channel. This is
+    /// not the serialized size, which is not known for messages posted as
+    /// `Dart_CObject`; See [`Frame::payload_size`].
+    pub approximate_bytes_sent: u64,
+    /// Estimated size of all payloads received from Dart on this channel.
+    pub approximate_bytes_received: u64,
+    /// Method call metrics aggregated over all methods.
+    pub total: CallMetrics,
+    /// Metrics per method. Methods over the limit are aggregated under
+    /// [`OTHER_KEY`].
+    pub methods: HashMap<String, CallMetrics>,
+}
+
+/// Snapshot of message channel metrics returned by
+/// [`MessageChannelBase::metrics`](crate::MessageChannelBase::metrics).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MetricsSnapshot {
+    /// Metrics per channel. Channels over the limit are aggregated under
+    /// [`OTHER_KEY`].
+    pub channels: HashMap<String, ChannelMetrics>,
+}
+
+#[derive(Default)]
+pub(crate) struct MetricsRegistry {
+    enabled: AtomicBool,
+    channels: Mutex<HashMap<String, ChannelMetrics>>,
+}
+
+/// Returns entry for given key, or for [`OTHER_KEY`] if the map is full.
+fn bounded_entry<'a, T: Default>(
+    map: &'a mut HashMap<String, T>,
+    key: &str,
+    max: usize,
+) -> &'a mut T {
+    let key = if map.contains_key(key) || map.len() < max {
+        key
+    } else {
+        OTHER_KEY
+    };
+    map.entry(key.into()).or_default()
+}
+
+fn send_error_name(error: &SendMessageError) -> &'static str {
+    match error {
+        SendMessageError::InvalidIsolate => "InvalidIsolate",
+        SendMessageError::MessageRefused => "MessageRefused",
+        SendMessageError::IsolateShutDown => "IsolateShutDown",
+        SendMessageError::Timeout => "Timeout",
+        SendMessageError::ChannelNotFound { .. } => "ChannelNotFound",
+        SendMessageError::HandlerNotRegistered { .. } => "HandlerNotRegistered",
+    }
+}
+
+fn is_error_reply(reply: &Value) -> bool {
+    matches!(reply, Value::List(list) if list.first().and_then(Value::as_str) == Some("err"))
+}
+
+impl MetricsRegistry {
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        MetricsSnapshot {
+            channels: self.channels.lock().unwrap().clone(),
+        }
+    }
+
+    pub fn record_frame(&self, frame: &Frame) {
+        if !self.is_enabled() {
+            return;
+        }
+        if let Some(channel) = frame.channel {
+            let mut channels = self.channels.lock().unwrap();
+            let metrics = bounded_entry(&mut channels, channel, MAX_CHANNELS);
+            let size = frame.payload_size as u64;
+            match frame.direction {
+                FrameDirection::Outgoing => metrics.approximate_bytes_sent += size,
+                FrameDirection::Incoming => metrics.approximate_bytes_received += size,
+                FrameDirection::Local => {}
+            }
+        }
+    }
+
+    fn update_call<F: Fn(&mut CallMetrics)>(&self, channel: &str, method: &str, f: F) {
+        let mut channels = self.channels.lock().unwrap();
+        let metrics = bounded_entry(&mut channels, channel, MAX_CHANNELS);
+        f(&mut metrics.total);
+        f(bounded_entry(&mut metrics.methods, method, MAX_METHODS));
+    }
+
+    /// Records outgoing method call. Returns callback to be invoked with the result.
+    pub fn track_outgoing_call(
+        &'static self,
+        channel: &str,
+        method: &str,
+    ) -> impl FnOnce(&Result<Value, MethodCallError>) {
+        let enabled = self.is_enabled();
+        if enabled {
+            self.update_call(channel, method, |m| m.outgoing_calls += 1);
+        }
+        let channel = channel.to_owned();
+        let method = method.to_owned();
+        let started = Instant::now();
+        move |result| {
+            if !enabled {
+                return;
+            }
+            let latency = started.elapsed();
+            self.update_call(&channel, &method, |m| {
+                match result {
+                    Ok(_) | Err(MethodCallError::ConversionError(_)) => {}
+                    Err(MethodCallError::PlatformError(_)) => m.outgoing_platform_errors += 1,
+                    Err(MethodCallError::SendError(err)) => {
+                        *m.send_errors
+                            .entry(send_error_name(err).into())
+                            .or_default() += 1;
+                        return;
+                    }
+                }
+                m.outgoing_latency.record(latency);
+            });
+        }
+    }
+
+    /// Records incoming method call and wraps the reply so that handler
+    /// latency and errors are recorded when the reply is sent.
+    pub fn track_incoming_call(
+        &'static self,
+        channel: &str,
+        method: &str,
+        reply: Box<dyn FnOnce(Value) -> bool + Send>,
+    ) -> Box<dyn FnOnce(Value) -> bool + Send> {
+        if !self.is_enabled() {
+            return reply;
+        }
+        self.update_call(channel, method, |m| m.incoming_calls += 1);
+        let channel = channel.to_owned();
+        let method = method.to_owned();
+        let started = Instant::now();
+        Box::new(move |value| {
+            let latency = started.elapsed();
+            let is_error = is_error_reply(&value);
+            self.update_call(&channel, &method, |m| {
+                if is_error {
+                    m.incoming_platform_errors += 1;
+                }
+                m.handler_latency.record(latency);
+            });
+            reply(value)
+        })
+    }
+}
+
+fn count(value: u64) -> Value {
+    Value::I64(value.try_into().unwrap_or(i64::MAX))
+}
+
+fn micros(duration: Duration) -> Value {
+    count(duration.as_micros().try_into().unwrap_or(u64::MAX))
+}
+
+impl From<LatencyHistogram> for Value {
+    fn from(histogram: LatencyHistogram) -> Self {
+        let buckets: Vec<i64> = histogram
+            .buckets
+            .iter()
+            .map(|b| (*b).try_into().unwrap_or(i64::MAX))
+            .collect();
+        Value::Map(
+            vec![
+                ("count".into(), count(histogram.count)),
+                ("total_us".into(), micros(histogram.total)),
+                ("buckets".into(), buckets.into()),
+            ]
+            .into(),
+        )
+    }
+}
+
+impl From<CallMetrics> for Value {
+    fn from(metrics: CallMetrics) -> Self {
+        let send_errors: Vec<(Value, Value)> = metrics
+            .send_errors
+            .into_iter()
+            .map(|(k, v)| (k.into(), count(v)))
+            .collect();
+        Value::Map(
+            vec![
+                ("outgoing_calls".into(), count(metrics.outgoing_calls)),
+                ("incoming_calls".into(), count(metrics.incoming_calls)),
+                (
+                    "outgoing_platform_errors".into(),
+                    count(metrics.outgoing_platform_errors),
+                ),
+                (
+                    "incoming_platform_errors".into(),
+                    count(metrics.incoming_platform_errors),
+                ),
+                ("send_errors".into(), send_errors.into()),
+                ("outgoing_latency".into(), metrics.outgoing_latency.into()),
+                ("handler_latency".into(), metrics.handler_latency.into()),
+            ]
+            .into(),
+        )
+    }
+}
+
+impl From<ChannelMetrics> for Value {
+    fn from(metrics: ChannelMetrics) -> Self {
+        Value::Map(
+            vec![
+                (
+                    "approximate_bytes_sent".into(),
+                    count(metrics.approximate_bytes_sent),
+                ),
+                (
+                    "approximate_bytes_received".into(),
+                    count(metrics.approximate_bytes_received),
+                ),
+                ("total".into(), metrics.total.into()),
+                ("methods".into(), metrics.methods.into()),
+            ]
+            .into(),
+        )
+    }
+}
+
+/// The value also contains `latency_buckets_us` with upper bounds of
+/// histogram buckets in microseconds.
+impl From<MetricsSnapshot> for Value {
+    fn from(snapshot: MetricsSnapshot) -> Self {
+        let bounds: Vec<i64> = LATENCY_BUCKETS
+            .iter()
+            .map(|b| b.as_micros() as i64)
+            .collect();
+        Value::Map(
+            vec![
+                ("latency_buckets_us".into(), bounds.into()),
+                ("channels".into(), snapshot.channels.into()),
+            ]
+            .into(),
+        )
+    }
+}
@@ -37,6 +37,10 @@ pub(super) unsafe extern "C" fn allocate_vec_i64(size: usize) -> *mut i64 {
     allocate_vec::<i64>(size)
 }
 
+pub(super) unsafe extern "C" fn allocate_vec_u64(size: usize) -> *mut u64 {
+    allocate_vec::<u64>(size)
+}
+
 pub(super) unsafe extern "C" fn allocate_vec_f32(size: usize) -> *mut f32 {
     allocate_vec::<f32>(size)
 }
@@ -73,6 +77,10 @@ pub(super) unsafe extern "C" fn free_vec_i64(data: *mut i64, len: usize) {
     let _ = Vec::from_raw_parts(data, len, len);
 }
 
+pub(super) unsafe extern "C" fn free_vec_u64(data: *mut u64, len: usize) {
+    let _ = Vec::from_raw_parts(data, len, len);
+}
+
 pub(super) unsafe extern "C" fn free_vec_f32(data: *mut f32, len: usize) {
     let _ = Vec::from_raw_parts(data, len, len);
 }
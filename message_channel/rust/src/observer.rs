@@ -0,0 +1,148 @@
+use std::{collections::HashSet, time::Duration};
+
+use log::trace;
+
+use crate::{IsolateId, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FrameDirection {
+    /// Frame sent from Rust to Dart.
+    Outgoing,
+    /// Frame received from Dart.
+    Incoming,
+    /// Frame produced locally without anything crossing the boundary, i.e.
+    /// expired pending reply.
+    Local,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FrameKind {
+    /// Message that expects a reply.
+    Message,
+    /// Message that doesn't expect a reply.
+    PostMessage,
+    /// Reply to a message.
+    Reply,
+    /// Reply indicating that there is no channel or handler for the message.
+    NoChannel,
+    /// Dart cancelled message that is still being processed.
+    Cancel,
+    /// No reply was received within the timeout.
+    Timeout,
+    /// Pending reply failed because the target isolate exited or refused
+    /// the message.
+    IsolateExit,
+}
+
+/// Information about single frame passed to [`MessageChannelObserver`].
+#[derive(Debug, Clone)]
+pub struct Frame<'a> {
+    pub direction: FrameDirection,
+    pub kind: FrameKind,
+    pub isolate: IsolateId,
+    /// Channel name. May be `None` for frames where the channel is not known,
+    /// i.e. cancellation of a message that has already been replied to.
+    pub channel: Option<&'a str>,
+    pub reply_id: Option<i64>,
+    /// Approximate size of the payload in bytes.
+    pub payload_size: usize,
+    /// For replies, time elapsed since the message was sent or received.
+    pub latency: Option<Duration>,
+}
+
+/// Observer notified about all traffic going through the
+/// [`MessageChannel`](crate::MessageChannel). Set with
+/// [`MessageChannelBase::set_observer`](crate::MessageChannelBase::set_observer).
+///
+/// The observer is called synchronously from whichever thread the frame
+/// is processed on, possibly while message channel internal lock is held.
+/// It must not call back into the message channel.
+pub trait MessageChannelObserver: Send + Sync {
+    fn on_frame(&self, frame: &Frame);
+}
+
+/// Observer that logs all frames through `log` at trace level.
+#[derive(Debug, Default)]
+pub struct LogObserver {
+    include: Option<HashSet<String>>,
+    exclude: HashSet<String>,
+}
+
+impl LogObserver {
+    /// Creates observer that logs frames for all channels.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only log frames for channels explicitly included. Can be called
+    /// multiple times.
+    pub fn include_channel(mut self, channel: &str) -> Self {
+        self.include
+            .get_or_insert_with(HashSet::new)
+            .insert(channel.into());
+        self
+    }
+
+    /// Do not log frames for given channel.
+    pub fn exclude_channel(mut self, channel: &str) -> Self {
+        self.exclude.insert(channel.into());
+        self
+    }
+
+    fn should_log(&self, channel: Option<&str>) -> bool {
+        match channel {
+            Some(channel) => {
+                self.include.as_ref().is_none_or(|i| i.contains(channel))
+                    && !self.exclude.contains(channel)
+            }
+            None => self.include.is_none(),
+        }
+    }
+}
+
+impl MessageChannelObserver for LogObserver {
+    fn on_frame(&self, frame: &Frame) {
+        if !self.should_log(frame.channel) {
+            return;
+        }
+        trace!(
+            "MessageChannel: {:?} {:?} isolate: {}, channel: {}, reply_id: {}, size: {}, latency: {}",
+            frame.direction,
+            frame.kind,
+            frame.isolate.0,
+            frame.channel.unwrap_or("-"),
+            frame.reply_id.map_or("-".into(), |id| id.to_string()),
+            frame.payload_size,
+            frame.latency.map_or("-".into(), |l| format!("{l:?}")),
+        );
+    }
+}
+
+/// Returns approximate size of value in bytes without serializing it.
+pub(crate) fn approximate_size(value: &Value) -> usize {
+    fn slice_size<T>(slice: &[T]) -> usize {
+        std::mem::size_of_val(slice)
+    }
+    match value {
+        Value::Null | Value::Bool(_) => 1,
+        Value::I64(_) | Value::F64(_) => 8,
+        Value::String(v) => v.len(),
+        Value::I8List(v) => slice_size(v),
+        Value::U8List(v) => slice_size(v),
+        Value::I16List(v) => slice_size(v),
+        Value::U16List(v) => slice_size(v),
+        Value::I32List(v) => slice_size(v),
+        Value::U32List(v) => slice_size(v),
+        Value::I64List(v) => slice_size(v),
+        Value::U64List(v) => slice_size(v),
+        Value::F32List(v) => slice_size(v),
+        Value::F64List(v) => slice_size(v),
+        Value::External(v) => v.len(),
+        Value::List(v) => v.iter().map(approximate_size).sum(),
+        Value::Map(v) => v
+            .iter()
+            .map(|(k, v)| approximate_size(k) + approximate_size(v))
+            .sum(),
+        Value::Dart(_) | Value::FinalizableHandle(_) => 8,
+    }
+}
@@ -0,0 +1,285 @@
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+};
+
+use crate::Value;
+
+/// Describes how a Rust type is represented in [`Value`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValueType {
+    /// Arbitrary value.
+    Any,
+    Null,
+    Bool,
+    Int,
+    Double,
+    String,
+    Int8List,
+    Uint8List,
+    Int16List,
+    Uint16List,
+    Int32List,
+    Uint32List,
+    Int64List,
+    Uint64List,
+    Float32List,
+    Float64List,
+    List(Box<ValueType>),
+    Map(Box<ValueType>, Box<ValueType>),
+    Optional(Box<ValueType>),
+    /// Type described by [`TypeSchema`] with given name.
+    Named(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldSchema {
+    /// Name of the Rust field.
+    pub name: String,
+    /// Map key used for the field, after applying `rename` and `rename_all`.
+    pub key: String,
+    pub ty: ValueType,
+    pub skip_if_empty: bool,
+    pub default: bool,
+    /// Entries of the field value are merged into the parent map; `key` is
+    /// not used.
+    pub flatten: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldsSchema {
+    /// Serialized as map. Skipped fields are not included.
+    Named(Vec<FieldSchema>),
+    /// Single field is serialized as the field value itself, multiple fields
+    /// are serialized as list.
+    Unnamed(Vec<ValueType>),
+    Unit,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VariantSchema {
+    /// Name of the Rust variant.
+    pub name: String,
+    /// Variant name used in serialized value, after applying `rename` and
+    /// `rename_all`.
+    pub key: String,
+    pub fields: FieldsSchema,
+    /// Discriminant of fieldless variant in enum with `repr = "int"`.
+    pub discriminant: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeSchema {
+    Struct {
+        name: String,
+        fields: FieldsSchema,
+    },
+    Enum {
+        name: String,
+        /// Value of the `tag` attribute.
+        tag: Option<String>,
+        /// Value of the `content` attribute.
+        content: Option<String>,
+        /// Variants are serialized without tag or variant name.
+        untagged: bool,
+        variants: Vec<VariantSchema>,
+    },
+}
+
+impl TypeSchema {
+    pub fn name(&self) -> &str {
+        match self {
+            TypeSchema::Struct { name, .. } => name,
+            TypeSchema::Enum { name, .. } => name,
+        }
+    }
+}
+
+/// Implemented for types that can describe their [`Value`] representation.
+/// Use `#[derive(ValueSchema)]` for types deriving `IntoValue` and
+/// `TryFromValue`.
+pub trait ValueSchema {
+    fn value_type() -> ValueType;
+
+    /// Type of `Vec<Self>`. Overridden for types that are serialized as
+    /// typed data.
+    fn list_type() -> ValueType {
+        ValueType::List(Box::new(Self::value_type()))
+    }
+
+    /// Registers schema of this type and all types it references.
+    fn register(_registry: &mut SchemaRegistry) {}
+}
+
+/// Collection of type schemas, usually used to generate Dart source
+/// through [`SchemaRegistry::to_dart`].
+#[derive(Debug, Default)]
+pub struct SchemaRegistry {
+    seen: HashSet<String>,
+    types: Vec<TypeSchema>,
+}
+
+impl SchemaRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds type and all types it references to the registry.
+    pub fn add<T: ValueSchema>(&mut self) -> &mut Self {
+        T::register(self);
+        self
+    }
+
+    /// Registered types. Types are ordered so that dependencies come first.
+    pub fn types(&self) -> &[TypeSchema] {
+        &self.types
+    }
+
+    /// Called by [`ValueSchema::register`] implementations before registering
+    /// dependencies. Returns `false` if the type has already been registered.
+    pub fn begin(&mut self, name: &str) -> bool {
+        self.seen.insert(name.into())
+    }
+
+    pub fn insert(&mut self, schema: TypeSchema) {
+        self.seen.insert(schema.name().into());
+        self.types.push(schema);
+    }
+}
+
+macro_rules! impl_value_schema {
+    ($ty:ty, $value_type:expr) => {
+        impl ValueSchema for $ty {
+            fn value_type() -> ValueType {
+                $value_type
+            }
+        }
+    };
+    ($ty:ty, $value_type:expr, $list_type:expr) => {
+        impl ValueSchema for $ty {
+            fn value_type() -> ValueType {
+                $value_type
+            }
+
+            fn list_type() -> ValueType {
+                $list_type
+            }
+        }
+    };
+}
+
+impl_value_schema!(Value, ValueType::Any);
+impl_value_schema!((), ValueType::Null);
+impl_value_schema!(bool, ValueType::Bool);
+impl_value_schema!(String, ValueType::String);
+impl_value_schema!(i8, ValueType::Int, ValueType::Int8List);
+impl_value_schema!(u8, ValueType::Int, ValueType::Uint8List);
+impl_value_schema!(i16, ValueType::Int, ValueType::Int16List);
+impl_value_schema!(u16, ValueType::Int, ValueType::Uint16List);
+impl_value_schema!(i32, ValueType::Int, ValueType::Int32List);
+impl_value_schema!(u32, ValueType::Int, ValueType::Uint32List);
+impl_value_schema!(i64, ValueType::Int, ValueType::Int64List);
+impl_value_schema!(f32, ValueType::Double, ValueType::Float32List);
+impl_value_schema!(f64, ValueType::Double, ValueType::Float64List);
+impl_value_schema!(u64, ValueType::Int, ValueType::Uint64List);
+impl_value_schema!(usize, ValueType::Int);
+impl_value_schema!(isize, ValueType::Int);
+impl_value_schema!(char, ValueType::String);
+impl_value_schema!(Cow<'static, str>, ValueType::String);
+
+impl<T: ValueSchema> ValueSchema for Option<T> {
+    fn value_type() -> ValueType {
+        ValueType::Optional(Box::new(T::value_type()))
+    }
+
+    fn register(registry: &mut SchemaRegistry) {
+        T::register(registry);
+    }
+}
+
+impl<T: ValueSchema> ValueSchema for Vec<T> {
+    fn value_type() -> ValueType {
+        T::list_type()
+    }
+
+    fn register(registry: &mut SchemaRegistry) {
+        T::register(registry);
+    }
+}
+
+impl<K: ValueSchema, V: ValueSchema> ValueSchema for HashMap<K, V> {
+    fn value_type() -> ValueType {
+        ValueType::Map(Box::new(K::value_type()), Box::new(V::value_type()))
+    }
+
+    fn register(registry: &mut SchemaRegistry) {
+        K::register(registry);
+        V::register(registry);
+    }
+}
+
+impl<K: ValueSchema, V: ValueSchema> ValueSchema for BTreeMap<K, V> {
+    fn value_type() -> ValueType {
+        HashMap::<K, V>::value_type()
+    }
+
+    fn register(registry: &mut SchemaRegistry) {
+        HashMap::<K, V>::register(registry);
+    }
+}
+
+impl<T: ValueSchema> ValueSchema for Box<T> {
+    fn value_type() -> ValueType {
+        T::value_type()
+    }
+
+    fn list_type() -> ValueType {
+        T::list_type()
+    }
+
+    fn register(registry: &mut SchemaRegistry) {
+        T::register(registry);
+    }
+}
+
+macro_rules! impl_value_schema_list {
+    ($ty:ty, $($param:tt)*) => {
+        impl<T: ValueSchema, $($param)*> ValueSchema for $ty {
+            fn value_type() -> ValueType {
+                T::list_type()
+            }
+
+            fn register(registry: &mut SchemaRegistry) {
+                T::register(registry);
+            }
+        }
+    };
+}
+
+impl_value_schema_list!(HashSet<T>,);
+impl_value_schema_list!(BTreeSet<T>,);
+impl_value_schema_list!([T; N], const N: usize);
+
+// Tuples are lists of heterogeneous items, which `ValueType` can not describe.
+macro_rules! impl_value_schema_tuple {
+    ($($name:ident)+) => {
+        impl<$($name: ValueSchema),+> ValueSchema for ($($name,)+) {
+            fn value_type() -> ValueType {
+                ValueType::List(Box::new(ValueType::Any))
+            }
+
+            fn register(registry: &mut SchemaRegistry) {
+                $($name::register(registry);)+
+            }
+        }
+    };
+}
+
+impl_value_schema_tuple!(A);
+impl_value_schema_tuple!(A B);
+impl_value_schema_tuple!(A B C);
+impl_value_schema_tuple!(A B C D);
+impl_value_schema_tuple!(A B C D E);
+impl_value_schema_tuple!(A B C D E F);
+impl_value_schema_tuple!(A B C D E F G);
+impl_value_schema_tuple!(A B C D E F G H);
@@ -0,0 +1,815 @@
+use std::fmt::Write;
+
+use crate::{FieldsSchema, SchemaRegistry, TypeSchema, ValueType, VariantSchema};
+
+// Dart keywords and members that generated identifiers must not shadow.
+const RESERVED: &[&str] = &[
+    "abstract",
+    "as",
+    "assert",
+    "async",
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "covariant",
+    "default",
+    "deferred",
+    "do",
+    "dynamic",
+    "else",
+    "enum",
+    "export",
+    "extends",
+    "extension",
+    "external",
+    "factory",
+    "false",
+    "final",
+    "finally",
+    "for",
+    "get",
+    "hashCode",
+    "if",
+    "implements",
+    "import",
+    "in",
+    "index",
+    "interface",
+    "is",
+    "late",
+    "library",
+    "mixin",
+    "name",
+    "new",
+    "null",
+    "operator",
+    "part",
+    "required",
+    "rethrow",
+    "return",
+    "runtimeType",
+    "sealed",
+    "set",
+    "static",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "toString",
+    "toValue",
+    "true",
+    "try",
+    "typedef",
+    "values",
+    "var",
+    "void",
+    "when",
+    "while",
+    "with",
+    "yield",
+];
+
+fn lower_camel_case(name: &str) -> String {
+    let mut res = String::new();
+    let mut upper = false;
+    for (i, c) in name.trim_start_matches('_').chars().enumerate() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            res.extend(c.to_uppercase());
+            upper = false;
+        } else if i == 0 {
+            res.extend(c.to_lowercase());
+        } else {
+            res.push(c);
+        }
+    }
+    if RESERVED.contains(&res.as_str()) {
+        res.push('_');
+    }
+    res
+}
+
+fn string_literal(s: &str) -> String {
+    let mut res = String::from("'");
+    for c in s.chars() {
+        match c {
+            '\\' => res.push_str("\\\\"),
+            '\'' => res.push_str("\\'"),
+            '$' => res.push_str("\\$"),
+            '\n' => res.push_str("\\n"),
+            '\r' => res.push_str("\\r"),
+            '\t' => res.push_str("\\t"),
+            c => res.push(c),
+        }
+    }
+    res.push('\'');
+    res
+}
+
+fn dart_type(ty: &ValueType) -> String {
+    match ty {
+        ValueType::Any => "Object?".into(),
+        ValueType::Null => "Null".into(),
+        ValueType::Bool => "bool".into(),
+        ValueType::Int => "int".into(),
+        ValueType::Double => "double".into(),
+        ValueType::String => "String".into(),
+        ValueType::Int8List => "Int8List".into(),
+        ValueType::Uint8List => "Uint8List".into(),
+        ValueType::Int16List => "Int16List".into(),
+        ValueType::Uint16List => "Uint16List".into(),
+        ValueType::Int32List => "Int32List".into(),
+        ValueType::Uint32List => "Uint32List".into(),
+        ValueType::Int64List => "Int64List".into(),
+        ValueType::Uint64List => "Uint64List".into(),
+        ValueType::Float32List => "Float32List".into(),
+        ValueType::Float64List => "Float64List".into(),
+        ValueType::List(item) => format!("List<{}>", dart_type(item)),
+        ValueType::Map(key, value) => format!("Map<{}, {}>", dart_type(key), dart_type(value)),
+        ValueType::Optional(ty) => {
+            let res = dart_type(ty);
+            if res.ends_with('?') || res == "Null" {
+                res
+            } else {
+                format!("{res}?")
+            }
+        }
+        ValueType::Named(name) => name.clone(),
+    }
+}
+
+fn is_nullable(ty: &ValueType) -> bool {
+    matches!(
+        ty,
+        ValueType::Any | ValueType::Null | ValueType::Optional(_)
+    )
+}
+
+/// Returns Dart expression converting codec value `expr` to `ty`.
+fn decode(expr: &str, ty: &ValueType, depth: usize) -> String {
+    match ty {
+        ValueType::Any => expr.into(),
+        ValueType::Null => "null".into(),
+        ValueType::List(item) => format!(
+            "({expr} as List).map<{}>((e{depth}) => {}).toList()",
+            dart_type(item),
+            decode(&format!("e{depth}"), item, depth + 1),
+        ),
+        ValueType::Map(key, value) => format!(
+            "({expr} as Map).map<{}, {}>((k{depth}, v{depth}) => MapEntry({}, {}))",
+            dart_type(key),
+            dart_type(value),
+            decode(&format!("k{depth}"), key, depth + 1),
+            decode(&format!("v{depth}"), value, depth + 1),
+        ),
+        ValueType::Optional(inner) => {
+            if is_nullable(inner) {
+                decode(expr, inner, depth)
+            } else {
+                format!("({expr} == null ? null : {})", decode(expr, inner, depth))
+            }
+        }
+        ValueType::Named(name) => format!("{name}.fromValue({expr})"),
+        ty => format!("({expr} as {})", dart_type(ty)),
+    }
+}
+
+fn is_identity(ty: &ValueType) -> bool {
+    match ty {
+        ValueType::List(item) => is_identity(item),
+        ValueType::Map(key, value) => is_identity(key) && is_identity(value),
+        ValueType::Optional(inner) => is_identity(inner),
+        ValueType::Named(_) => false,
+        _ => true,
+    }
+}
+
+/// Returns Dart expression converting `expr` of type `ty` to codec value.
+fn encode(expr: &str, ty: &ValueType, nullable: bool, depth: usize) -> String {
+    if is_identity(ty) {
+        return expr.into();
+    }
+    let access = if nullable { "?." } else { "." };
+    match ty {
+        ValueType::List(item) => format!(
+            "{expr}{access}map((e{depth}) => {}).toList()",
+            encode(&format!("e{depth}"), item, false, depth + 1),
+        ),
+        ValueType::Map(key, value) => format!(
+            "{expr}{access}map((k{depth}, v{depth}) => MapEntry({}, {}))",
+            encode(&format!("k{depth}"), key, false, depth + 1),
+            encode(&format!("v{depth}"), value, false, depth + 1),
+        ),
+        ValueType::Optional(inner) => encode(expr, inner, true, depth),
+        ValueType::Named(_) => format!("{expr}{access}toValue()"),
+        _ => expr.into(),
+    }
+}
+
+/// Dart value used for missing fields with `default` attribute.
+fn default_value(ty: &ValueType) -> Option<&'static str> {
+    match ty {
+        ValueType::Any | ValueType::Null | ValueType::Optional(_) => Some("null"),
+        ValueType::Bool => Some("false"),
+        ValueType::Int => Some("0"),
+        ValueType::Double => Some("0.0"),
+        ValueType::String => Some("''"),
+        ValueType::List(_) => Some("const []"),
+        ValueType::Map(_, _) => Some("const {}"),
+        _ => None,
+    }
+}
+
+/// Dart class members generated for Rust fields.
+struct Fields {
+    /// Constructor parameters.
+    params: Vec<String>,
+    /// Field declarations.
+    declarations: Vec<String>,
+    /// Constructor arguments decoding fields from `value`.
+    args: Vec<String>,
+    /// Statement preceding `args`, if any.
+    prologue: Option<String>,
+    /// Expression encoding the fields, `None` for unit.
+    payload: Option<String>,
+    /// Map entries for named fields; used to inline fields in tagged enums.
+    entries: Vec<String>,
+}
+
+fn fields(fields: &FieldsSchema) -> Fields {
+    let mut res = Fields {
+        params: Vec::new(),
+        declarations: Vec::new(),
+        args: Vec::new(),
+        prologue: None,
+        payload: None,
+        entries: Vec::new(),
+    };
+    match fields {
+        FieldsSchema::Named(fields) => {
+            res.prologue = Some("final map = value as Map;".into());
+            for field in fields {
+                let name = lower_camel_case(&field.name);
+                let key = string_literal(&field.key);
+                let ty = dart_type(&field.ty);
+                if is_nullable(&field.ty) {
+                    res.params.push(format!("this.{name}"));
+                } else {
+                    res.params.push(format!("required this.{name}"));
+                }
+                res.declarations.push(format!("final {ty} {name};"));
+                if field.flatten {
+                    let inner = match &field.ty {
+                        ValueType::Optional(inner) => inner,
+                        ty => ty,
+                    };
+                    res.args
+                        .push(format!("{name}: {}", decode("map", inner, 0)));
+                    let encoded = encode(&name, &field.ty, is_nullable(&field.ty), 0);
+                    if is_nullable(&field.ty) {
+                        res.entries.push(format!("...?({encoded} as Map?)"));
+                    } else {
+                        res.entries.push(format!("...({encoded} as Map)"));
+                    }
+                    continue;
+                }
+                let decoded = decode(&format!("map[{key}]"), &field.ty, 0);
+                let decoded = match default_value(&field.ty) {
+                    Some(default) if field.default && !is_nullable(&field.ty) => {
+                        format!("map.containsKey({key}) ? {decoded} : {default}")
+                    }
+                    _ => decoded,
+                };
+                res.args.push(format!("{name}: {decoded}"));
+                let encoded = encode(&name, &field.ty, is_nullable(&field.ty), 0);
+                if field.skip_if_empty && is_nullable(&field.ty) {
+                    res.entries
+                        .push(format!("if ({name} != null) {key}: {encoded}"));
+                } else {
+                    res.entries.push(format!("{key}: {encoded}"));
+                }
+            }
+            res.payload = Some(map_literal(&res.entries));
+            res.params = vec![format!("{{{}}}", res.params.join(", "))];
+        }
+        FieldsSchema::Unnamed(types) if types.len() == 1 => {
+            let ty = &types[0];
+            res.params.push("this.value".into());
+            res.declarations
+                .push(format!("final {} value;", dart_type(ty)));
+            res.args.push(decode("value", ty, 0));
+            res.payload = Some(encode("value", ty, is_nullable(ty), 0));
+        }
+        FieldsSchema::Unnamed(types) => {
+            res.prologue = Some("final list = value as List;".into());
+            let mut items = Vec::new();
+            for (i, ty) in types.iter().enumerate() {
+                let name = format!("value{i}");
+                res.params.push(format!("this.{name}"));
+                res.declarations
+                    .push(format!("final {} {name};", dart_type(ty)));
+                res.args.push(decode(&format!("list[{i}]"), ty, 0));
+                items.push(encode(&name, ty, is_nullable(ty), 0));
+            }
+            res.payload = Some(format!("[{}]", items.join(", ")));
+        }
+        FieldsSchema::Unit => {}
+    }
+    res
+}
+
+fn map_literal(entries: &[String]) -> String {
+    format!("{{{}}}", entries.join(", "))
+}
+
+/// Writes class with constructor, fields and `fromValue` factory. `to_value`
+/// is the body of `toValue` method.
+fn write_class(
+    out: &mut String,
+    name: &str,
+    superclass: Option<&str>,
+    fields: &Fields,
+    from_value: &str,
+    to_value: &str,
+) {
+    match superclass {
+        Some(superclass) => writeln!(out, "class {name} extends {superclass} {{"),
+        None => writeln!(out, "class {name} {{"),
+    }
+    .unwrap();
+    writeln!(out, "  const {name}({});", fields.params.join(", ")).unwrap();
+    // Unit enum variants are constructed directly.
+    if superclass.is_none() || !fields.args.is_empty() {
+        writeln!(out).unwrap();
+        writeln!(out, "  factory {name}.{from_value}(Object? value) {{").unwrap();
+        if let Some(prologue) = &fields.prologue {
+            writeln!(out, "    {prologue}").unwrap();
+        }
+        if fields.args.is_empty() {
+            writeln!(out, "    return const {name}();").unwrap();
+        } else {
+            writeln!(out, "    return {name}(").unwrap();
+            for arg in &fields.args {
+                writeln!(out, "      {arg},").unwrap();
+            }
+            writeln!(out, "    );").unwrap();
+        }
+        writeln!(out, "  }}").unwrap();
+    }
+    for declaration in &fields.declarations {
+        writeln!(out).unwrap();
+        writeln!(out, "  {declaration}").unwrap();
+    }
+    writeln!(out).unwrap();
+    if superclass.is_some() {
+        writeln!(out, "  @override").unwrap();
+    }
+    writeln!(out, "  Object? toValue() => {to_value};").unwrap();
+    writeln!(out, "}}").unwrap();
+}
+
+fn write_struct(out: &mut String, name: &str, schema: &FieldsSchema) {
+    let fields = fields(schema);
+    let to_value = fields.payload.clone().unwrap_or_else(|| "null".into());
+    write_class(out, name, None, &fields, "fromValue", &to_value);
+}
+
+/// Writes Dart enum for fieldless Rust enum. Variants are keyed by their name,
+/// or by discriminant for enums with `repr = "int"`.
+fn write_unit_enum(out: &mut String, name: &str, variants: &[VariantSchema]) {
+    let repr_int = variants.iter().all(|v| v.discriminant.is_some());
+    writeln!(out, "enum {name} {{").unwrap();
+    let members: Vec<String> = variants
+        .iter()
+        .map(|v| {
+            let key = match v.discriminant {
+                Some(discriminant) if repr_int => discriminant.to_string(),
+                _ => string_literal(&v.key),
+            };
+            format!("  {}({key})", lower_camel_case(&v.name))
+        })
+        .collect();
+    writeln!(out, "{};", members.join(",\n")).unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "  const {name}(this._key);").unwrap();
+    writeln!(out).unwrap();
+    let key_type = if repr_int { "int" } else { "String" };
+    writeln!(out, "  final {key_type} _key;").unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "  static {name} fromValue(Object? value) {{").unwrap();
+    writeln!(out, "    for (final v in values) {{").unwrap();
+    writeln!(out, "      if (v._key == value) return v;").unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(
+        out,
+        "    throw ArgumentError.value(value, 'value', 'Unknown {name}');"
+    )
+    .unwrap();
+    writeln!(out, "  }}").unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "  Object? toValue() => _key;").unwrap();
+    writeln!(out, "}}").unwrap();
+}
+
+fn write_enum(
+    out: &mut String,
+    name: &str,
+    tag: Option<&str>,
+    content: Option<&str>,
+    untagged: bool,
+    variants: &[VariantSchema],
+) {
+    let is_unit = |v: &VariantSchema| v.fields == FieldsSchema::Unit;
+    if tag.is_none() && !untagged && variants.iter().all(is_unit) {
+        write_unit_enum(out, name, variants);
+        return;
+    }
+
+    writeln!(out, "sealed class {name} {{").unwrap();
+    writeln!(out, "  const {name}();").unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "  factory {name}.fromValue(Object? value) {{").unwrap();
+    match tag {
+        // Variants are tried in order, same as in Rust.
+        None if untagged => {
+            for variant in variants {
+                if is_unit(variant) {
+                    writeln!(
+                        out,
+                        "    if (value == null) return const {name}{}();",
+                        variant.name
+                    )
+                    .unwrap();
+                } else {
+                    writeln!(out, "    try {{").unwrap();
+                    writeln!(
+                        out,
+                        "      return {name}{}._fromContent(value);",
+                        variant.name
+                    )
+                    .unwrap();
+                    writeln!(out, "    }} catch (_) {{").unwrap();
+                    writeln!(out, "      // Try next variant.").unwrap();
+                    writeln!(out, "    }}").unwrap();
+                }
+            }
+        }
+        None => {
+            for variant in variants.iter().filter(|v| is_unit(v)) {
+                writeln!(
+                    out,
+                    "    if (value == {}) return const {name}{}();",
+                    string_literal(&variant.key),
+                    variant.name
+                )
+                .unwrap();
+            }
+            writeln!(out, "    if (value is Map && value.isNotEmpty) {{").unwrap();
+            writeln!(out, "      final entry = value.entries.first;").unwrap();
+            for variant in variants.iter().filter(|v| !is_unit(v)) {
+                writeln!(
+                    out,
+                    "      if (entry.key == {}) return {name}{}._fromContent(entry.value);",
+                    string_literal(&variant.key),
+                    variant.name
+                )
+                .unwrap();
+            }
+            writeln!(out, "    }}").unwrap();
+        }
+        Some(tag) => {
+            writeln!(out, "    final map = value as Map;").unwrap();
+            writeln!(out, "    final tag = map[{}];", string_literal(tag)).unwrap();
+            let content = match content {
+                Some(content) => format!("map[{}]", string_literal(content)),
+                None => "map".into(),
+            };
+            for variant in variants {
+                let key = string_literal(&variant.key);
+                if is_unit(variant) {
+                    writeln!(
+                        out,
+                        "    if (tag == {key}) return const {name}{}();",
+                        variant.name
+                    )
+                    .unwrap();
+                } else {
+                    writeln!(
+                        out,
+                        "    if (tag == {key}) return {name}{}._fromContent({content});",
+                        variant.name
+                    )
+                    .unwrap();
+                }
+            }
+        }
+    }
+    writeln!(
+        out,
+        "    throw ArgumentError.value(value, 'value', 'Unknown {name}');"
+    )
+    .unwrap();
+    writeln!(out, "  }}").unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "  Object? toValue();").unwrap();
+    writeln!(out, "}}").unwrap();
+
+    for variant in variants {
+        let fields = fields(&variant.fields);
+        let key = string_literal(&variant.key);
+        let to_value = match (tag, content, &fields.payload) {
+            (None, _, None) if untagged => "null".into(),
+            (None, _, Some(payload)) if untagged => payload.clone(),
+            (None, _, None) => key,
+            (None, _, Some(payload)) => format!("{{{key}: {payload}}}"),
+            (Some(tag), None, _) => {
+                let mut entries = vec![format!("{}: {key}", string_literal(tag))];
+                entries.extend(fields.entries.iter().cloned());
+                map_literal(&entries)
+            }
+            (Some(tag), Some(_), None) => format!("{{{}: {key}}}", string_literal(tag)),
+            (Some(tag), Some(content), Some(payload)) => format!(
+                "{{{}: {key}, {}: {payload}}}",
+                string_literal(tag),
+                string_literal(content)
+            ),
+        };
+        writeln!(out).unwrap();
+        write_class(
+            out,
+            &format!("{name}{}", variant.name),
+            Some(name),
+            &fields,
+            "_fromContent",
+            &to_value,
+        );
+    }
+}
+
+impl SchemaRegistry {
+    /// Generates Dart source with classes for all registered types. Each class
+    /// has `fromValue` factory and `toValue` method that convert from and to
+    /// values as represented by the Dart message channel codec.
+    pub fn to_dart(&self) -> String {
+        let mut out = String::new();
+        writeln!(
+            out,
+            "// Generated by irondash_message_channel. Do not edit."
+        )
+        .unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "import 'dart:typed_data';").unwrap();
+        for schema in self.types() {
+            writeln!(out).unwrap();
+            match schema {
+                TypeSchema::Struct { name, fields } => write_struct(&mut out, name, fields),
+                TypeSchema::Enum {
+                    name,
+                    tag,
+                    content,
+                    untagged,
+                    variants,
+                } => write_enum(
+                    &mut out,
+                    name,
+                    tag.as_deref(),
+                    content.as_deref(),
+                    *untagged,
+                    variants,
+                ),
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{FieldSchema, FieldsSchema, SchemaRegistry, TypeSchema, ValueType, VariantSchema};
+
+    use super::{decode, encode, lower_camel_case, string_literal};
+
+    #[test]
+    fn test_names() {
+        assert_eq!(lower_camel_case("content_length"), "contentLength");
+        assert_eq!(lower_camel_case("Circle"), "circle");
+        assert_eq!(lower_camel_case("class"), "class_");
+        assert_eq!(string_literal("a'$b\\"), "'a\\'\\$b\\\\'");
+    }
+
+    #[test]
+    fn test_conversions() {
+        let ty = ValueType::Optional(Box::new(ValueType::List(Box::new(ValueType::Named(
+            "Foo".into(),
+        )))));
+        assert_eq!(
+            decode("map['x']", &ty, 0),
+            "(map['x'] == null ? null : (map['x'] as List).map<Foo>((e0) => Foo.fromValue(e0)).toList())"
+        );
+        assert_eq!(
+            encode("x", &ty, false, 0),
+            "x?.map((e0) => e0.toValue()).toList()"
+        );
+        let ty = ValueType::Map(Box::new(ValueType::String), Box::new(ValueType::Int64List));
+        assert_eq!(
+            decode("v", &ty, 0),
+            "(v as Map).map<String, Int64List>((k0, v0) => MapEntry((k0 as String), (v0 as Int64List)))"
+        );
+        assert_eq!(encode("v", &ty, false, 0), "v");
+    }
+
+    #[test]
+    fn test_struct() {
+        let mut registry = SchemaRegistry::new();
+        registry.insert(TypeSchema::Struct {
+            name: "Request".into(),
+            fields: FieldsSchema::Named(vec![
+                FieldSchema {
+                    name: "url".into(),
+                    key: "url".into(),
+                    ty: ValueType::String,
+                    skip_if_empty: false,
+                    default: false,
+                    flatten: false,
+                },
+                FieldSchema {
+                    name: "max_size".into(),
+                    key: "maxSize".into(),
+                    ty: ValueType::Optional(Box::new(ValueType::Int)),
+                    skip_if_empty: true,
+                    default: false,
+                    flatten: false,
+                },
+            ]),
+        });
+        let expected = r#"// Generated by irondash_message_channel. Do not edit.
+
+import 'dart:typed_data';
+
+class Request {
+  const Request({required this.url, this.maxSize});
+
+  factory Request.fromValue(Object? value) {
+    final map = value as Map;
+    return Request(
+      url: (map['url'] as String),
+      maxSize: (map['maxSize'] == null ? null : (map['maxSize'] as int)),
+    );
+  }
+
+  final String url;
+
+  final int? maxSize;
+
+  Object? toValue() => {'url': url, if (maxSize != null) 'maxSize': maxSize};
+}
+"#;
+        assert_eq!(registry.to_dart(), expected);
+    }
+
+    #[test]
+    fn test_flatten() {
+        let mut registry = SchemaRegistry::new();
+        registry.insert(TypeSchema::Struct {
+            name: "Response".into(),
+            fields: FieldsSchema::Named(vec![
+                FieldSchema {
+                    name: "header".into(),
+                    key: "header".into(),
+                    ty: ValueType::Named("Header".into()),
+                    skip_if_empty: false,
+                    default: false,
+                    flatten: true,
+                },
+                FieldSchema {
+                    name: "extra".into(),
+                    key: "extra".into(),
+                    ty: ValueType::Optional(Box::new(ValueType::Named("Extra".into()))),
+                    skip_if_empty: false,
+                    default: false,
+                    flatten: true,
+                },
+            ]),
+        });
+        let expected = r#"// Generated by irondash_message_channel. Do not edit.
+
+import 'dart:typed_data';
+
+class Response {
+  const Response({required this.header, this.extra});
+
+  factory Response.fromValue(Object? value) {
+    final map = value as Map;
+    return Response(
+      header: Header.fromValue(map),
+      extra: Extra.fromValue(map),
+    );
+  }
+
+  final Header header;
+
+  final Extra? extra;
+
+  Object? toValue() => {...(header.toValue() as Map), ...?(extra?.toValue() as Map?)};
+}
+"#;
+        assert_eq!(registry.to_dart(), expected);
+    }
+
+    #[test]
+    fn test_enums() {
+        let variant = |name: &str, fields: FieldsSchema, discriminant: Option<i64>| VariantSchema {
+            name: name.into(),
+            key: name.into(),
+            fields,
+            discriminant,
+        };
+        let mut registry = SchemaRegistry::new();
+        registry.insert(TypeSchema::Enum {
+            name: "Mode".into(),
+            tag: None,
+            content: None,
+            untagged: false,
+            variants: vec![
+                variant("A", FieldsSchema::Unit, Some(1)),
+                variant("B", FieldsSchema::Unit, Some(5)),
+            ],
+        });
+        registry.insert(TypeSchema::Enum {
+            name: "Untagged".into(),
+            tag: None,
+            content: None,
+            untagged: true,
+            variants: vec![
+                variant("None", FieldsSchema::Unit, None),
+                variant("Int", FieldsSchema::Unnamed(vec![ValueType::Int]), None),
+            ],
+        });
+        let expected = r#"// Generated by irondash_message_channel. Do not edit.
+
+import 'dart:typed_data';
+
+enum Mode {
+  a(1),
+  b(5);
+
+  const Mode(this._key);
+
+  final int _key;
+
+  static Mode fromValue(Object? value) {
+    for (final v in values) {
+      if (v._key == value) return v;
+    }
+    throw ArgumentError.value(value, 'value', 'Unknown Mode');
+  }
+
+  Object? toValue() => _key;
+}
+
+sealed class Untagged {
+  const Untagged();
+
+  factory Untagged.fromValue(Object? value) {
+    if (value == null) return const UntaggedNone();
+    try {
+      return UntaggedInt._fromContent(value);
+    } catch (_) {
+      // Try next variant.
+    }
+    throw ArgumentError.value(value, 'value', 'Unknown Untagged');
+  }
+
+  Object? toValue();
+}
+
+class UntaggedNone extends Untagged {
+  const UntaggedNone();
+
+  @override
+  Object? toValue() => null;
+}
+
+class UntaggedInt extends Untagged {
+  const UntaggedInt(this.value);
+
+  factory UntaggedInt._fromContent(Object? value) {
+    return UntaggedInt(
+      (value as int),
+    );
+  }
+
+  final int value;
+
+  @override
+  Object? toValue() => value;
+}
+"#;
+        assert_eq!(registry.to_dart(), expected);
+    }
+}
@@ -1,9 +1,18 @@
 use std::{
-    any::TypeId, cmp::Ordering, collections::HashMap, convert::Infallible, fmt::Display,
-    hash::Hash, num::TryFromIntError, ops::Deref, sync::Arc,
+    any::TypeId,
+    borrow::Cow,
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    convert::Infallible,
+    fmt::Display,
+    hash::Hash,
+    num::TryFromIntError,
+    ops::Deref,
+    sync::Arc,
 };
 
-use irondash_dart_ffi::raw;
+pub use irondash_dart_ffi::ExternalBytes;
+use irondash_dart_ffi::{raw, DartPort};
 
 use crate::FinalizableHandle;
 
@@ -21,6 +30,7 @@ pub enum Value {
     I32List(Vec<i32>),
     U32List(Vec<u32>),
     I64List(Vec<i64>),
+    U64List(Vec<u64>),
     F32List(Vec<f32>),
     F64List(Vec<f64>),
     List(Vec<Value>),
@@ -30,7 +40,13 @@ pub enum Value {
     // functionality and we'll save time building HashMap that is not used.
     Map(ValueTupleList),
 
-    /// Special Dart objects. These can only be sent from Rust to Dart
+    /// Bytes owned by an arbitrary Rust object. Can only be sent from Rust
+    /// to Dart, where it will be received as unmodifiable `Uint8List` backed
+    /// by the owner's memory without copying. See [`ExternalBytes`].
+    External(ExternalBytes),
+
+    /// Special Dart objects. `SendPort` and `Capability` instances sent from
+    /// Dart are received as this variant and can be sent back to Dart.
     Dart(DartObject),
 
     /// Can only be send from Rust to Dart. On Dart side this will be a
@@ -50,6 +66,17 @@ pub enum DartObject {
     Capability(raw::DartCObjectCapability),
 }
 
+impl DartObject {
+    /// Returns port that can be used to post messages directly to the
+    /// `SendPort`, bypassing message channel.
+    pub fn send_port(&self) -> Option<DartPort> {
+        match self {
+            DartObject::SendPort(port) => Some(DartPort::new(port.id)),
+            DartObject::Capability(_) => None,
+        }
+    }
+}
+
 /// Wrapper for Value tuple that ensures that the underyling list is sorted
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Hash)]
 pub struct ValueTupleList(Vec<(Value, Value)>);
@@ -82,9 +109,71 @@ impl_from!(Value::F64, f32);
 impl_from!(Value::F64, f64);
 impl_from!(Value::String, String);
 impl_from!(Value::String, &str);
-impl_from!(Value::Map, Vec<(Value, Value)>);
 impl_from!(Value::Dart, DartObject);
+impl_from!(Value::External, ExternalBytes);
 impl_from!(Value::FinalizableHandle, Arc<FinalizableHandle>);
+impl_from!(Value::String, Cow<'_, str>);
+impl_from!(Value::U8List, &[u8]);
+
+impl From<isize> for Value {
+    fn from(v: isize) -> Self {
+        Value::I64(v as i64)
+    }
+}
+
+// Dart integers are signed 64-bit, so unsigned values must fit into i64.
+// Larger values can not be represented, which is why there is no `From`.
+macro_rules! impl_try_from_unsigned {
+    ($for_type:ty) => {
+        impl TryFrom<$for_type> for Value {
+            type Error = TryFromError;
+
+            fn try_from(v: $for_type) -> Result<Self, Self::Error> {
+                Ok(Value::I64(i64::try_from(v)?))
+            }
+        }
+    };
+}
+
+impl_try_from_unsigned!(u64);
+impl_try_from_unsigned!(usize);
+
+impl From<Vec<u64>> for Value {
+    fn from(v: Vec<u64>) -> Self {
+        Value::U64List(v)
+    }
+}
+
+impl From<char> for Value {
+    fn from(v: char) -> Self {
+        Value::String(v.to_string())
+    }
+}
+
+impl<T: Into<Value>> From<Box<T>> for Value {
+    fn from(v: Box<T>) -> Self {
+        (*v).into()
+    }
+}
+
+impl<T: Into<Value> + 'static, const N: usize> From<[T; N]> for Value {
+    fn from(v: [T; N]) -> Self {
+        Vec::from(v).into()
+    }
+}
+
+// Sets are represented as lists.
+impl<T: Into<Value> + 'static> From<HashSet<T>> for Value {
+    fn from(v: HashSet<T>) -> Self {
+        v.into_iter().collect::<Vec<_>>().into()
+    }
+}
+
+impl<T: Into<Value> + 'static> From<BTreeSet<T>> for Value {
+    fn from(v: BTreeSet<T>) -> Self {
+        v.into_iter().collect::<Vec<_>>().into()
+    }
+}
 
 impl<T: Into<Value>> From<Option<T>> for Value {
     fn from(v: Option<T>) -> Self {
@@ -120,6 +209,9 @@ impl<T: Into<Value> + 'static> From<Vec<T>> for Value {
             Value::F32List(unsafe { std::mem::transmute::<Vec<T>, Vec<f32>>(vec) })
         } else if type_id == TypeId::of::<f64>() {
             Value::F64List(unsafe { std::mem::transmute::<Vec<T>, Vec<f64>>(vec) })
+        } else if type_id == TypeId::of::<(Value, Value)>() {
+            // Tuples are lists, but list of Value pairs has always been a map.
+            Value::Map(unsafe { std::mem::transmute::<Vec<T>, Vec<(Value, Value)>>(vec) }.into())
         } else {
             Value::List(vec.into_iter().map(|v| v.into()).collect())
         }
@@ -142,12 +234,123 @@ impl<K: Into<Value>, V: Into<Value>> From<HashMap<K, V>> for Value {
     }
 }
 
+impl<K: Into<Value>, V: Into<Value>> From<BTreeMap<K, V>> for Value {
+    fn from(map: BTreeMap<K, V>) -> Self {
+        let values: Vec<(Value, Value)> =
+            map.into_iter().map(|(k, v)| (k.into(), v.into())).collect();
+        Value::Map(values.into())
+    }
+}
+
+// Tuples are represented as lists.
+macro_rules! impl_tuple {
+    ($len:expr, $($index:tt $name:ident $error:ident),+) => {
+        impl<$($name: Into<Value>),+> From<($($name,)+)> for Value {
+            fn from(v: ($($name,)+)) -> Self {
+                Value::List(vec![$(v.$index.into()),+])
+            }
+        }
+
+        impl<$($name, $error),+> TryFrom<Value> for ($($name,)+)
+        where
+            $($name: TryFrom<Value, Error = $error>, $error: Into<TryFromError>,)+
+        {
+            type Error = TryFromError;
+
+            fn try_from(value: Value) -> Result<Self, Self::Error> {
+                match value {
+                    Value::List(list) if list.len() == $len => {
+                        let mut iter = list.into_iter();
+                        Ok(($(
+                            iter.next()
+                                .unwrap()
+                                .try_into()
+                                .map_err(|e: $error| e.into().at(PathSegment::Index($index)))?,
+                        )+))
+                    }
+                    Value::List(list) => Err(TryFromError::OtherError(format!(
+                        "Expected list with {} items, got {}.",
+                        $len,
+                        list.len()
+                    ))),
+                    value => Err(TryFromError::unexpected_type("List", &value)),
+                }
+            }
+        }
+    };
+}
+
+impl_tuple!(1, 0 T0 E0);
+impl_tuple!(2, 0 T0 E0, 1 T1 E1);
+impl_tuple!(3, 0 T0 E0, 1 T1 E1, 2 T2 E2);
+impl_tuple!(4, 0 T0 E0, 1 T1 E1, 2 T2 E2, 3 T3 E3);
+impl_tuple!(5, 0 T0 E0, 1 T1 E1, 2 T2 E2, 3 T3 E3, 4 T4 E4);
+impl_tuple!(6, 0 T0 E0, 1 T1 E1, 2 T2 E2, 3 T3 E3, 4 T4 E4, 5 T5 E5);
+impl_tuple!(7, 0 T0 E0, 1 T1 E1, 2 T2 E2, 3 T3 E3, 4 T4 E4, 5 T5 E5, 6 T6 E6);
+impl_tuple!(8, 0 T0 E0, 1 T1 E1, 2 T2 E2, 3 T3 E3, 4 T4 E4, 5 T5 E5, 6 T6 E6, 7 T7 E7);
+
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum TryFromError {
+    /// Not returned by conversions in this crate, which report
+    /// [`TryFromError::UnexpectedType`] instead. Kept for custom conversions.
     BadType,
     IntConversionError,
     FloatConversionError,
     OtherError(String),
+    /// Value is of different kind than the target type expects. `actual` is
+    /// the name of [`Value`] variant as returned by [`Value::kind`];
+    /// `expected` is usually a variant name as well, or a short description
+    /// when more kinds are accepted.
+    UnexpectedType {
+        expected: &'static str,
+        actual: &'static str,
+    },
+    /// Conversion of value nested in a map or list failed.
+    Nested {
+        path: ValuePath,
+        error: Box<TryFromError>,
+    },
+}
+
+impl TryFromError {
+    pub(crate) fn unexpected_type(expected: &'static str, actual: &Value) -> Self {
+        Self::UnexpectedType {
+            expected,
+            actual: actual.kind(),
+        }
+    }
+
+    /// Prepends path segment to the error. Used when converting nested
+    /// values so that the error points at the offending value.
+    pub fn at(self, segment: PathSegment) -> Self {
+        match self {
+            TryFromError::Nested { mut path, error } => {
+                path.0.insert(0, segment);
+                TryFromError::Nested { path, error }
+            }
+            error => TryFromError::Nested {
+                path: ValuePath(vec![segment]),
+                error: Box::new(error),
+            },
+        }
+    }
+
+    /// Path to the value that failed to convert, if the value was nested.
+    pub fn path(&self) -> Option<&ValuePath> {
+        match self {
+            TryFromError::Nested { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+
+    /// Returns the error without path information.
+    pub fn root_cause(&self) -> &TryFromError {
+        match self {
+            TryFromError::Nested { error, .. } => error,
+            error => error,
+        }
+    }
 }
 
 impl Display for TryFromError {
@@ -163,7 +366,52 @@ impl Display for TryFromError {
             TryFromError::OtherError(str) => {
                 write!(f, "{str}")
             }
+            TryFromError::UnexpectedType { expected, actual } => {
+                write!(f, "Expected {expected}, got {actual}.")
+            }
+            TryFromError::Nested { path, error } => {
+                write!(f, "{path}: {error}")
+            }
+        }
+    }
+}
+
+/// Single step in [`ValuePath`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+    /// Struct field, i.e. string map key.
+    Field(Cow<'static, str>),
+    /// List item or tuple field.
+    Index(usize),
+    /// Key of a map converted to `HashMap`, formatted for display.
+    Key(String),
+}
+
+impl PathSegment {
+    pub fn key(key: &Value) -> Self {
+        match key {
+            Value::String(key) => PathSegment::Key(format!("{key:?}")),
+            Value::I64(key) => PathSegment::Key(key.to_string()),
+            key => PathSegment::Key(format!("{key:?}")),
+        }
+    }
+}
+
+/// Location of a nested value, displayed as `items[3].price`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ValuePath(pub Vec<PathSegment>);
+
+impl Display for ValuePath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, segment) in self.0.iter().enumerate() {
+            match segment {
+                PathSegment::Field(name) if i == 0 => write!(f, "{name}")?,
+                PathSegment::Field(name) => write!(f, ".{name}")?,
+                PathSegment::Index(index) => write!(f, "[{index}]")?,
+                PathSegment::Key(key) => write!(f, "[{key}]")?,
+            }
         }
+        Ok(())
     }
 }
 
@@ -182,13 +430,13 @@ impl From<Infallible> for TryFromError {
 }
 
 macro_rules! impl_try_from {
-    ($variant:path, $for_type:ty) => {
+    (Value::$variant:ident, $for_type:ty) => {
         impl TryFrom<Value> for $for_type {
             type Error = TryFromError;
             fn try_from(v: Value) -> Result<Self, Self::Error> {
                 match v {
-                    $variant(d) => Ok(d.into()),
-                    _ => Err(TryFromError::BadType),
+                    Value::$variant(d) => Ok(d.into()),
+                    v => Err(TryFromError::unexpected_type(stringify!($variant), &v)),
                 }
             }
         }
@@ -196,14 +444,14 @@ macro_rules! impl_try_from {
 }
 
 macro_rules! impl_try_from2 {
-    ($variant:path, $for_type:ty) => {
+    (Value::$variant:ident, $for_type:ty) => {
         impl TryFrom<Value> for $for_type {
             type Error = TryFromError;
             fn try_from(v: Value) -> Result<Self, Self::Error> {
                 use ::core::convert::TryInto;
                 match v {
-                    $variant(d) => Ok(d.try_into().map_err(TryFromError::from)?),
-                    _ => Err(TryFromError::BadType),
+                    Value::$variant(d) => Ok(d.try_into().map_err(TryFromError::from)?),
+                    v => Err(TryFromError::unexpected_type(stringify!($variant), &v)),
                 }
             }
         }
@@ -216,7 +464,7 @@ impl TryFrom<Value> for () {
     fn try_from(value: Value) -> Result<Self, Self::Error> {
         match value {
             Value::Null => Ok(()),
-            _ => Err(TryFromError::BadType),
+            value => Err(TryFromError::unexpected_type("Null", &value)),
         }
     }
 }
@@ -231,8 +479,11 @@ impl_try_from2!(Value::I64, u32);
 impl_try_from!(Value::I64, i64);
 impl_try_from!(Value::F64, f64);
 impl_try_from!(Value::String, String);
+impl_try_from!(Value::String, Cow<'static, str>);
+impl_try_from2!(Value::I64, u64);
+impl_try_from2!(Value::I64, usize);
+impl_try_from2!(Value::I64, isize);
 impl_try_from!(Value::Map, ValueTupleList);
-impl_try_from!(Value::Map, Vec<(Value, Value)>);
 impl_try_from!(Value::Dart, DartObject);
 impl_try_from!(Value::FinalizableHandle, Arc<FinalizableHandle>);
 
@@ -255,11 +506,102 @@ impl TryFrom<Value> for f32 {
                     }
                 }
             }
-            _ => Err(Self::Error::BadType),
+            value => Err(TryFromError::unexpected_type("F64", &value)),
+        }
+    }
+}
+
+impl TryFrom<Value> for char {
+    type Error = TryFromError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::String(s) => {
+                let mut chars = s.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Ok(c),
+                    _ => Err(TryFromError::OtherError(format!(
+                        "Expected single character, got {s:?}."
+                    ))),
+                }
+            }
+            value => Err(TryFromError::unexpected_type("String", &value)),
         }
     }
 }
 
+impl<T: TryFrom<Value, Error = E> + 'static, E: Into<TryFromError>, const N: usize> TryFrom<Value>
+    for [T; N]
+{
+    type Error = TryFromError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        let vec: Vec<T> = value.try_into()?;
+        vec.try_into().map_err(|vec: Vec<T>| {
+            TryFromError::OtherError(format!("Expected list with {N} items, got {}.", vec.len()))
+        })
+    }
+}
+
+impl<T: TryFrom<Value, Error = E> + Eq + Hash + 'static, E: Into<TryFromError>> TryFrom<Value>
+    for HashSet<T>
+{
+    type Error = TryFromError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        let vec: Vec<T> = value.try_into()?;
+        Ok(vec.into_iter().collect())
+    }
+}
+
+impl<T: TryFrom<Value, Error = E> + Ord + 'static, E: Into<TryFromError>> TryFrom<Value>
+    for BTreeSet<T>
+{
+    type Error = TryFromError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        let vec: Vec<T> = value.try_into()?;
+        Ok(vec.into_iter().collect())
+    }
+}
+
+fn try_from_map<K, V, E1, E2, M>(value: Value) -> Result<M, TryFromError>
+where
+    K: TryFrom<Value, Error = E1>,
+    V: TryFrom<Value, Error = E2>,
+    E1: Into<TryFromError>,
+    E2: Into<TryFromError>,
+    M: FromIterator<(K, V)>,
+{
+    match value {
+        Value::Map(map) => map
+            .into_iter()
+            .map(|(k, v)| {
+                let segment = PathSegment::key(&k);
+                Ok((
+                    k.try_into().map_err(|e: E1| e.into().at(segment.clone()))?,
+                    v.try_into().map_err(|e: E2| e.into().at(segment))?,
+                ))
+            })
+            .collect(),
+        value => Err(TryFromError::unexpected_type("Map", &value)),
+    }
+}
+
+impl<
+        K: TryFrom<Value, Error = E1> + Ord,
+        V: TryFrom<Value, Error = E2>,
+        E1: Into<TryFromError>,
+        E2: Into<TryFromError>,
+    > TryFrom<Value> for BTreeMap<K, V>
+{
+    type Error = TryFromError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        try_from_map(value)
+    }
+}
+
 // Allow converting to any Kind of HashMap as long as key and value
 // are types that can be converted from Value.
 impl<
@@ -272,26 +614,21 @@ impl<
     type Error = TryFromError;
 
     fn try_from(value: Value) -> Result<Self, Self::Error> {
-        match value {
-            Value::Map(map) => map
-                .into_iter()
-                .map(|(k, v)| {
-                    Ok((
-                        k.try_into().map_err(|e: E1| e.into())?,
-                        v.try_into().map_err(|e: E2| e.into())?,
-                    ))
-                })
-                .collect(),
-            _ => Err(TryFromError::BadType),
-        }
+        try_from_map(value)
     }
 }
 
-fn try_extract<T: 'static, V: 'static>(list: Vec<T>) -> Result<Vec<V>, TryFromError> {
+fn try_extract<T: 'static, V: 'static>(
+    list: Vec<T>,
+    kind: &'static str,
+) -> Result<Vec<V>, TryFromError> {
     if TypeId::of::<V>() == TypeId::of::<T>() {
         Ok(unsafe { std::mem::transmute::<Vec<T>, Vec<V>>(list) })
     } else {
-        Err(TryFromError::BadType)
+        Err(TryFromError::UnexpectedType {
+            expected: "List",
+            actual: kind,
+        })
     }
 }
 
@@ -302,18 +639,29 @@ impl<V: TryFrom<Value, Error = E> + 'static, E: Into<TryFromError>> TryFrom<Valu
         match value {
             Value::List(list) => list
                 .into_iter()
-                .map(|v| v.try_into().map_err(|e: E| e.into()))
+                .enumerate()
+                .map(|(i, v)| {
+                    v.try_into()
+                        .map_err(|e: E| e.into().at(PathSegment::Index(i)))
+                })
                 .collect(),
-            Value::I8List(list) => try_extract(list),
-            Value::U8List(list) => try_extract(list),
-            Value::I16List(list) => try_extract(list),
-            Value::U16List(list) => try_extract(list),
-            Value::I32List(list) => try_extract(list),
-            Value::U32List(list) => try_extract(list),
-            Value::I64List(list) => try_extract(list),
-            Value::F32List(list) => try_extract(list),
-            Value::F64List(list) => try_extract(list),
-            _ => Err(TryFromError::BadType),
+            Value::I8List(list) => try_extract(list, "I8List"),
+            Value::U8List(list) => try_extract(list, "U8List"),
+            Value::I16List(list) => try_extract(list, "I16List"),
+            Value::U16List(list) => try_extract(list, "U16List"),
+            Value::I32List(list) => try_extract(list, "I32List"),
+            Value::U32List(list) => try_extract(list, "U32List"),
+            Value::I64List(list) => try_extract(list, "I64List"),
+            Value::U64List(list) => try_extract(list, "U64List"),
+            Value::F32List(list) => try_extract(list, "F32List"),
+            Value::F64List(list) => try_extract(list, "F64List"),
+            Value::External(bytes) if TypeId::of::<V>() == TypeId::of::<u8>() => {
+                try_extract(bytes.to_vec(), "External")
+            }
+            Value::Map(map) if TypeId::of::<V>() == TypeId::of::<(Value, Value)>() => {
+                try_extract(map.into(), "Map")
+            }
+            value => Err(TryFromError::unexpected_type("List", &value)),
         }
     }
 }
@@ -351,10 +699,12 @@ impl std::hash::Hash for Value {
             Value::I32List(v) => v.hash(state),
             Value::U32List(v) => v.hash(state),
             Value::I64List(v) => v.hash(state),
+            Value::U64List(v) => v.hash(state),
             Value::F32List(v) => v.iter().for_each(|x| hash_f32(*x, state)),
             Value::F64List(v) => v.iter().for_each(|x| hash_f64(*x, state)),
             Value::List(v) => v.hash(state),
             Value::Map(v) => v.hash(state),
+            Value::External(v) => v.hash(state),
             Value::Dart(v) => v.hash(state),
             Value::FinalizableHandle(v) => v.hash(state),
         }
@@ -368,10 +718,129 @@ impl ValueTupleList {
             .windows(2)
             .any(|w| w[0].0.partial_cmp(&w[1].0) != Some(Ordering::Less))
         {
-            value.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+            value.sort_by(|a, b| compare_keys(&a.0, &b.0));
         }
         Self(value)
     }
+
+    /// Returns value for given key. Uses binary search as the list is sorted.
+    pub fn get(&self, key: &Value) -> Option<&Value> {
+        self.0
+            .binary_search_by(|(k, _)| compare_keys(k, key))
+            .ok()
+            .map(|index| &self.0[index].1)
+    }
+}
+
+fn compare_keys(a: &Value, b: &Value) -> Ordering {
+    a.partial_cmp(b).unwrap_or(Ordering::Equal)
+}
+
+impl Value {
+    /// Name of the variant, used in conversion errors.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Value::Null => "Null",
+            Value::Bool(_) => "Bool",
+            Value::I64(_) => "I64",
+            Value::F64(_) => "F64",
+            Value::String(_) => "String",
+            Value::I8List(_) => "I8List",
+            Value::U8List(_) => "U8List",
+            Value::I16List(_) => "I16List",
+            Value::U16List(_) => "U16List",
+            Value::I32List(_) => "I32List",
+            Value::U32List(_) => "U32List",
+            Value::I64List(_) => "I64List",
+            Value::U64List(_) => "U64List",
+            Value::F32List(_) => "F32List",
+            Value::F64List(_) => "F64List",
+            Value::List(_) => "List",
+            Value::Map(_) => "Map",
+            Value::External(_) => "External",
+            Value::Dart(_) => "Dart",
+            Value::FinalizableHandle(_) => "FinalizableHandle",
+        }
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, Value::Null)
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Value::I64(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::F64(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[Value]> {
+        match self {
+            Value::List(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_map(&self) -> Option<&ValueTupleList> {
+        match self {
+            Value::Map(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Returns map value for given key or `None` if this is not a map or the
+    /// key is not present.
+    pub fn get<K: Into<Value>>(&self, key: K) -> Option<&Value> {
+        self.as_map().and_then(|map| map.get(&key.into()))
+    }
+
+    /// Returns list item at given index or `None` if this is not a
+    /// [`Value::List`] or the index is out of bounds.
+    pub fn get_index(&self, index: usize) -> Option<&Value> {
+        self.as_list().and_then(|list| list.get(index))
+    }
+
+    /// Looks up nested value using JSON pointer syntax (RFC 6901), i.e.
+    /// `/key/0/other_key`. Map keys are matched as strings first and then as
+    /// integers; list items are matched by index.
+    pub fn pointer(&self, pointer: &str) -> Option<&Value> {
+        if pointer.is_empty() {
+            return Some(self);
+        }
+        let pointer = pointer.strip_prefix('/')?;
+        pointer
+            .split('/')
+            .map(|token| token.replace("~1", "/").replace("~0", "~"))
+            .try_fold(self, |value, token| match value {
+                Value::Map(map) => map.get(&Value::String(token.clone())).or_else(|| {
+                    let key: i64 = token.parse().ok()?;
+                    map.get(&Value::I64(key))
+                }),
+                Value::List(list) => list.get(token.parse::<usize>().ok()?),
+                _ => None,
+            })
+    }
 }
 
 impl Deref for ValueTupleList {
@@ -428,7 +897,12 @@ impl From<DartObject> for irondash_dart_ffi::DartValue {
 
 #[cfg(test)]
 mod tests {
-    use crate::{TryFromError, Value};
+    use std::{
+        borrow::Cow,
+        collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    };
+
+    use crate::{value, PathSegment, TryFromError, Value};
 
     #[test]
     fn test_equality() {
@@ -457,6 +931,9 @@ mod tests {
         let v: Value = (vec![1u32]).into();
         assert_eq!(v, Value::U32List(vec![1]));
 
+        let v: Value = (vec![u64::MAX]).into();
+        assert_eq!(v, Value::U64List(vec![u64::MAX]));
+
         let v: Value = (vec![1i64]).into();
         assert_eq!(v, Value::I64List(vec![1]));
 
@@ -499,6 +976,10 @@ mod tests {
         let r: Vec<u32> = v.try_into()?;
         assert_eq!(r, vec![1u32]);
 
+        let v = Value::U64List(vec![u64::MAX]);
+        let r: Vec<u64> = v.try_into()?;
+        assert_eq!(r, vec![u64::MAX]);
+
         let v = Value::I64List(vec![1]);
         let r: Vec<i64> = v.try_into()?;
         assert_eq!(r, vec![1i64]);
@@ -534,4 +1015,187 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_try_from_error_path() {
+        let v = Value::List(vec![Value::I64(1), Value::String("2".into())]);
+        let r: Result<Vec<i64>, _> = v.try_into();
+        let err = r.unwrap_err();
+        assert_eq!(
+            err,
+            TryFromError::UnexpectedType {
+                expected: "I64",
+                actual: "String"
+            }
+            .at(PathSegment::Index(1))
+        );
+        assert_eq!(err.path().unwrap().to_string(), "[1]");
+        assert_eq!(err.to_string(), "[1]: Expected I64, got String.");
+
+        let v = value!({ "a": [1, null] });
+        let r: Result<HashMap<String, Vec<i64>>, _> = v.try_into();
+        let err = r.unwrap_err().at(PathSegment::Field("items".into()));
+        assert_eq!(err.path().unwrap().to_string(), "items[\"a\"][1]");
+        assert_eq!(
+            err.root_cause(),
+            &TryFromError::UnexpectedType {
+                expected: "I64",
+                actual: "Null"
+            }
+        );
+
+        let r: Result<Vec<u8>, _> = Value::I8List(vec![1]).try_into();
+        assert_eq!(
+            r,
+            Err(TryFromError::UnexpectedType {
+                expected: "List",
+                actual: "I8List"
+            })
+        );
+    }
+
+    #[test]
+    fn test_value_macro() {
+        let name = "name";
+        let v = value!({
+            "a": 1,
+            "b": [1.0, 2.0, null, [], {}],
+            "c": null,
+            (name): name.to_uppercase(),
+            10: { "nested": [true, -1] },
+        });
+        let expected = Value::Map(
+            vec![
+                ("a".into(), 1.into()),
+                (
+                    "b".into(),
+                    Value::List(vec![
+                        1.0.into(),
+                        2.0.into(),
+                        Value::Null,
+                        Value::List(vec![]),
+                        Value::Map(vec![].into()),
+                    ]),
+                ),
+                ("c".into(), Value::Null),
+                ("name".into(), "NAME".into()),
+                (
+                    10.into(),
+                    Value::Map(
+                        vec![("nested".into(), Value::List(vec![true.into(), (-1).into()]))].into(),
+                    ),
+                ),
+            ]
+            .into(),
+        );
+        assert_eq!(v, expected);
+        assert_eq!(value!(null), Value::Null);
+        assert_eq!(value!(1 + 2), Value::I64(3));
+        assert_eq!(value!([v.clone()]), Value::List(vec![expected]));
+    }
+
+    #[test]
+    fn test_accessors() {
+        let v = value!({
+            "a": 1,
+            "b": [1.5, "x", { "a/b": true, "~": false }],
+            10: "ten",
+        });
+        assert_eq!(v.get("a").and_then(Value::as_i64), Some(1));
+        assert_eq!(v.get(10).and_then(Value::as_str), Some("ten"));
+        assert_eq!(v.get("missing"), None);
+        assert_eq!(
+            v.get("b").and_then(|b| b.get_index(0)),
+            Some(&Value::F64(1.5))
+        );
+        assert_eq!(v.get("b").and_then(|b| b.get_index(3)), None);
+        assert_eq!(
+            v.get("b").and_then(Value::as_list).map(|l| l.len()),
+            Some(3)
+        );
+        assert_eq!(v.as_map().map(|m| m.len()), Some(3));
+        assert!(v.as_str().is_none());
+
+        assert_eq!(v.pointer(""), Some(&v));
+        assert_eq!(v.pointer("/a"), Some(&Value::I64(1)));
+        assert_eq!(v.pointer("/10"), Some(&Value::String("ten".into())));
+        assert_eq!(v.pointer("/b/1").and_then(Value::as_str), Some("x"));
+        assert_eq!(v.pointer("/b/2/a~1b").and_then(Value::as_bool), Some(true));
+        assert_eq!(v.pointer("/b/2/~0").and_then(Value::as_bool), Some(false));
+        assert_eq!(v.pointer("/b/5"), None);
+        assert_eq!(v.pointer("/a/0"), None);
+        assert_eq!(v.pointer("a"), None);
+    }
+
+    #[test]
+    fn test_map_lookup() {
+        let entries: Vec<(Value, Value)> =
+            (0..100).rev().map(|i| (i.into(), (i * 2).into())).collect();
+        let v = Value::Map(entries.into());
+        for i in 0..100 {
+            assert_eq!(v.get(i), Some(&Value::I64(i * 2)));
+        }
+        assert_eq!(v.get(100), None);
+        assert_eq!(v.get("0"), None);
+    }
+
+    #[test]
+    fn test_std_conversions() -> Result<(), TryFromError> {
+        assert_eq!(Value::try_from(5u64)?, Value::I64(5));
+        assert_eq!(
+            Value::try_from(u64::MAX),
+            Err(TryFromError::IntConversionError)
+        );
+        assert_eq!(Value::try_from(7usize)?, Value::I64(7));
+        assert_eq!(u64::try_from(Value::I64(5))?, 5);
+        assert!(u64::try_from(Value::I64(-1)).is_err());
+        assert_eq!(usize::try_from(Value::I64(7))?, 7);
+        assert_eq!(isize::try_from(Value::from(-7isize))?, -7);
+
+        assert_eq!(Value::from('x'), Value::String("x".into()));
+        assert_eq!(char::try_from(Value::from('x'))?, 'x');
+        assert!(char::try_from(Value::from("xy")).is_err());
+
+        let cow: Cow<str> = Cow::Borrowed("cow");
+        assert_eq!(Value::from(cow), Value::String("cow".into()));
+        let bytes: &[u8] = &[1, 2];
+        assert_eq!(Value::from(bytes), Value::U8List(vec![1, 2]));
+
+        let tuple = (1i64, "a".to_owned(), true);
+        let value: Value = tuple.clone().into();
+        assert_eq!(
+            value,
+            Value::List(vec![1i64.into(), "a".into(), true.into()])
+        );
+        assert_eq!(<(i64, String, bool)>::try_from(value.clone())?, tuple);
+        assert_eq!(
+            <(i64, String)>::try_from(value).unwrap_err().to_string(),
+            "Expected list with 2 items, got 3."
+        );
+
+        let array: [i64; 3] = [1, 2, 3];
+        let value: Value = array.into();
+        assert_eq!(value, Value::I64List(vec![1, 2, 3]));
+        assert_eq!(<[i64; 3]>::try_from(value.clone())?, array);
+        assert!(<[i64; 2]>::try_from(value).is_err());
+
+        let set: BTreeSet<String> = ["a".to_owned(), "b".to_owned()].into();
+        let value: Value = set.clone().into();
+        assert_eq!(value, Value::List(vec!["a".into(), "b".into()]));
+        assert_eq!(BTreeSet::<String>::try_from(value.clone())?, set);
+        assert_eq!(HashSet::<String>::try_from(value)?.len(), 2);
+
+        let map: BTreeMap<i64, String> = [(1, "one".to_owned())].into();
+        let value: Value = map.clone().into();
+        assert_eq!(BTreeMap::<i64, String>::try_from(value)?, map);
+
+        assert_eq!(Value::from(Box::new(3i64)), Value::I64(3));
+
+        // List of value pairs is still a map, not a list of tuples.
+        let entries: Vec<(Value, Value)> = vec![("k".into(), 1i64.into())];
+        let value: Value = entries.clone().into();
+        assert!(matches!(value, Value::Map(_)));
+        assert_eq!(Vec::<(Value, Value)>::try_from(value)?, entries);
+        Ok(())
+    }
 }
@@ -152,12 +152,14 @@ unsafe fn _value_to_objc(value: &Value) -> Result<Option<Id<NSObject>>, TryFromE
         Value::F64(v) => Ok(Some(Id::cast(NSNumber::numberWithDouble(*v)))),
         Value::String(s) => Ok(Some(Id::cast(NSString::from_str(s)))),
         Value::U8List(d) => Ok(Some(Id::cast(NSData::with_bytes(d)))),
+        Value::External(d) => Ok(Some(Id::cast(NSData::with_bytes(d)))),
         Value::I8List(d) => Ok(Some(Id::cast(NSData::with_bytes(transform_slice(d))))),
         Value::U16List(d) => Ok(Some(Id::cast(NSData::with_bytes(transform_slice(d))))),
         Value::I16List(d) => Ok(Some(Id::cast(NSData::with_bytes(transform_slice(d))))),
         Value::U32List(d) => Ok(Some(Id::cast(NSData::with_bytes(transform_slice(d))))),
         Value::I32List(d) => Ok(Some(Id::cast(NSData::with_bytes(transform_slice(d))))),
         Value::I64List(d) => Ok(Some(Id::cast(NSData::with_bytes(transform_slice(d))))),
+        Value::U64List(d) => Ok(Some(Id::cast(NSData::with_bytes(transform_slice(d))))),
         Value::F32List(d) => Ok(Some(Id::cast(NSData::with_bytes(transform_slice(d))))),
         Value::F64List(d) => Ok(Some(Id::cast(NSData::with_bytes(transform_slice(d))))),
         Value::List(items) => {
@@ -0,0 +1,419 @@
+//! Lossless JSON representation of [`Value`]. Enabled with the `json` feature.
+//!
+//! Values that map directly to JSON are written as is. [`Value::I64`] is
+//! written as integer and finite [`Value::F64`] always contains a decimal
+//! point or exponent (`1.0`), so the two can be told apart when parsing.
+//!
+//! Everything else is written as single entry object with `$` prefixed tag:
+//!
+//! * Non-finite floats: `{"$F64": "NaN"}`, `"Infinity"` or `"-Infinity"`.
+//! * Typed lists: `{"$U8List": [1, 2]}`, same for other typed list variants.
+//!   Non-finite items of float lists are written as strings like above.
+//!   [`Value::External`] is written as `$U8List`.
+//! * Maps with non-string keys: `{"$Map": [[key, value], ...]}`.
+//!
+//! Maps where all keys are strings are written as JSON objects. Keys starting
+//! with `$` are escaped by another `$` (`"$key"` becomes `"$$key"`).
+//!
+//! [`Value::Dart`] and [`Value::FinalizableHandle`] can not be represented
+//! in JSON and result in an error.
+
+use serde_json::{Map, Number};
+
+use crate::{TryFromError, Value, ValueTupleList};
+
+type Json = serde_json::Value;
+
+const TAG_F64: &str = "$F64";
+const TAG_MAP: &str = "$Map";
+const TAG_I8_LIST: &str = "$I8List";
+const TAG_U8_LIST: &str = "$U8List";
+const TAG_I16_LIST: &str = "$I16List";
+const TAG_U16_LIST: &str = "$U16List";
+const TAG_I32_LIST: &str = "$I32List";
+const TAG_U32_LIST: &str = "$U32List";
+const TAG_I64_LIST: &str = "$I64List";
+const TAG_U64_LIST: &str = "$U64List";
+const TAG_F32_LIST: &str = "$F32List";
+const TAG_F64_LIST: &str = "$F64List";
+
+impl Value {
+    /// Converts value to JSON string. See [module documentation](self) for
+    /// description of the format.
+    pub fn to_json_string(&self) -> Result<String, TryFromError> {
+        Ok(to_json(self)?.to_string())
+    }
+
+    /// Same as [`Value::to_json_string`] but the result is pretty printed.
+    pub fn to_json_string_pretty(&self) -> Result<String, TryFromError> {
+        serde_json::to_string_pretty(&to_json(self)?).map_err(json_error)
+    }
+
+    /// Parses value from JSON string produced by [`Value::to_json_string`].
+    /// Plain JSON is accepted as well.
+    pub fn from_json_str(json: &str) -> Result<Value, TryFromError> {
+        let json: Json = serde_json::from_str(json).map_err(json_error)?;
+        from_json(json)
+    }
+}
+
+fn json_error(error: serde_json::Error) -> TryFromError {
+    TryFromError::OtherError(format!("Invalid JSON: {error}"))
+}
+
+fn tagged(tag: &str, value: Json) -> Json {
+    let mut map = Map::new();
+    map.insert(tag.into(), value);
+    Json::Object(map)
+}
+
+fn float_to_json(value: f64) -> Json {
+    match Number::from_f64(value) {
+        Some(number) => Json::Number(number),
+        None if value.is_nan() => Json::String("NaN".into()),
+        None if value > 0.0 => Json::String("Infinity".into()),
+        None => Json::String("-Infinity".into()),
+    }
+}
+
+fn list_to_json<T: Copy + Into<Json>>(tag: &str, list: &[T]) -> Json {
+    tagged(tag, Json::Array(list.iter().map(|v| (*v).into()).collect()))
+}
+
+fn float_list_to_json<T: Copy + Into<f64>>(tag: &str, list: &[T]) -> Json {
+    tagged(
+        tag,
+        Json::Array(list.iter().map(|v| float_to_json((*v).into())).collect()),
+    )
+}
+
+fn escape_key(key: &str) -> String {
+    if key.starts_with('$') {
+        format!("${key}")
+    } else {
+        key.into()
+    }
+}
+
+fn to_json(value: &Value) -> Result<Json, TryFromError> {
+    Ok(match value {
+        Value::Null => Json::Null,
+        Value::Bool(v) => Json::Bool(*v),
+        Value::I64(v) => Json::Number((*v).into()),
+        Value::F64(v) => match float_to_json(*v) {
+            Json::String(s) => tagged(TAG_F64, Json::String(s)),
+            number => number,
+        },
+        Value::String(v) => Json::String(v.clone()),
+        Value::I8List(v) => list_to_json(TAG_I8_LIST, v),
+        Value::U8List(v) => list_to_json(TAG_U8_LIST, v),
+        Value::I16List(v) => list_to_json(TAG_I16_LIST, v),
+        Value::U16List(v) => list_to_json(TAG_U16_LIST, v),
+        Value::I32List(v) => list_to_json(TAG_I32_LIST, v),
+        Value::U32List(v) => list_to_json(TAG_U32_LIST, v),
+        Value::I64List(v) => list_to_json(TAG_I64_LIST, v),
+        Value::U64List(v) => list_to_json(TAG_U64_LIST, v),
+        Value::F32List(v) => float_list_to_json(TAG_F32_LIST, v),
+        Value::F64List(v) => float_list_to_json(TAG_F64_LIST, v),
+        Value::External(v) => list_to_json(TAG_U8_LIST, v),
+        Value::List(v) => Json::Array(v.iter().map(to_json).collect::<Result<_, _>>()?),
+        Value::Map(v) => {
+            if v.iter().all(|(k, _)| matches!(k, Value::String(_))) {
+                let mut map = Map::new();
+                for (k, v) in v.iter() {
+                    if let Value::String(k) = k {
+                        map.insert(escape_key(k), to_json(v)?);
+                    }
+                }
+                Json::Object(map)
+            } else {
+                let entries = v
+                    .iter()
+                    .map(|(k, v)| Ok(Json::Array(vec![to_json(k)?, to_json(v)?])))
+                    .collect::<Result<_, TryFromError>>()?;
+                tagged(TAG_MAP, Json::Array(entries))
+            }
+        }
+        Value::Dart(_) => {
+            return Err(TryFromError::OtherError(
+                "Value::Dart can not be represented as JSON".into(),
+            ))
+        }
+        Value::FinalizableHandle(_) => {
+            return Err(TryFromError::OtherError(
+                "Value::FinalizableHandle can not be represented as JSON".into(),
+            ))
+        }
+    })
+}
+
+/// Kind of [`Value`] the JSON value would be parsed as, used in errors.
+fn json_kind(json: &Json) -> &'static str {
+    match json {
+        Json::Null => "Null",
+        Json::Bool(_) => "Bool",
+        Json::Number(n) if n.is_f64() => "F64",
+        Json::Number(_) => "I64",
+        Json::String(_) => "String",
+        Json::Array(_) => "List",
+        Json::Object(_) => "Map",
+    }
+}
+
+fn unexpected_type(expected: &'static str, json: &Json) -> TryFromError {
+    TryFromError::UnexpectedType {
+        expected,
+        actual: json_kind(json),
+    }
+}
+
+fn float_from_json(json: Json) -> Result<f64, TryFromError> {
+    match json {
+        Json::Number(n) => n.as_f64().ok_or(TryFromError::FloatConversionError),
+        Json::String(s) => match s.as_str() {
+            "NaN" => Ok(f64::NAN),
+            "Infinity" => Ok(f64::INFINITY),
+            "-Infinity" => Ok(f64::NEG_INFINITY),
+            _ => Err(TryFromError::OtherError(format!(
+                "Invalid float value {s:?}"
+            ))),
+        },
+        json => Err(unexpected_type("F64", &json)),
+    }
+}
+
+fn list_items(json: Json) -> Result<Vec<Json>, TryFromError> {
+    match json {
+        Json::Array(items) => Ok(items),
+        json => Err(unexpected_type("List", &json)),
+    }
+}
+
+fn int_list_from_json<T: TryFrom<i64>>(json: Json) -> Result<Vec<T>, TryFromError> {
+    list_items(json)?
+        .into_iter()
+        .map(|item| {
+            let item = item.as_i64().ok_or_else(|| match item.is_u64() {
+                true => TryFromError::IntConversionError,
+                false => unexpected_type("I64", &item),
+            })?;
+            T::try_from(item).map_err(|_| TryFromError::IntConversionError)
+        })
+        .collect()
+}
+
+fn float_list_from_json(json: Json) -> Result<Vec<f64>, TryFromError> {
+    list_items(json)?.into_iter().map(float_from_json).collect()
+}
+
+fn tagged_from_json(tag: &str, json: Json) -> Result<Value, TryFromError> {
+    Ok(match tag {
+        TAG_F64 => Value::F64(float_from_json(json)?),
+        TAG_I8_LIST => Value::I8List(int_list_from_json(json)?),
+        TAG_U8_LIST => Value::U8List(int_list_from_json(json)?),
+        TAG_I16_LIST => Value::I16List(int_list_from_json(json)?),
+        TAG_U16_LIST => Value::U16List(int_list_from_json(json)?),
+        TAG_I32_LIST => Value::I32List(int_list_from_json(json)?),
+        TAG_U32_LIST => Value::U32List(int_list_from_json(json)?),
+        TAG_I64_LIST => Value::I64List(int_list_from_json(json)?),
+        TAG_U64_LIST => Value::U64List(
+            list_items(json)?
+                .into_iter()
+                .map(|item| {
+                    item.as_u64().ok_or_else(|| match item.is_i64() {
+                        true => TryFromError::IntConversionError,
+                        false => unexpected_type("I64", &item),
+                    })
+                })
+                .collect::<Result<_, _>>()?,
+        ),
+        TAG_F32_LIST => Value::F32List(
+            float_list_from_json(json)?
+                .into_iter()
+                .map(|v| v as f32)
+                .collect(),
+        ),
+        TAG_F64_LIST => Value::F64List(float_list_from_json(json)?),
+        TAG_MAP => {
+            let entries = list_items(json)?
+                .into_iter()
+                .map(|entry| {
+                    let mut entry = list_items(entry)?.into_iter();
+                    match (entry.next(), entry.next(), entry.next()) {
+                        (Some(k), Some(v), None) => Ok((from_json(k)?, from_json(v)?)),
+                        _ => Err(TryFromError::OtherError(
+                            "Map entry must be a [key, value] pair".into(),
+                        )),
+                    }
+                })
+                .collect::<Result<_, TryFromError>>()?;
+            Value::Map(ValueTupleList::new(entries))
+        }
+        tag => return Err(TryFromError::OtherError(format!("Unknown tag {tag:?}"))),
+    })
+}
+
+fn is_tag(key: &str) -> bool {
+    key.starts_with('$') && !key.starts_with("$$")
+}
+
+fn from_json(json: Json) -> Result<Value, TryFromError> {
+    Ok(match json {
+        Json::Null => Value::Null,
+        Json::Bool(v) => Value::Bool(v),
+        Json::Number(n) => {
+            if n.is_f64() {
+                Value::F64(n.as_f64().ok_or(TryFromError::FloatConversionError)?)
+            } else {
+                Value::I64(n.as_i64().ok_or(TryFromError::IntConversionError)?)
+            }
+        }
+        Json::String(v) => Value::String(v),
+        Json::Array(v) => Value::List(v.into_iter().map(from_json).collect::<Result<_, _>>()?),
+        Json::Object(map) => {
+            if map.len() == 1 && map.keys().all(|k| is_tag(k)) {
+                let (tag, value) = map.into_iter().next().unwrap();
+                return tagged_from_json(&tag, value);
+            }
+            let entries = map
+                .into_iter()
+                .map(|(k, v)| {
+                    let k = if is_tag(&k) {
+                        return Err(TryFromError::OtherError(format!(
+                            "Unexpected tag {k:?} in map"
+                        )));
+                    } else if let Some(k) = k.strip_prefix('$') {
+                        k.to_owned()
+                    } else {
+                        k
+                    };
+                    Ok((Value::String(k), from_json(v)?))
+                })
+                .collect::<Result<_, TryFromError>>()?;
+            Value::Map(ValueTupleList::new(entries))
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{
+        value, DartObject, ExternalBytes, FinalizableHandle, IsolateId, TryFromError, Value,
+    };
+
+    fn round_trip(value: Value) {
+        let json = value.to_json_string().unwrap();
+        assert_eq!(Value::from_json_str(&json).unwrap(), value, "{json}");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        round_trip(value!({
+            "null": null,
+            "bool": true,
+            "int": 10,
+            "float": 10.0,
+            "negative_zero": -0.0,
+            "string": "hello",
+            "$dollar": "$",
+            "$$double": 1,
+            "list": [1, 1.5, "x", [], {}],
+        }));
+        round_trip(Value::I8List(vec![-1, 2]));
+        round_trip(Value::U8List(vec![1, 255]));
+        round_trip(Value::I16List(vec![-1, 2]));
+        round_trip(Value::U16List(vec![1, 65535]));
+        round_trip(Value::I32List(vec![i32::MIN, i32::MAX]));
+        round_trip(Value::U32List(vec![u32::MAX]));
+        round_trip(Value::I64List(vec![i64::MIN, i64::MAX]));
+        round_trip(Value::U64List(vec![u64::MAX]));
+        round_trip(Value::F32List(vec![0.1, f32::INFINITY]));
+        round_trip(Value::F64List(vec![0.1, f64::NEG_INFINITY]));
+        round_trip(Value::F64(f64::INFINITY));
+        round_trip(Value::F64(f64::NEG_INFINITY));
+        round_trip(value!({ 1: "one", "two": 2, (Value::Null): [true] }));
+        round_trip(value!([{ (Value::F64(1.5)): { "$Map": null } }]));
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!(value!(1).to_json_string().unwrap(), "1");
+        assert_eq!(value!(1.0).to_json_string().unwrap(), "1.0");
+        assert_eq!(
+            Value::F64(f64::NAN).to_json_string().unwrap(),
+            r#"{"$F64":"NaN"}"#
+        );
+        assert_eq!(
+            Value::U8List(vec![1, 2]).to_json_string().unwrap(),
+            r#"{"$U8List":[1,2]}"#
+        );
+        assert_eq!(
+            value!({ "$a": 1 }).to_json_string().unwrap(),
+            r#"{"$$a":1}"#
+        );
+        assert_eq!(
+            value!({ 1: 2 }).to_json_string().unwrap(),
+            r#"{"$Map":[[1,2]]}"#
+        );
+        assert_eq!(
+            Value::External(ExternalBytes::new(vec![3u8]))
+                .to_json_string()
+                .unwrap(),
+            r#"{"$U8List":[3]}"#
+        );
+        match Value::from_json_str(r#"{"$F64": "NaN"}"#).unwrap() {
+            Value::F64(v) => assert!(v.is_nan()),
+            other => panic!("unexpected value {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let dart = Value::Dart(DartObject::Capability(
+            irondash_dart_ffi::raw::DartCObjectCapability { id: 1 },
+        ));
+        assert!(matches!(
+            value!([dart]).to_json_string(),
+            Err(TryFromError::OtherError(_))
+        ));
+        let handle =
+            Value::FinalizableHandle(Arc::new(FinalizableHandle::new(0, IsolateId(0), || {})));
+        assert!(matches!(
+            handle.to_json_string(),
+            Err(TryFromError::OtherError(_))
+        ));
+        assert!(Value::from_json_str("{").is_err());
+        assert!(Value::from_json_str(r#"{"$Unknown": 1}"#).is_err());
+        assert!(Value::from_json_str(r#"{"$U8List": [256]}"#).is_err());
+        assert!(Value::from_json_str(r#"{"$Map": [[1]]}"#).is_err());
+        assert!(Value::from_json_str(r#"{"$F64": 1, "a": 2}"#).is_err());
+        assert!(Value::from_json_str("18446744073709551615").is_err());
+        assert_eq!(
+            Value::from_json_str(r#"{"$U8List": 1}"#),
+            Err(TryFromError::UnexpectedType {
+                expected: "List",
+                actual: "I64",
+            })
+        );
+        assert_eq!(
+            Value::from_json_str(r#"{"$I32List": [1, "2"]}"#),
+            Err(TryFromError::UnexpectedType {
+                expected: "I64",
+                actual: "String",
+            })
+        );
+        assert_eq!(
+            Value::from_json_str(r#"{"$U64List": [-1]}"#),
+            Err(TryFromError::IntConversionError)
+        );
+        assert_eq!(
+            Value::from_json_str(r#"{"$F64": true}"#),
+            Err(TryFromError::UnexpectedType {
+                expected: "F64",
+                actual: "Bool",
+            })
+        );
+    }
+}
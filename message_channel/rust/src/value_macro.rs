@@ -0,0 +1,109 @@
+/// Constructs [`Value`](crate::Value) from a JSON-like literal.
+///
+/// ```
+/// # use irondash_message_channel::{value, Value};
+/// let name = "irondash";
+/// let v = value!({
+///     "name": name,
+///     "version": [0, 8],
+///     "ratio": 1.5,
+///     "license": null,
+///     10: { "nested": true },
+/// });
+/// assert_eq!(v.pointer("/version/1"), Some(&Value::I64(8)));
+/// ```
+///
+/// Any expression convertible into [`Value`](crate::Value) can be used as
+/// a value. Map keys must be a single token tree, i.e. a literal, an
+/// identifier or an expression in parentheses. Lists are always created as
+/// [`Value::List`](crate::Value::List), never as typed lists.
+#[macro_export]
+macro_rules! value {
+    ($($value:tt)+) => {
+        $crate::value_internal!($($value)+)
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! value_internal {
+    //
+    // List elements; accumulated in [...]
+    //
+    (@list [$($elems:expr,)*]) => {
+        ::std::vec![$($elems,)*]
+    };
+    (@list [$($elems:expr,)*] null $(, $($rest:tt)*)?) => {
+        $crate::value_internal!(@list [$($elems,)* $crate::Value::Null,] $($($rest)*)?)
+    };
+    (@list [$($elems:expr,)*] [$($list:tt)*] $(, $($rest:tt)*)?) => {
+        $crate::value_internal!(
+            @list [$($elems,)* $crate::value_internal!([$($list)*]),] $($($rest)*)?
+        )
+    };
+    (@list [$($elems:expr,)*] {$($map:tt)*} $(, $($rest:tt)*)?) => {
+        $crate::value_internal!(
+            @list [$($elems,)* $crate::value_internal!({$($map)*}),] $($($rest)*)?
+        )
+    };
+    (@list [$($elems:expr,)*] $next:expr $(, $($rest:tt)*)?) => {
+        $crate::value_internal!(
+            @list [$($elems,)* $crate::value_internal!($next),] $($($rest)*)?
+        )
+    };
+
+    //
+    // Map entries; accumulated in [...]
+    //
+    (@map [$($entries:expr,)*]) => {
+        ::std::vec![$($entries,)*]
+    };
+    (@map [$($entries:expr,)*] $key:tt : null $(, $($rest:tt)*)?) => {
+        $crate::value_internal!(
+            @map [$($entries,)* ($crate::Value::from($key), $crate::Value::Null),]
+            $($($rest)*)?
+        )
+    };
+    (@map [$($entries:expr,)*] $key:tt : [$($list:tt)*] $(, $($rest:tt)*)?) => {
+        $crate::value_internal!(
+            @map [
+                $($entries,)*
+                ($crate::Value::from($key), $crate::value_internal!([$($list)*])),
+            ]
+            $($($rest)*)?
+        )
+    };
+    (@map [$($entries:expr,)*] $key:tt : {$($map:tt)*} $(, $($rest:tt)*)?) => {
+        $crate::value_internal!(
+            @map [
+                $($entries,)*
+                ($crate::Value::from($key), $crate::value_internal!({$($map)*})),
+            ]
+            $($($rest)*)?
+        )
+    };
+    (@map [$($entries:expr,)*] $key:tt : $value:expr $(, $($rest:tt)*)?) => {
+        $crate::value_internal!(
+            @map [$($entries,)* ($crate::Value::from($key), $crate::Value::from($value)),]
+            $($($rest)*)?
+        )
+    };
+
+    //
+    // Entry points
+    //
+    (null) => {
+        $crate::Value::Null
+    };
+    ([$($list:tt)*]) => {
+        $crate::Value::List($crate::value_internal!(@list [] $($list)*))
+    };
+    ({$($map:tt)*}) => {
+        $crate::Value::Map($crate::ValueTupleList::new(
+            $crate::value_internal!(@map [] $($map)*)
+        ))
+    };
+    ($other:expr) => {
+        $crate::Value::from($other)
+    };
+}
@@ -0,0 +1,870 @@
+//! Serde support for [`Value`]. Enabled with the `serde` feature.
+//!
+//! Enums use the externally tagged representation (same as `serde_json`).
+//! Sequences of primitive numbers are serialized as typed lists, i.e.
+//! `Vec<u8>` becomes [`Value::U8List`], matching `From<Vec<T>>` for [`Value`].
+//! The list type is derived from the element type where it can be determined,
+//! so empty `Vec<u8>` still becomes [`Value::U8List`].
+
+use std::{any::type_name, cell::Cell, fmt::Display};
+
+use serde::{
+    de::{
+        self,
+        value::{MapDeserializer, SeqDeserializer},
+        DeserializeOwned, EnumAccess, IntoDeserializer, VariantAccess, Visitor,
+    },
+    forward_to_deserialize_any,
+    ser::{self, Serialize},
+    Deserializer,
+};
+
+use crate::{TryFromError, Value};
+
+/// Converts serializable type to [`Value`].
+pub fn to_value<T: Serialize + ?Sized>(value: &T) -> Result<Value, TryFromError> {
+    value.serialize(Serializer::new::<T>(None))
+}
+
+/// Converts [`Value`] to deserializable type.
+pub fn from_value<T: DeserializeOwned>(value: Value) -> Result<T, TryFromError> {
+    T::deserialize(value)
+}
+
+impl ser::Error for TryFromError {
+    fn custom<T: Display>(msg: T) -> Self {
+        TryFromError::OtherError(msg.to_string())
+    }
+}
+
+impl de::Error for TryFromError {
+    fn custom<T: Display>(msg: T) -> Self {
+        TryFromError::OtherError(msg.to_string())
+    }
+}
+
+//
+// Serialization
+//
+
+#[derive(Clone, Copy, PartialEq)]
+enum ElementKind {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    I64,
+    U64,
+    F32,
+    F64,
+    Other,
+}
+
+impl ElementKind {
+    fn from_primitive(name: &str) -> Option<Self> {
+        match name {
+            "i8" => Some(Self::I8),
+            "u8" => Some(Self::U8),
+            "i16" => Some(Self::I16),
+            "u16" => Some(Self::U16),
+            "i32" => Some(Self::I32),
+            "u32" => Some(Self::U32),
+            "i64" => Some(Self::I64),
+            "u64" => Some(Self::U64),
+            "f32" => Some(Self::F32),
+            "f64" => Some(Self::F64),
+            _ => None,
+        }
+    }
+
+    /// Element kind of common sequence types, based on the type name. This is
+    /// best effort; For unknown types the kind is inferred from elements.
+    fn from_sequence(name: &str) -> Option<Self> {
+        let mut name = name;
+        loop {
+            name = name.trim_start_matches('&');
+            match name.strip_prefix("mut ") {
+                Some(rest) => name = rest,
+                None => break,
+            }
+        }
+        if let Some(inner) = name.strip_prefix('[').and_then(|n| n.strip_suffix(']')) {
+            return Self::from_primitive(inner.split(';').next()?.trim());
+        }
+        let (path, inner) = name.strip_suffix('>')?.split_once('<')?;
+        match path {
+            "alloc::vec::Vec" | "alloc::collections::vec_deque::VecDeque" => {
+                Self::from_primitive(inner)
+            }
+            "alloc::boxed::Box" | "alloc::rc::Rc" | "alloc::sync::Arc" | "alloc::borrow::Cow" => {
+                Self::from_sequence(inner)
+            }
+            _ => None,
+        }
+    }
+}
+
+struct Serializer<'a> {
+    // When serializing sequence element, records the primitive type of the
+    // element so that the sequence can become a typed list.
+    element_kind: Option<&'a Cell<ElementKind>>,
+    // Name of the type being serialized.
+    type_name: &'static str,
+}
+
+impl<'a> Serializer<'a> {
+    fn new<T: ?Sized>(element_kind: Option<&'a Cell<ElementKind>>) -> Self {
+        Self {
+            element_kind,
+            type_name: type_name::<T>(),
+        }
+    }
+
+    fn primitive(self, kind: ElementKind, value: Value) -> Result<Value, TryFromError> {
+        if let Some(element_kind) = self.element_kind {
+            element_kind.set(kind);
+        }
+        Ok(value)
+    }
+}
+
+impl<'a> ser::Serializer for Serializer<'a> {
+    type Ok = Value;
+    type Error = TryFromError;
+
+    type SerializeSeq = SerializeSeq;
+    type SerializeTuple = SerializeSeq;
+    type SerializeTupleStruct = SerializeSeq;
+    type SerializeTupleVariant = SerializeVariant<SerializeSeq>;
+    type SerializeMap = SerializeMap;
+    type SerializeStruct = SerializeMap;
+    type SerializeStructVariant = SerializeVariant<SerializeMap>;
+
+    fn serialize_bool(self, v: bool) -> Result<Value, TryFromError> {
+        Ok(Value::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Value, TryFromError> {
+        self.primitive(ElementKind::I8, v.into())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Value, TryFromError> {
+        self.primitive(ElementKind::I16, v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Value, TryFromError> {
+        self.primitive(ElementKind::I32, v.into())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Value, TryFromError> {
+        self.primitive(ElementKind::I64, v.into())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Value, TryFromError> {
+        self.primitive(ElementKind::U8, v.into())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Value, TryFromError> {
+        self.primitive(ElementKind::U16, v.into())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Value, TryFromError> {
+        self.primitive(ElementKind::U32, v.into())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Value, TryFromError> {
+        match self.element_kind {
+            // Sequence element; Keep the bit pattern until the sequence knows
+            // whether it becomes U64List. See `SerializeSeq::large_u64`.
+            Some(element_kind) => {
+                element_kind.set(ElementKind::U64);
+                Ok(Value::I64(v as i64))
+            }
+            None => Ok(Value::I64(v.try_into()?)),
+        }
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Value, TryFromError> {
+        self.primitive(ElementKind::F32, v.into())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Value, TryFromError> {
+        self.primitive(ElementKind::F64, v.into())
+    }
+
+    fn serialize_char(self, v: char) -> Result<Value, TryFromError> {
+        Ok(Value::String(v.into()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Value, TryFromError> {
+        Ok(Value::String(v.into()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Value, TryFromError> {
+        Ok(Value::U8List(v.into()))
+    }
+
+    fn serialize_none(self) -> Result<Value, TryFromError> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Value, TryFromError> {
+        to_value(value)
+    }
+
+    fn serialize_unit(self) -> Result<Value, TryFromError> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Value, TryFromError> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Value, TryFromError> {
+        Ok(Value::String(variant.into()))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Value, TryFromError> {
+        to_value(value)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Value, TryFromError> {
+        Ok(Value::Map(
+            vec![(Value::String(variant.into()), to_value(value)?)].into(),
+        ))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeSeq, TryFromError> {
+        Ok(SerializeSeq::new(
+            len,
+            Some(ElementKind::from_sequence(self.type_name)),
+        ))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeSeq, TryFromError> {
+        Ok(SerializeSeq::new(Some(len), None))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SerializeSeq, TryFromError> {
+        Ok(SerializeSeq::new(Some(len), None))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, TryFromError> {
+        Ok(SerializeVariant {
+            variant,
+            inner: SerializeSeq::new(Some(len), None),
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<SerializeMap, TryFromError> {
+        Ok(SerializeMap::new(len))
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SerializeMap, TryFromError> {
+        Ok(SerializeMap::new(Some(len)))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, TryFromError> {
+        Ok(SerializeVariant {
+            variant,
+            inner: SerializeMap::new(Some(len)),
+        })
+    }
+}
+
+struct SerializeSeq {
+    values: Vec<Value>,
+    // Common kind of all elements, `None` if there are no elements yet.
+    kind: Option<ElementKind>,
+    // `None` for sequences that never become typed lists (i.e. tuples),
+    // `Some(None)` if the element type is not known upfront.
+    declared_kind: Option<Option<ElementKind>>,
+    // Whether there is u64 element that doesn't fit into i64. That is only
+    // allowed if the sequence becomes U64List.
+    large_u64: bool,
+}
+
+impl SerializeSeq {
+    fn new(len: Option<usize>, declared_kind: Option<Option<ElementKind>>) -> Self {
+        Self {
+            values: Vec::with_capacity(len.unwrap_or(0)),
+            kind: None,
+            declared_kind,
+            large_u64: false,
+        }
+    }
+
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), TryFromError> {
+        let element_kind = Cell::new(ElementKind::Other);
+        let value = value.serialize(Serializer::new::<T>(Some(&element_kind)))?;
+        let element_kind = element_kind.get();
+        if element_kind == ElementKind::U64 && matches!(value, Value::I64(v) if v < 0) {
+            self.large_u64 = true;
+        }
+        self.kind = match self.kind {
+            Some(kind) if kind != element_kind => Some(ElementKind::Other),
+            _ => Some(element_kind),
+        };
+        self.values.push(value);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Value, TryFromError> {
+        fn collect<T: TryFrom<Value, Error = TryFromError>>(
+            values: Vec<Value>,
+        ) -> Result<Vec<T>, TryFromError> {
+            values.into_iter().map(T::try_from).collect()
+        }
+        let kind = match self.declared_kind {
+            Some(declared) => declared.or(self.kind).unwrap_or(ElementKind::Other),
+            None => ElementKind::Other,
+        };
+        if self.large_u64 && kind != ElementKind::U64 {
+            return Err(TryFromError::IntConversionError);
+        }
+        let value = match kind {
+            ElementKind::I8 => Value::I8List(collect(self.values)?),
+            ElementKind::U8 => Value::U8List(collect(self.values)?),
+            ElementKind::I16 => Value::I16List(collect(self.values)?),
+            ElementKind::U16 => Value::U16List(collect(self.values)?),
+            ElementKind::I32 => Value::I32List(collect(self.values)?),
+            ElementKind::U32 => Value::U32List(collect(self.values)?),
+            ElementKind::I64 => Value::I64List(collect(self.values)?),
+            ElementKind::U64 => Value::U64List(
+                self.values
+                    .into_iter()
+                    .map(|v| match v {
+                        Value::I64(v) => Ok(v as u64),
+                        v => Err(TryFromError::unexpected_type("I64", &v)),
+                    })
+                    .collect::<Result<_, _>>()?,
+            ),
+            ElementKind::F32 => Value::F32List(
+                self.values
+                    .into_iter()
+                    .map(|v| match v {
+                        Value::F64(v) => Ok(v as f32),
+                        v => Err(TryFromError::unexpected_type("F64", &v)),
+                    })
+                    .collect::<Result<_, _>>()?,
+            ),
+            ElementKind::F64 => Value::F64List(collect(self.values)?),
+            ElementKind::Other => Value::List(self.values),
+        };
+        Ok(value)
+    }
+}
+
+impl ser::SerializeSeq for SerializeSeq {
+    type Ok = Value;
+    type Error = TryFromError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), TryFromError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, TryFromError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for SerializeSeq {
+    type Ok = Value;
+    type Error = TryFromError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), TryFromError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, TryFromError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeSeq {
+    type Ok = Value;
+    type Error = TryFromError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), TryFromError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, TryFromError> {
+        self.finish()
+    }
+}
+
+struct SerializeMap {
+    entries: Vec<(Value, Value)>,
+    next_key: Option<Value>,
+}
+
+impl SerializeMap {
+    fn new(len: Option<usize>) -> Self {
+        Self {
+            entries: Vec::with_capacity(len.unwrap_or(0)),
+            next_key: None,
+        }
+    }
+}
+
+impl ser::SerializeMap for SerializeMap {
+    type Ok = Value;
+    type Error = TryFromError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), TryFromError> {
+        self.next_key = Some(to_value(key)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), TryFromError> {
+        let key = self
+            .next_key
+            .take()
+            .ok_or_else(|| TryFromError::OtherError("serialize_value called before key".into()))?;
+        self.entries.push((key, to_value(value)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, TryFromError> {
+        Ok(Value::Map(self.entries.into()))
+    }
+}
+
+impl ser::SerializeStruct for SerializeMap {
+    type Ok = Value;
+    type Error = TryFromError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), TryFromError> {
+        self.entries.push((key.into(), to_value(value)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, TryFromError> {
+        Ok(Value::Map(self.entries.into()))
+    }
+}
+
+struct SerializeVariant<T> {
+    variant: &'static str,
+    inner: T,
+}
+
+impl<T> SerializeVariant<T> {
+    fn finish(variant: &'static str, value: Value) -> Value {
+        Value::Map(vec![(Value::String(variant.into()), value)].into())
+    }
+}
+
+impl ser::SerializeTupleVariant for SerializeVariant<SerializeSeq> {
+    type Ok = Value;
+    type Error = TryFromError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), TryFromError> {
+        self.inner.push(value)
+    }
+
+    fn end(self) -> Result<Value, TryFromError> {
+        Ok(Self::finish(self.variant, self.inner.finish()?))
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeVariant<SerializeMap> {
+    type Ok = Value;
+    type Error = TryFromError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), TryFromError> {
+        ser::SerializeStruct::serialize_field(&mut self.inner, key, value)
+    }
+
+    fn end(self) -> Result<Value, TryFromError> {
+        let value = ser::SerializeStruct::end(self.inner)?;
+        Ok(Self::finish(self.variant, value))
+    }
+}
+
+//
+// Deserialization
+//
+
+impl<'de> IntoDeserializer<'de, TryFromError> for Value {
+    type Deserializer = Value;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+fn visit_list<'de, V, T>(visitor: V, list: Vec<T>) -> Result<V::Value, TryFromError>
+where
+    V: Visitor<'de>,
+    T: IntoDeserializer<'de, TryFromError>,
+{
+    let mut deserializer = SeqDeserializer::new(list.into_iter());
+    let res = visitor.visit_seq(&mut deserializer)?;
+    deserializer.end()?;
+    Ok(res)
+}
+
+impl<'de> Deserializer<'de> for Value {
+    type Error = TryFromError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, TryFromError> {
+        match self {
+            Value::Null => visitor.visit_unit(),
+            Value::Bool(v) => visitor.visit_bool(v),
+            Value::I64(v) => visitor.visit_i64(v),
+            Value::F64(v) => visitor.visit_f64(v),
+            Value::String(v) => visitor.visit_string(v),
+            Value::I8List(v) => visit_list(visitor, v),
+            Value::U8List(v) => visit_list(visitor, v),
+            Value::I16List(v) => visit_list(visitor, v),
+            Value::U16List(v) => visit_list(visitor, v),
+            Value::I32List(v) => visit_list(visitor, v),
+            Value::U32List(v) => visit_list(visitor, v),
+            Value::I64List(v) => visit_list(visitor, v),
+            Value::U64List(v) => visit_list(visitor, v),
+            Value::F32List(v) => visit_list(visitor, v),
+            Value::F64List(v) => visit_list(visitor, v),
+            Value::External(v) => visit_list(visitor, v.to_vec()),
+            Value::List(v) => visit_list(visitor, v),
+            Value::Map(v) => {
+                let mut deserializer = MapDeserializer::new(v.into_iter());
+                let res = visitor.visit_map(&mut deserializer)?;
+                deserializer.end()?;
+                Ok(res)
+            }
+            value @ (Value::Dart(_) | Value::FinalizableHandle(_)) => {
+                Err(TryFromError::unexpected_type("serializable value", &value))
+            }
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, TryFromError> {
+        match self {
+            Value::Null => visitor.visit_none(),
+            value => visitor.visit_some(value),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, TryFromError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, TryFromError> {
+        match self {
+            Value::U8List(v) => visitor.visit_byte_buf(v),
+            Value::External(v) => visitor.visit_bytes(&v),
+            value => value.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, TryFromError> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, TryFromError> {
+        match self {
+            Value::String(variant) => visitor.visit_enum(EnumDeserializer {
+                variant,
+                value: None,
+            }),
+            Value::Map(map) => {
+                let mut iter = map.into_iter();
+                match (iter.next(), iter.next()) {
+                    (Some((Value::String(variant), value)), None) => {
+                        visitor.visit_enum(EnumDeserializer {
+                            variant,
+                            value: Some(value),
+                        })
+                    }
+                    _ => Err(TryFromError::OtherError(
+                        "expected map with single string key".into(),
+                    )),
+                }
+            }
+            value => Err(TryFromError::unexpected_type("String or Map", &value)),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        unit unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+struct EnumDeserializer {
+    variant: String,
+    value: Option<Value>,
+}
+
+impl<'de> EnumAccess<'de> for EnumDeserializer {
+    type Error = TryFromError;
+    type Variant = VariantDeserializer;
+
+    fn variant_seed<V: de::DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, VariantDeserializer), TryFromError> {
+        let variant = seed.deserialize(Value::String(self.variant))?;
+        Ok((variant, VariantDeserializer { value: self.value }))
+    }
+}
+
+struct VariantDeserializer {
+    value: Option<Value>,
+}
+
+impl<'de> VariantAccess<'de> for VariantDeserializer {
+    type Error = TryFromError;
+
+    fn unit_variant(self) -> Result<(), TryFromError> {
+        match self.value {
+            None | Some(Value::Null) => Ok(()),
+            Some(value) => Err(TryFromError::unexpected_type("Null", &value)),
+        }
+    }
+
+    fn newtype_variant_seed<T: de::DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, TryFromError> {
+        seed.deserialize(self.value.unwrap_or_default())
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, TryFromError> {
+        match self.value {
+            Some(value) => value.deserialize_seq(visitor),
+            None => Err(TryFromError::unexpected_type("List", &Value::Null)),
+        }
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, TryFromError> {
+        match self.value {
+            Some(value) => value.deserialize_map(visitor),
+            None => Err(TryFromError::unexpected_type("Map", &Value::Null)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde::{Deserialize, Serialize};
+
+    use super::{from_value, to_value};
+    use crate::{TryFromError, Value};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Struct {
+        name: String,
+        count: i32,
+        optional: Option<f64>,
+        bytes: Vec<u8>,
+        floats: Vec<f32>,
+        strings: Vec<String>,
+        tuple: (i64, bool),
+        map: HashMap<String, i64>,
+        nested: Vec<Enum>,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum Enum {
+        Unit,
+        Newtype(i64),
+        Tuple(i64, String),
+        Struct { value: u16 },
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let value = Struct {
+            name: "name".into(),
+            count: 10,
+            optional: None,
+            bytes: vec![1, 2, 3],
+            floats: vec![1.5, 2.5],
+            strings: vec!["a".into(), "b".into()],
+            tuple: (5, true),
+            map: HashMap::from([("key".into(), 1)]),
+            nested: vec![
+                Enum::Unit,
+                Enum::Newtype(1),
+                Enum::Tuple(2, "x".into()),
+                Enum::Struct { value: 3 },
+            ],
+        };
+        let serialized = to_value(&value).unwrap();
+        let deserialized: Struct = from_value(serialized).unwrap();
+        assert_eq!(deserialized, value);
+    }
+
+    #[test]
+    fn test_typed_lists() {
+        assert_eq!(to_value(&vec![1u8, 2]).unwrap(), Value::U8List(vec![1, 2]));
+        assert_eq!(to_value(&vec![1i8, 2]).unwrap(), Value::I8List(vec![1, 2]));
+        assert_eq!(
+            to_value(&vec![1u16, 2]).unwrap(),
+            Value::U16List(vec![1, 2])
+        );
+        assert_eq!(
+            to_value(&vec![1i32, 2]).unwrap(),
+            Value::I32List(vec![1, 2])
+        );
+        assert_eq!(
+            to_value(&vec![1i64, 2]).unwrap(),
+            Value::I64List(vec![1, 2])
+        );
+        assert_eq!(
+            to_value(&vec![1.5f32, 2.0]).unwrap(),
+            Value::F32List(vec![1.5, 2.0])
+        );
+        assert_eq!(
+            to_value(&vec![1.5f64, 2.0]).unwrap(),
+            Value::F64List(vec![1.5, 2.0])
+        );
+        // Same as `From<Vec<T>>`
+        assert_eq!(to_value(&vec![1u8, 2]).unwrap(), Value::from(vec![1u8, 2]));
+        // Tuples and non-primitive elements are regular lists
+        assert_eq!(
+            to_value(&(1u8, 2u8)).unwrap(),
+            Value::List(vec![1.into(), 2.into()])
+        );
+        assert_eq!(
+            to_value(&vec![Some(1u8)]).unwrap(),
+            Value::List(vec![1.into()])
+        );
+        // List type is taken from element type even without elements
+        assert_eq!(
+            to_value(&Vec::<u8>::new()).unwrap(),
+            Value::U8List(Vec::new())
+        );
+        assert_eq!(
+            to_value(&Vec::<f32>::new()).unwrap(),
+            Value::F32List(Vec::new())
+        );
+        assert_eq!(
+            to_value(&[0i16; 0][..]).unwrap(),
+            Value::I16List(Vec::new())
+        );
+        assert_eq!(
+            to_value(&Vec::<String>::new()).unwrap(),
+            Value::List(Vec::new())
+        );
+
+        let list: Vec<u8> = from_value(Value::U8List(vec![1, 2])).unwrap();
+        assert_eq!(list, vec![1, 2]);
+        let list: Vec<i64> = from_value(Value::List(vec![1.into(), 2.into()])).unwrap();
+        assert_eq!(list, vec![1, 2]);
+        let list: Vec<f32> = from_value(Value::F32List(vec![1.5])).unwrap();
+        assert_eq!(list, vec![1.5]);
+    }
+
+    #[test]
+    fn test_u64_list() {
+        let list = vec![1u64, u64::MAX];
+        let value = to_value(&list).unwrap();
+        assert_eq!(value, Value::U64List(vec![1, u64::MAX]));
+        let deserialized: Vec<u64> = from_value(value).unwrap();
+        assert_eq!(deserialized, list);
+        assert_eq!(
+            to_value(&Vec::<u64>::new()).unwrap(),
+            Value::U64List(Vec::new())
+        );
+        // Small values outside of sequence are still I64
+        assert_eq!(to_value(&5u64).unwrap(), Value::I64(5));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(from_value::<String>(Value::I64(10)).is_err());
+        assert!(from_value::<u8>(Value::I64(1000)).is_err());
+        assert!(from_value::<Enum>(Value::String("Missing".into())).is_err());
+        assert!(to_value(&u64::MAX).is_err());
+        assert!(to_value(&(u64::MAX, 1u8)).is_err());
+        assert_eq!(
+            from_value::<Enum>(Value::I64(1)),
+            Err(TryFromError::UnexpectedType {
+                expected: "String or Map",
+                actual: "I64",
+            })
+        );
+        assert_eq!(
+            from_value::<Enum>(Value::String("Tuple".into())),
+            Err(TryFromError::UnexpectedType {
+                expected: "List",
+                actual: "Null",
+            })
+        );
+        assert_eq!(
+            from_value::<Enum>(Value::Map(vec![("Unit".into(), 1.into())].into())),
+            Err(TryFromError::UnexpectedType {
+                expected: "Null",
+                actual: "I64",
+            })
+        );
+    }
+}
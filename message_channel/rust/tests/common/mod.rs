@@ -0,0 +1,77 @@
+//! Fixtures shared by integration tests that run against the mock transport.
+
+// Not every test uses every fixture.
+#![allow(dead_code)]
+
+use std::cell::RefCell;
+
+use async_trait::async_trait;
+use irondash_message_channel::{
+    AsyncMethodHandler, AsyncMethodInvoker, IsolateId, Late, MethodCall, MethodCallError,
+    PlatformError, PlatformResult, Value,
+};
+
+/// Platform error with given code and no message or detail.
+pub fn platform_error(code: &str) -> PlatformError {
+    PlatformError {
+        code: code.into(),
+        message: None,
+        detail: Value::Null,
+    }
+}
+
+/// Returns code of the platform error; Panics for any other result.
+pub fn error_code(result: Result<Value, MethodCallError>) -> String {
+    match result {
+        Err(MethodCallError::PlatformError(err)) => err.code,
+        other => panic!("unexpected result {other:?}"),
+    }
+}
+
+/// Handles `add` (sums list of integers) and `call_back` (calls `multiply`
+/// on the calling isolate and returns the result). Other methods fail with
+/// `invalid_method`.
+pub struct Calculator {
+    pub invoker: Late<AsyncMethodInvoker>,
+    pub destroyed_isolates: RefCell<Vec<IsolateId>>,
+}
+
+impl Default for Calculator {
+    fn default() -> Self {
+        Self {
+            invoker: Late::new(),
+            destroyed_isolates: RefCell::new(Vec::new()),
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl AsyncMethodHandler for Calculator {
+    async fn on_method_call(&self, call: MethodCall) -> PlatformResult {
+        match call.method.as_str() {
+            "add" => {
+                let args: Vec<i64> = call.args.try_into()?;
+                Ok(args.iter().sum::<i64>().into())
+            }
+            "call_back" => {
+                let res = self
+                    .invoker
+                    .call_method(call.isolate, "multiply", call.args)
+                    .await;
+                res.map_err(|err| PlatformError {
+                    message: Some(err.to_string()),
+                    ..platform_error("call_back_failed")
+                })
+            }
+            _ => Err(platform_error("invalid_method")),
+        }
+    }
+
+    fn assign_invoker(&self, invoker: AsyncMethodInvoker) {
+        self.invoker.set(invoker);
+    }
+
+    fn on_isolate_destroyed(&self, isolate: IsolateId) {
+        self.destroyed_isolates.borrow_mut().push(isolate);
+    }
+}
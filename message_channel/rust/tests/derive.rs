@@ -1,8 +1,12 @@
 #[cfg(feature = "irondash_message_channel_derive")]
 mod tests {
-    use std::collections::HashMap;
+    use std::{
+        borrow::Cow,
+        collections::{BTreeMap, BTreeSet, HashMap},
+        net::Ipv4Addr,
+    };
 
-    use irondash_message_channel::{TryFromError, Value};
+    use irondash_message_channel::{PlatformError, TryFromError, Value};
     use irondash_message_channel_derive::{IntoValue, TryFromValue};
 
     #[derive(PartialEq, Debug, Clone)]
@@ -650,4 +654,762 @@ mod tests {
         }
         Ok(())
     }
+
+    #[derive(Clone, PartialEq, Debug, IntoValue, TryFromValue)]
+    #[irondash(rename_all = "camelCase")]
+    struct FlattenHeader {
+        request_id: i64,
+        #[irondash(skip_if_empty)]
+        trace: Option<String>,
+    }
+
+    #[derive(Clone, PartialEq, Debug, IntoValue, TryFromValue)]
+    #[irondash(tag = "kind")]
+    enum FlattenPayload {
+        Ping,
+        Data { bytes: Vec<u8> },
+    }
+
+    #[derive(Clone, PartialEq, Debug, IntoValue, TryFromValue)]
+    struct FlattenMessage {
+        #[irondash(flatten)]
+        header: FlattenHeader,
+        #[irondash(flatten)]
+        payload: FlattenPayload,
+        extra: i64,
+    }
+
+    #[derive(Clone, PartialEq, Debug, IntoValue, TryFromValue)]
+    #[irondash(tag = "t")]
+    enum FlattenInVariant {
+        Request {
+            #[irondash(flatten)]
+            header: FlattenHeader,
+            #[irondash(flatten)]
+            rest: HashMap<String, Value>,
+        },
+    }
+
+    #[derive(Clone, PartialEq, Debug, IntoValue, TryFromValue)]
+    struct FlattenOptional {
+        #[irondash(flatten)]
+        header: Option<FlattenHeader>,
+        extra: i64,
+    }
+
+    #[test]
+    fn test_flatten() -> Result<(), TryFromError> {
+        {
+            let v1 = FlattenMessage {
+                header: FlattenHeader {
+                    request_id: 10,
+                    trace: None,
+                },
+                payload: FlattenPayload::Data {
+                    bytes: vec![1, 2, 3],
+                },
+                extra: 5,
+            };
+            let sv1: Value = v1.clone().into();
+            assert_eq!(
+                sv1,
+                Value::Map(
+                    vec![
+                        ("requestId".into(), 10.into()),
+                        ("kind".into(), "Data".into()),
+                        ("bytes".into(), vec![1u8, 2, 3].into()),
+                        ("extra".into(), 5.into()),
+                    ]
+                    .into()
+                ),
+            );
+            let v1d: FlattenMessage = sv1.try_into()?;
+            assert_eq!(v1d, v1);
+        }
+        {
+            let v1 = FlattenMessage {
+                header: FlattenHeader {
+                    request_id: 10,
+                    trace: Some("trace".into()),
+                },
+                payload: FlattenPayload::Ping,
+                extra: 5,
+            };
+            let sv1: Value = v1.clone().into();
+            let v1d: FlattenMessage = sv1.try_into()?;
+            assert_eq!(v1d, v1);
+        }
+        {
+            // Tag of the enclosing enum must not be passed to flattened fields.
+            let sv1 = Value::Map(
+                vec![
+                    ("t".into(), "Request".into()),
+                    ("requestId".into(), 1.into()),
+                    ("other".into(), "value".into()),
+                ]
+                .into(),
+            );
+            let v1d: FlattenInVariant = sv1.clone().try_into()?;
+            let mut rest = HashMap::new();
+            rest.insert("requestId".to_owned(), Value::from(1));
+            rest.insert("other".to_owned(), Value::from("value"));
+            assert_eq!(
+                v1d,
+                FlattenInVariant::Request {
+                    header: FlattenHeader {
+                        request_id: 1,
+                        trace: None,
+                    },
+                    rest,
+                }
+            );
+        }
+        {
+            let sv1 = Value::Map(vec![("extra".into(), 5.into())].into());
+            let res: Result<FlattenMessage, _> = sv1.try_into();
+            assert!(res.is_err());
+        }
+        {
+            // Flattened `None` contributes no entries.
+            let v1 = FlattenOptional {
+                header: None,
+                extra: 5,
+            };
+            let sv1: Value = v1.clone().into();
+            assert_eq!(sv1, Value::Map(vec![("extra".into(), 5.into())].into()));
+            let v1d: FlattenOptional = sv1.try_into()?;
+            assert_eq!(v1d, v1);
+
+            let v2 = FlattenOptional {
+                header: Some(FlattenHeader {
+                    request_id: 1,
+                    trace: None,
+                }),
+                extra: 5,
+            };
+            let sv2: Value = v2.clone().into();
+            assert_eq!(
+                sv2,
+                Value::Map(vec![("requestId".into(), 1.into()), ("extra".into(), 5.into())].into())
+            );
+            let v2d: FlattenOptional = sv2.try_into()?;
+            assert_eq!(v2d, v2);
+        }
+        Ok(())
+    }
+
+    mod ipv4_value {
+        use std::net::Ipv4Addr;
+
+        use irondash_message_channel::{TryFromError, Value};
+
+        pub fn to_value(addr: Ipv4Addr) -> Value {
+            addr.to_string().into()
+        }
+
+        pub fn try_from_value(value: Value) -> Result<Ipv4Addr, TryFromError> {
+            let string: String = value.try_into()?;
+            string
+                .parse()
+                .map_err(|e| TryFromError::OtherError(format!("{e}")))
+        }
+    }
+
+    mod optional_ipv4_value {
+        use std::net::Ipv4Addr;
+
+        use irondash_message_channel::{TryFromError, Value};
+
+        pub fn to_value(addr: Option<Ipv4Addr>) -> Value {
+            addr.map(super::ipv4_value::to_value).into()
+        }
+
+        pub fn try_from_value(value: Value) -> Result<Option<Ipv4Addr>, TryFromError> {
+            match value {
+                Value::Null => Ok(None),
+                value => super::ipv4_value::try_from_value(value).map(Some),
+            }
+        }
+    }
+
+    #[derive(Clone, PartialEq, Debug, IntoValue, TryFromValue)]
+    #[irondash(rename_all = "camelCase")]
+    struct StructWith {
+        #[irondash(with = "ipv4_value", rename = "address")]
+        local_address: Ipv4Addr,
+        #[irondash(with = "optional_ipv4_value", skip_if_empty)]
+        gateway: Option<Ipv4Addr>,
+        #[irondash(with = "optional_ipv4_value")]
+        dns_server: Option<Ipv4Addr>,
+    }
+
+    #[derive(Clone, PartialEq, Debug, IntoValue, TryFromValue)]
+    #[irondash(into = "String", try_from = "String")]
+    struct Email(String);
+
+    impl From<Email> for String {
+        fn from(email: Email) -> Self {
+            email.0
+        }
+    }
+
+    impl TryFrom<String> for Email {
+        type Error = String;
+
+        fn try_from(value: String) -> Result<Self, Self::Error> {
+            if value.contains('@') {
+                Ok(Email(value))
+            } else {
+                Err(format!("invalid email {value}"))
+            }
+        }
+    }
+
+    #[derive(Clone, PartialEq, Debug, IntoValue, TryFromValue)]
+    #[irondash(from = "i64", into = "i64")]
+    enum Priority {
+        Low,
+        High,
+    }
+
+    impl From<i64> for Priority {
+        fn from(value: i64) -> Self {
+            if value > 0 {
+                Priority::High
+            } else {
+                Priority::Low
+            }
+        }
+    }
+
+    impl From<Priority> for i64 {
+        fn from(priority: Priority) -> Self {
+            match priority {
+                Priority::Low => 0,
+                Priority::High => 1,
+            }
+        }
+    }
+
+    #[derive(Clone, PartialEq, Debug, IntoValue, TryFromValue)]
+    struct StructWithConversions {
+        email: Email,
+        priority: Priority,
+    }
+
+    #[test]
+    fn test_with() -> Result<(), TryFromError> {
+        {
+            let v1 = StructWith {
+                local_address: Ipv4Addr::new(192, 168, 1, 10),
+                gateway: None,
+                dns_server: None,
+            };
+            let sv1: Value = v1.clone().into();
+            assert_eq!(
+                sv1,
+                Value::Map(
+                    vec![
+                        ("address".into(), "192.168.1.10".into()),
+                        ("dnsServer".into(), Value::Null),
+                    ]
+                    .into()
+                ),
+            );
+            let v1d: StructWith = sv1.try_into()?;
+            assert_eq!(v1d, v1);
+        }
+        {
+            let v1 = StructWith {
+                local_address: Ipv4Addr::new(192, 168, 1, 10),
+                gateway: Some(Ipv4Addr::new(192, 168, 1, 1)),
+                dns_server: Some(Ipv4Addr::new(8, 8, 8, 8)),
+            };
+            let sv1: Value = v1.clone().into();
+            let v1d: StructWith = sv1.try_into()?;
+            assert_eq!(v1d, v1);
+        }
+        {
+            // Missing optional fields
+            let sv1 = Value::Map(vec![("address".into(), "10.0.0.1".into())].into());
+            let v1d: StructWith = sv1.try_into()?;
+            assert_eq!(
+                v1d,
+                StructWith {
+                    local_address: Ipv4Addr::new(10, 0, 0, 1),
+                    gateway: None,
+                    dns_server: None,
+                }
+            );
+        }
+        {
+            let sv1 = Value::Map(vec![("address".into(), "invalid".into())].into());
+            let res: Result<StructWith, _> = sv1.try_into();
+            assert!(res.is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_container_conversions() -> Result<(), TryFromError> {
+        {
+            let v1 = StructWithConversions {
+                email: Email("a@b.c".into()),
+                priority: Priority::High,
+            };
+            let sv1: Value = v1.clone().into();
+            assert_eq!(
+                sv1,
+                Value::Map(
+                    vec![
+                        ("email".into(), "a@b.c".into()),
+                        ("priority".into(), 1.into()),
+                    ]
+                    .into()
+                ),
+            );
+            let v1d: StructWithConversions = sv1.try_into()?;
+            assert_eq!(v1d, v1);
+        }
+        {
+            let res: Result<Email, _> = Value::from("abc").try_into();
+            assert_eq!(
+                res,
+                Err(TryFromError::OtherError("invalid email abc".into()))
+            );
+            let res: Result<Email, _> = Value::from(10).try_into();
+            assert_eq!(
+                res,
+                Err(TryFromError::UnexpectedType {
+                    expected: "String",
+                    actual: "I64"
+                })
+            );
+        }
+        Ok(())
+    }
+
+    #[derive(Clone, PartialEq, Debug, IntoValue, TryFromValue)]
+    #[irondash(untagged)]
+    enum Untagged {
+        None,
+        Int(i64),
+        Point(f64, f64),
+        Named {
+            name: String,
+        },
+        #[irondash(rename_all = "camelCase")]
+        Full {
+            first_name: String,
+            last_name: String,
+        },
+        Text(String),
+    }
+
+    #[test]
+    fn test_untagged() -> Result<(), TryFromError> {
+        let values = vec![
+            (Untagged::None, Value::Null),
+            (Untagged::Int(10), Value::I64(10)),
+            (
+                Untagged::Point(1.0, 2.0),
+                Value::List(vec![1.0.into(), 2.0.into()]),
+            ),
+            (
+                Untagged::Named { name: "A".into() },
+                Value::Map(vec![("name".into(), "A".into())].into()),
+            ),
+            (Untagged::Text("B".into()), "B".into()),
+        ];
+        for (v1, expected) in values {
+            let sv1: Value = v1.clone().into();
+            assert_eq!(sv1, expected);
+            let v1d: Untagged = sv1.try_into()?;
+            assert_eq!(v1d, v1);
+        }
+        {
+            // Variants are tried in order; Named is the first one that
+            // succeeds because unknown keys are ignored.
+            let sv1 = Value::Map(
+                vec![
+                    ("name".into(), "A".into()),
+                    ("firstName".into(), "B".into()),
+                ]
+                .into(),
+            );
+            let v1d: Untagged = sv1.try_into()?;
+            assert_eq!(v1d, Untagged::Named { name: "A".into() });
+
+            let sv1 = Value::Map(
+                vec![
+                    ("firstName".into(), "A".into()),
+                    ("lastName".into(), "B".into()),
+                ]
+                .into(),
+            );
+            let v1d: Untagged = sv1.try_into()?;
+            assert_eq!(
+                v1d,
+                Untagged::Full {
+                    first_name: "A".into(),
+                    last_name: "B".into()
+                }
+            );
+        }
+        {
+            // Error of the last variant is reported.
+            let res: Result<Untagged, _> = Value::Bool(true).try_into();
+            assert_eq!(
+                res,
+                Err(TryFromError::UnexpectedType {
+                    expected: "String",
+                    actual: "Bool",
+                })
+            );
+        }
+        Ok(())
+    }
+
+    #[derive(Clone, PartialEq, Debug, IntoValue, TryFromValue)]
+    #[irondash(untagged)]
+    enum UntaggedTrailingUnit {
+        Int(i64),
+        Empty,
+    }
+
+    #[test]
+    fn test_untagged_trailing_unit() -> Result<(), TryFromError> {
+        let v: UntaggedTrailingUnit = Value::I64(3).try_into()?;
+        assert_eq!(v, UntaggedTrailingUnit::Int(3));
+        let v: UntaggedTrailingUnit = Value::Null.try_into()?;
+        assert_eq!(v, UntaggedTrailingUnit::Empty);
+        let res: Result<UntaggedTrailingUnit, _> = Value::Bool(true).try_into();
+        assert!(res.is_err());
+        Ok(())
+    }
+
+    #[derive(Clone, Copy, PartialEq, Debug, IntoValue, TryFromValue)]
+    #[irondash(repr = "int")]
+    enum Mode {
+        A = 1,
+        B = 5,
+        C,
+    }
+
+    #[derive(Clone, Copy, PartialEq, Debug, IntoValue, TryFromValue)]
+    #[irondash(repr = "int")]
+    enum Index {
+        First,
+        Second,
+    }
+
+    #[test]
+    fn test_repr_int() -> Result<(), TryFromError> {
+        for (v1, expected) in [(Mode::A, 1), (Mode::B, 5), (Mode::C, 6)] {
+            let sv1: Value = v1.into();
+            assert_eq!(sv1, Value::I64(expected));
+            let v1d: Mode = sv1.try_into()?;
+            assert_eq!(v1d, v1);
+        }
+        let v1d: Index = Value::I64(1).try_into()?;
+        assert_eq!(v1d, Index::Second);
+        let res: Result<Mode, _> = Value::I64(2).try_into();
+        assert_eq!(
+            res,
+            Err(TryFromError::OtherError("unknown enum value 2".into()))
+        );
+        let res: Result<Mode, _> = Value::from("A").try_into();
+        assert_eq!(
+            res,
+            Err(TryFromError::UnexpectedType {
+                expected: "I64",
+                actual: "String"
+            })
+        );
+        Ok(())
+    }
+
+    #[derive(Clone, PartialEq, Debug, IntoValue, TryFromValue)]
+    struct PathItem {
+        price: f64,
+    }
+
+    #[derive(Clone, PartialEq, Debug, IntoValue, TryFromValue)]
+    struct PathRequest {
+        items: Vec<PathItem>,
+        shape: Option<Enum3CustomTagContent>,
+    }
+
+    #[derive(Clone, PartialEq, Debug, IntoValue, TryFromValue)]
+    struct PathMessage {
+        request: PathRequest,
+        pair: Tuple1,
+    }
+
+    #[test]
+    fn test_error_path() {
+        let item = |price: Value| Value::Map(vec![("price".into(), price)].into());
+        let message = |items: Vec<Value>, shape: Value, pair: Value| {
+            Value::Map(
+                vec![
+                    (
+                        "request".into(),
+                        Value::Map(
+                            vec![
+                                ("items".into(), Value::List(items)),
+                                ("shape".into(), shape),
+                            ]
+                            .into(),
+                        ),
+                    ),
+                    ("pair".into(), pair),
+                ]
+                .into(),
+            )
+        };
+        let pair = Value::List(vec![1.into(), Value::Null, Value::Null]);
+        let items = vec![
+            item(1.0.into()),
+            item(2.0.into()),
+            item(3.0.into()),
+            item("4".into()),
+        ];
+        let res: Result<PathMessage, _> = message(items, Value::Null, pair.clone()).try_into();
+        let err = res.unwrap_err();
+        assert_eq!(err.path().unwrap().to_string(), "request.items[3].price");
+        assert_eq!(
+            err.root_cause(),
+            &TryFromError::UnexpectedType {
+                expected: "F64",
+                actual: "String"
+            }
+        );
+        let platform_error = PlatformError::from(err);
+        assert_eq!(
+            platform_error.message.as_deref(),
+            Some("request.items[3].price: Expected F64, got String.")
+        );
+        assert_eq!(platform_error.detail, "request.items[3].price".into());
+
+        let shape = Value::Map(
+            vec![
+                ("t".into(), "XYZ".into()),
+                (
+                    "c".into(),
+                    Value::Map(vec![("x".into(), "1".into())].into()),
+                ),
+            ]
+            .into(),
+        );
+        let res: Result<PathMessage, _> = message(vec![], shape, pair).try_into();
+        assert_eq!(
+            res.unwrap_err().path().unwrap().to_string(),
+            "request.shape.c.x"
+        );
+
+        let pair = Value::List(vec![1.into(), 2.into(), Value::Null]);
+        let res: Result<PathMessage, _> = message(vec![], Value::Null, pair).try_into();
+        assert_eq!(res.unwrap_err().path().unwrap().to_string(), "pair[1]");
+    }
+
+    #[derive(Clone, PartialEq, Debug, IntoValue, TryFromValue)]
+    #[irondash(deny_unknown_fields, rename_all = "camelCase")]
+    struct StrictStruct {
+        #[irondash(alias = "name", alias = "title")]
+        display_name: String,
+        value: Option<i64>,
+    }
+
+    #[derive(Clone, PartialEq, Debug, IntoValue, TryFromValue)]
+    #[irondash(tag = "t", deny_unknown_fields)]
+    enum StrictEnum {
+        A { x: i64 },
+    }
+
+    #[test]
+    fn test_deny_unknown_fields() -> Result<(), TryFromError> {
+        {
+            let v1 = StrictStruct {
+                display_name: "Name".into(),
+                value: None,
+            };
+            let sv1: Value = v1.clone().into();
+            let v1d: StrictStruct = sv1.try_into()?;
+            assert_eq!(v1d, v1);
+        }
+        for key in ["name", "title"] {
+            let sv1 = Value::Map(vec![(key.into(), "Name".into())].into());
+            let v1d: StrictStruct = sv1.try_into()?;
+            assert_eq!(
+                v1d,
+                StrictStruct {
+                    display_name: "Name".into(),
+                    value: None,
+                }
+            );
+        }
+        {
+            let sv1 = Value::Map(
+                vec![
+                    ("displayName".into(), "Name".into()),
+                    ("valu".into(), 10.into()),
+                ]
+                .into(),
+            );
+            let res: Result<StrictStruct, _> = sv1.try_into();
+            let err = res.unwrap_err();
+            assert_eq!(err.path().unwrap().to_string(), "valu");
+            assert_eq!(
+                err.root_cause(),
+                &TryFromError::OtherError("unknown field \"valu\"".into())
+            );
+        }
+        {
+            // Field present under both name and alias.
+            let sv1 = Value::Map(
+                vec![
+                    ("displayName".into(), "Name".into()),
+                    ("title".into(), "Title".into()),
+                ]
+                .into(),
+            );
+            let res: Result<StrictStruct, _> = sv1.try_into();
+            let err = res.unwrap_err();
+            assert_eq!(err.path().unwrap().to_string(), "title");
+            assert_eq!(
+                err.root_cause(),
+                &TryFromError::OtherError("duplicate field \"displayName\"".into())
+            );
+        }
+        {
+            // Tag is not an unknown field.
+            let sv1 = Value::Map(vec![("t".into(), "A".into()), ("x".into(), 1.into())].into());
+            let v1d: StrictEnum = sv1.try_into()?;
+            assert_eq!(v1d, StrictEnum::A { x: 1 });
+
+            let sv1 = Value::Map(
+                vec![
+                    ("t".into(), "A".into()),
+                    ("x".into(), 1.into()),
+                    ("y".into(), 1.into()),
+                ]
+                .into(),
+            );
+            let res: Result<StrictEnum, _> = sv1.try_into();
+            assert!(res.is_err());
+        }
+        Ok(())
+    }
+
+    #[derive(Clone, PartialEq, Debug, IntoValue, TryFromValue)]
+    #[irondash(boxed)]
+    struct StdTypes {
+        offset: isize,
+        letter: char,
+        label: Cow<'static, str>,
+        pair: (i64, String),
+        rgb: [u8; 3],
+        tags: BTreeSet<String>,
+        sorted: BTreeMap<String, i64>,
+        #[irondash(skip_if_empty)]
+        patch: Option<Option<i64>>,
+        next: Option<Box<StdTypes>>,
+    }
+
+    #[test]
+    fn test_std_types() -> Result<(), TryFromError> {
+        let v1 = StdTypes {
+            offset: -3,
+            letter: 'x',
+            label: "label".into(),
+            pair: (4, "four".into()),
+            rgb: [1, 2, 3],
+            tags: ["a".to_owned()].into(),
+            sorted: [("b".to_owned(), 5)].into(),
+            patch: Some(None),
+            next: Some(Box::new(StdTypes {
+                offset: 8,
+                letter: 'y',
+                label: Cow::Borrowed("inner"),
+                pair: (9, "nine".into()),
+                rgb: [0; 3],
+                tags: BTreeSet::new(),
+                sorted: BTreeMap::new(),
+                patch: None,
+                next: None,
+            })),
+        };
+        let sv1: Value = v1.clone().into();
+        let map = sv1.as_map().unwrap();
+        assert_eq!(
+            map.get(&"pair".into()),
+            Some(&Value::List(vec![4.into(), "four".into()]))
+        );
+        assert_eq!(map.get(&"rgb".into()), Some(&Value::U8List(vec![1, 2, 3])));
+        assert_eq!(map.get(&"patch".into()), Some(&Value::Null));
+        let v1d: StdTypes = sv1.try_into()?;
+        assert_eq!(v1d, v1);
+
+        // Missing `Option<Option<T>>` field is `None`, null is `Some(None)`.
+        let inner = v1.next.unwrap();
+        let sv2: Value = (*inner).clone().into();
+        assert_eq!(sv2.as_map().unwrap().get(&"patch".into()), None);
+        let v2d: StdTypes = sv2.try_into()?;
+        assert_eq!(v2d.patch, None);
+
+        let mut v3 = *inner;
+        v3.patch = Some(Some(10));
+        let v3d: StdTypes = Value::from(v3.clone()).try_into()?;
+        assert_eq!(v3d.patch, Some(Some(10)));
+        Ok(())
+    }
+
+    // Converting `u64` and `usize` into `Value` can fail, which makes the
+    // derived conversion `TryFrom`.
+    #[derive(Clone, PartialEq, Debug, IntoValue, TryFromValue)]
+    struct Unsigned {
+        #[irondash(try_into)]
+        count: u64,
+        #[irondash(try_into, rename = "i")]
+        index: usize,
+    }
+
+    #[derive(Clone, PartialEq, Debug, IntoValue, TryFromValue)]
+    struct UnsignedTuple(#[irondash(try_into)] u64, String);
+
+    #[test]
+    fn test_unsigned() -> Result<(), TryFromError> {
+        let v1 = Unsigned { count: 1, index: 2 };
+        let sv1 = Value::try_from(v1.clone())?;
+        assert_eq!(
+            sv1,
+            Value::Map(vec![("count".into(), 1.into()), ("i".into(), 2.into())].into())
+        );
+        let v1d: Unsigned = sv1.try_into()?;
+        assert_eq!(v1d, v1);
+
+        let sv1 = Value::Map(vec![("count".into(), (-1).into()), ("i".into(), 2.into())].into());
+        let res: Result<Unsigned, _> = sv1.try_into();
+        let err = res.unwrap_err();
+        assert_eq!(err.path().unwrap().to_string(), "count");
+        assert_eq!(err.root_cause(), &TryFromError::IntConversionError);
+
+        let err = Value::try_from(Unsigned {
+            count: u64::MAX,
+            index: 0,
+        })
+        .unwrap_err();
+        assert_eq!(err.path().unwrap().to_string(), "count");
+        assert_eq!(err.root_cause(), &TryFromError::IntConversionError);
+
+        let v2 = UnsignedTuple(3, "x".into());
+        let sv2 = Value::try_from(v2.clone())?;
+        assert_eq!(sv2, Value::List(vec![3.into(), "x".into()]));
+        let v2d: UnsignedTuple = sv2.try_into()?;
+        assert_eq!(v2d, v2);
+
+        let err = Value::try_from(UnsignedTuple(u64::MAX, "x".into())).unwrap_err();
+        assert_eq!(err.path().unwrap().to_string(), "[0]");
+        Ok(())
+    }
 }
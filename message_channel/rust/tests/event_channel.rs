@@ -0,0 +1,199 @@
+#[cfg(feature = "mock_transport")]
+mod tests {
+    use std::{cell::RefCell, num::NonZeroUsize, rc::Rc, time::Duration};
+
+    use futures::stream;
+    use irondash_message_channel::{
+        run_until_complete, EventHandler, EventSink, MockIsolate, MockMessage,
+        MockMessageTransport, Value,
+    };
+    use irondash_run_loop::{JoinHandle, RunLoop};
+
+    struct PipeEvents {
+        window: Option<NonZeroUsize>,
+        pipes: Rc<RefCell<Vec<JoinHandle<()>>>>,
+    }
+
+    impl EventHandler for PipeEvents {
+        fn register_event_sink(&mut self, sink: EventSink, listen_argument: Value) {
+            let count: i64 = listen_argument.try_into().unwrap();
+            let items = stream::iter(0..count);
+            let pipe = match self.window {
+                Some(window) => sink.pipe_with_window(items, window),
+                None => sink.pipe(items),
+            };
+            self.pipes.borrow_mut().push(pipe);
+        }
+
+        fn unregister_event_sink(&mut self, _sink_id: i64) {}
+    }
+
+    async fn wait_a_bit() {
+        RunLoop::current().wait(Duration::from_millis(10)).await;
+    }
+
+    fn listen(isolate: &MockIsolate, channel: &str, subscription_id: i64, argument: Value) {
+        let args = vec![subscription_id.into(), argument];
+        let call = vec![Value::String("listen".into()), args.into()];
+        isolate.post_message(channel, call.into());
+    }
+
+    fn cancel(isolate: &MockIsolate, channel: &str, subscription_id: i64) {
+        let call = vec![Value::String("cancel".into()), subscription_id.into()];
+        isolate.post_message(channel, call.into());
+    }
+
+    // Returns (subscription_id, event) pairs
+    fn events(messages: &[MockMessage]) -> Vec<(i64, Value)> {
+        messages
+            .iter()
+            .map(|m| match m {
+                MockMessage::SendMessage { message, .. } => message.clone(),
+                MockMessage::PostMessage { message, .. } => message.clone(),
+            })
+            .map(|m| {
+                let mut m: Vec<Value> = m.try_into().unwrap();
+                let event = m.pop().unwrap();
+                (m.pop().unwrap().try_into().unwrap(), event)
+            })
+            .collect()
+    }
+
+    fn event_values(messages: &[MockMessage]) -> Vec<Value> {
+        events(messages).into_iter().map(|e| e.1).collect()
+    }
+
+    #[test]
+    fn test_pipe() {
+        let pipes = Rc::new(RefCell::new(Vec::new()));
+        let _channel = PipeEvents {
+            window: None,
+            pipes: pipes.clone(),
+        }
+        .register("pipe_events_1");
+        let isolate = MockMessageTransport::get().join_isolate();
+        listen(&isolate, "pipe_events_1", 1, 3.into());
+        let isolate = run_until_complete(async move {
+            wait_a_bit().await;
+            let pipe = pipes.borrow_mut().pop().unwrap();
+            pipe.await;
+            isolate
+        });
+        let messages = isolate.take_messages();
+        assert!(messages
+            .iter()
+            .all(|m| matches!(m, MockMessage::PostMessage { .. })));
+        assert_eq!(
+            event_values(&messages),
+            vec![Value::I64(0), Value::I64(1), Value::I64(2)]
+        );
+    }
+
+    #[test]
+    fn test_pipe_with_window() {
+        let pipes = Rc::new(RefCell::new(Vec::new()));
+        let _channel = PipeEvents {
+            window: NonZeroUsize::new(2),
+            pipes: pipes.clone(),
+        }
+        .register("pipe_events_2");
+        let isolate = MockMessageTransport::get().join_isolate();
+        listen(&isolate, "pipe_events_2", 1, 5.into());
+        run_until_complete(async move {
+            wait_a_bit().await;
+            // Nothing acknowledged yet; only window worth of items is sent.
+            let messages = isolate.take_messages();
+            assert_eq!(event_values(&messages), vec![Value::I64(0), Value::I64(1)]);
+
+            for message in messages {
+                if let MockMessage::SendMessage { reply_id, .. } = message {
+                    isolate.reply(reply_id, Value::Null);
+                }
+            }
+            wait_a_bit().await;
+            let messages = isolate.take_messages();
+            assert_eq!(event_values(&messages), vec![Value::I64(2), Value::I64(3)]);
+
+            // Cancelling the subscription stops the pipe.
+            cancel(&isolate, "pipe_events_2", 1);
+            let pipe = pipes.borrow_mut().pop().unwrap();
+            pipe.await;
+            assert!(isolate.take_messages().is_empty());
+        });
+    }
+
+    #[test]
+    fn test_pipe_isolate_exit() {
+        let pipes = Rc::new(RefCell::new(Vec::new()));
+        let _channel = PipeEvents {
+            window: NonZeroUsize::new(1),
+            pipes: pipes.clone(),
+        }
+        .register("pipe_events_3");
+        let isolate = MockMessageTransport::get().join_isolate();
+        listen(&isolate, "pipe_events_3", 1, 10.into());
+        run_until_complete(async move {
+            wait_a_bit().await;
+            assert_eq!(isolate.take_messages().len(), 1);
+            isolate.exit();
+            let pipe = pipes.borrow_mut().pop().unwrap();
+            pipe.await;
+        });
+    }
+
+    struct Listeners {
+        sinks: Vec<EventSink>,
+        unregistered: Rc<RefCell<Vec<i64>>>,
+    }
+
+    impl EventHandler for Listeners {
+        fn register_event_sink(&mut self, sink: EventSink, listen_argument: Value) {
+            sink.post_message(listen_argument).unwrap();
+            self.sinks.push(sink);
+        }
+
+        fn unregister_event_sink(&mut self, sink_id: i64) {
+            self.unregistered.borrow_mut().push(sink_id);
+        }
+    }
+
+    #[test]
+    fn test_multiple_listeners() {
+        let unregistered = Rc::new(RefCell::new(Vec::new()));
+        let channel = Listeners {
+            sinks: Vec::new(),
+            unregistered: unregistered.clone(),
+        }
+        .register("listener_events");
+        let isolate = MockMessageTransport::get().join_isolate();
+        listen(&isolate, "listener_events", 10, "a".into());
+        listen(&isolate, "listener_events", 11, "b".into());
+        let isolate = run_until_complete(async move {
+            wait_a_bit().await;
+            isolate
+        });
+        assert_eq!(
+            events(&isolate.take_messages()),
+            vec![(10, "a".into()), (11, "b".into())]
+        );
+        let sink_ids: Vec<_> = channel.borrow().sinks.iter().map(|s| s.id()).collect();
+        assert_eq!(sink_ids.len(), 2);
+
+        // Cancel only tears down matching subscription
+        cancel(&isolate, "listener_events", 10);
+        let isolate = run_until_complete(async move {
+            wait_a_bit().await;
+            isolate
+        });
+        assert_eq!(*unregistered.borrow(), vec![sink_ids[0]]);
+        assert!(channel.borrow().sinks[0].is_cancelled());
+        assert!(!channel.borrow().sinks[1].is_cancelled());
+        channel.borrow().sinks[1].post_message("c").unwrap();
+        run_until_complete(async { wait_a_bit().await });
+        assert_eq!(events(&isolate.take_messages()), vec![(11, "c".into())]);
+
+        isolate.exit();
+        run_until_complete(async { wait_a_bit().await });
+        assert_eq!(*unregistered.borrow(), sink_ids);
+    }
+}
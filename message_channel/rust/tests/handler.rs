@@ -0,0 +1,121 @@
+#[cfg(all(
+    feature = "mock_transport",
+    feature = "irondash_message_channel_derive"
+))]
+mod common;
+
+#[cfg(all(
+    feature = "mock_transport",
+    feature = "irondash_message_channel_derive"
+))]
+mod tests {
+    use std::cell::{Cell, RefCell};
+
+    use irondash_message_channel::{
+        handler, run_until_complete, AsyncMethodHandler, AsyncMethodInvoker, IsolateId,
+        MockMessageTransport, PlatformError, TryFromValue, Value,
+    };
+
+    use crate::common::{error_code, platform_error};
+
+    #[derive(TryFromValue)]
+    struct DivideRequest {
+        a: i64,
+        b: i64,
+    }
+
+    #[derive(Default)]
+    struct Calculator {
+        has_invoker: Cell<bool>,
+        destroyed_isolates: RefCell<Vec<IsolateId>>,
+    }
+
+    #[handler(rename_all = "camelCase")]
+    impl Calculator {
+        async fn add_numbers(&self, args: Vec<i64>) -> i64 {
+            self.sum(&args)
+        }
+
+        async fn divide(&self, request: DivideRequest) -> Result<i64, PlatformError> {
+            if request.b == 0 {
+                return Err(platform_error("division_by_zero"));
+            }
+            Ok(request.a / request.b)
+        }
+
+        async fn isolate_id(&self, isolate: IsolateId) -> i64 {
+            isolate.0
+        }
+
+        #[irondash(rename = "ping")]
+        async fn do_ping(&self) -> &'static str {
+            "pong"
+        }
+
+        #[irondash(skip)]
+        async fn _skipped(&self) {}
+
+        fn sum(&self, args: &[i64]) -> i64 {
+            args.iter().sum()
+        }
+
+        fn assign_invoker(&self, _invoker: AsyncMethodInvoker) {
+            self.has_invoker.set(true);
+        }
+
+        fn on_isolate_destroyed(&self, isolate: IsolateId) {
+            self.destroyed_isolates.borrow_mut().push(isolate);
+        }
+    }
+
+    #[test]
+    fn test_handler() {
+        let handler = Calculator::default().register("handler_calculator");
+        assert!(handler.handler().has_invoker.get());
+
+        let isolate = MockMessageTransport::get().join_isolate();
+        let isolate_id = isolate.id();
+        let (isolate, results) = run_until_complete(async move {
+            let mut results = Vec::new();
+            for (method, args) in [
+                ("addNumbers", Value::from(vec![1i64, 2, 3])),
+                ("divide", value_map(6, 3)),
+                ("divide", value_map(6, 0)),
+                ("divide", Value::from("abc")),
+                ("isolateId", Value::Null),
+                ("ping", Value::Null),
+                ("doPing", Value::Null),
+                ("skipped", Value::Null),
+                ("add_numbers", Value::Null),
+            ] {
+                results.push(
+                    isolate
+                        .invoke_method("handler_calculator", method, args)
+                        .await,
+                );
+            }
+            (isolate, results)
+        });
+        let mut results = results.into_iter();
+        assert_eq!(results.next().unwrap().unwrap(), Value::I64(6));
+        assert_eq!(results.next().unwrap().unwrap(), Value::I64(2));
+        assert_eq!(error_code(results.next().unwrap()), "division_by_zero");
+        assert_eq!(error_code(results.next().unwrap()), "try_from_error");
+        assert_eq!(results.next().unwrap().unwrap(), Value::I64(isolate_id.0));
+        assert_eq!(results.next().unwrap().unwrap(), Value::from("pong"));
+        for result in results {
+            assert_eq!(error_code(result), "invalid_method");
+        }
+
+        isolate.exit();
+        run_until_complete(async {});
+        assert_eq!(
+            *handler.handler().destroyed_isolates.borrow(),
+            vec![isolate_id]
+        );
+    }
+
+    fn value_map(a: i64, b: i64) -> Value {
+        Value::Map(vec![("a".into(), a.into()), ("b".into(), b.into())].into())
+    }
+}
@@ -0,0 +1,127 @@
+#[cfg(feature = "mock_transport")]
+mod common;
+
+#[cfg(feature = "mock_transport")]
+mod tests {
+    use std::thread;
+
+    use async_trait::async_trait;
+    use irondash_message_channel::{
+        run_until_complete, AsyncMethodHandler, Intercepted, MethodCall, MethodCallError,
+        MethodCallReply, MethodHandler, MethodInterceptor, MockMessageTransport, Next,
+        PlatformResult, Value,
+    };
+
+    use crate::common::platform_error;
+
+    struct AsyncEcho {}
+
+    #[async_trait(?Send)]
+    impl AsyncMethodHandler for AsyncEcho {
+        async fn on_method_call(&self, call: MethodCall) -> PlatformResult {
+            Ok(call.args)
+        }
+    }
+
+    struct Echo {}
+
+    impl MethodHandler for Echo {
+        fn on_method_call(&self, call: MethodCall, reply: MethodCallReply) {
+            // Reply from another thread to make sure the reply is routed back.
+            thread::spawn(move || reply.send_ok(call.args));
+        }
+    }
+
+    /// Refuses calls to "secret" method.
+    struct Deny {}
+
+    #[async_trait(?Send)]
+    impl MethodInterceptor for Deny {
+        async fn intercept(&self, call: MethodCall, next: Next<'_>) -> PlatformResult {
+            if call.method == "secret" {
+                return Err(platform_error("denied"));
+            }
+            next.run(call).await
+        }
+    }
+
+    /// Doubles integer argument.
+    struct Double {}
+
+    #[async_trait(?Send)]
+    impl MethodInterceptor for Double {
+        async fn intercept(&self, mut call: MethodCall, next: Next<'_>) -> PlatformResult {
+            if let Some(value) = call.args.as_i64() {
+                call.args = Value::I64(value * 2);
+            }
+            next.run(call).await
+        }
+    }
+
+    /// Wraps result in `[tag, result]` list.
+    struct Tag(&'static str);
+
+    #[async_trait(?Send)]
+    impl MethodInterceptor for Tag {
+        async fn intercept(&self, call: MethodCall, next: Next<'_>) -> PlatformResult {
+            let result = next.run(call).await?;
+            Ok(Value::List(vec![self.0.into(), result]))
+        }
+    }
+
+    fn expected() -> Value {
+        Value::List(vec![
+            "outer".into(),
+            Value::List(vec!["inner".into(), Value::I64(6)]),
+        ])
+    }
+
+    fn assert_denied(result: Result<Value, MethodCallError>) {
+        match result {
+            Err(MethodCallError::PlatformError(err)) => assert_eq!(err.code, "denied"),
+            other => panic!("unexpected result {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_async_handler_interceptors() {
+        let handler = Intercepted::new(AsyncEcho {}, Double {})
+            .with(Tag("inner"))
+            .with(Deny {})
+            .with(Tag("outer"));
+        let _handler = handler.register("interceptor_async");
+        let isolate = MockMessageTransport::get().join_isolate();
+        let (echo, secret) = run_until_complete(async move {
+            let echo = isolate
+                .invoke_method("interceptor_async", "echo", Value::I64(3))
+                .await;
+            let secret = isolate
+                .invoke_method("interceptor_async", "secret", Value::I64(3))
+                .await;
+            (echo, secret)
+        });
+        assert_eq!(echo.unwrap(), expected());
+        assert_denied(secret);
+    }
+
+    #[test]
+    fn test_method_handler_interceptors() {
+        let handler = Intercepted::new(Echo {}, Double {})
+            .with(Tag("inner"))
+            .with(Deny {})
+            .with(Tag("outer"));
+        let _handler = handler.register("interceptor_sync");
+        let isolate = MockMessageTransport::get().join_isolate();
+        let (echo, secret) = run_until_complete(async move {
+            let echo = isolate
+                .invoke_method("interceptor_sync", "echo", Value::I64(3))
+                .await;
+            let secret = isolate
+                .invoke_method("interceptor_sync", "secret", Value::I64(3))
+                .await;
+            (echo, secret)
+        });
+        assert_eq!(echo.unwrap(), expected());
+        assert_denied(secret);
+    }
+}
@@ -0,0 +1,55 @@
+#[cfg(feature = "mock_transport")]
+mod common;
+
+#[cfg(feature = "mock_transport")]
+mod tests {
+    use irondash_message_channel::{
+        run_until_complete, AsyncMethodHandler, MethodRouter, MockMessageTransport, Value,
+    };
+
+    use crate::common::{error_code, platform_error};
+
+    fn router() -> MethodRouter {
+        MethodRouter::new()
+            .method(
+                "add",
+                |args: Vec<i64>| async move { args.iter().sum::<i64>() },
+            )
+            .method("divide", |args: Vec<i64>| async move {
+                let (a, b) = (args[0], args[1]);
+                if b == 0 {
+                    Err(platform_error("division_by_zero"))
+                } else {
+                    Ok(a / b)
+                }
+            })
+            .method("ping", |_: Value| async move { "pong" })
+    }
+
+    #[test]
+    fn test_method_router() {
+        let _handler = router().register("method_router");
+        let isolate = MockMessageTransport::get().join_isolate();
+        let results = run_until_complete(async move {
+            let mut results = Vec::new();
+            for (method, args) in [
+                ("add", Value::from(vec![1i64, 2, 3])),
+                ("divide", Value::from(vec![6i64, 3])),
+                ("divide", Value::from(vec![6i64, 0])),
+                ("add", Value::from("abc")),
+                ("ping", Value::Null),
+                ("subtract", Value::Null),
+            ] {
+                results.push(isolate.invoke_method("method_router", method, args).await);
+            }
+            results
+        });
+        let mut results = results.into_iter();
+        assert_eq!(results.next().unwrap().unwrap(), Value::I64(6));
+        assert_eq!(results.next().unwrap().unwrap(), Value::I64(2));
+        assert_eq!(error_code(results.next().unwrap()), "division_by_zero");
+        assert_eq!(error_code(results.next().unwrap()), "try_from_error");
+        assert_eq!(results.next().unwrap().unwrap(), Value::from("pong"));
+        assert_eq!(error_code(results.next().unwrap()), "invalid_method");
+    }
+}
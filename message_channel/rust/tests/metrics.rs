@@ -0,0 +1,126 @@
+#[cfg(feature = "mock_transport")]
+mod common;
+
+#[cfg(feature = "mock_transport")]
+mod tests {
+    use std::time::Duration;
+
+    use irondash_message_channel::{
+        run_until_complete, AsyncMethodHandler, MessageChannel, MockMessageTransport, Value,
+        OTHER_KEY,
+    };
+
+    use crate::common::{platform_error, Calculator};
+
+    #[test]
+    fn test_metrics() {
+        MessageChannel::get().set_metrics_enabled(true);
+        let handler = Calculator::default().register("metrics_calculator");
+        let invoker = handler.handler().invoker.clone();
+        let isolate = MockMessageTransport::get().join_isolate();
+        let isolate_id = isolate.id();
+        isolate.set_method_handler("metrics_calculator", |method, _| match method {
+            "multiply" => Ok(Value::I64(6)),
+            _ => Err(platform_error("invalid_method")),
+        });
+        let isolate = run_until_complete(async move {
+            for _ in 0..2 {
+                isolate
+                    .invoke_method("metrics_calculator", "add", vec![1i64, 2])
+                    .await
+                    .unwrap();
+            }
+            isolate
+                .invoke_method("metrics_calculator", "subtract", Value::Null)
+                .await
+                .unwrap_err();
+            invoker
+                .call_method(isolate_id, "multiply", Value::Null)
+                .await
+                .unwrap();
+            invoker
+                .call_method(isolate_id, "divide", Value::Null)
+                .await
+                .unwrap_err();
+            isolate
+        });
+
+        // Isolate without handler for the channel never replies.
+        let silent_isolate = MockMessageTransport::get().join_isolate();
+        let silent_isolate_id = silent_isolate.id();
+        let invoker = handler.handler().invoker.clone();
+        run_until_complete(async move {
+            invoker
+                .call_method_with_timeout(
+                    silent_isolate_id,
+                    "multiply",
+                    Value::Null,
+                    Duration::from_millis(1),
+                )
+                .await
+                .unwrap_err();
+        });
+
+        let snapshot = MessageChannel::get().metrics();
+        let channel = &snapshot.channels["metrics_calculator"];
+        assert!(channel.approximate_bytes_sent > 0);
+        assert!(channel.approximate_bytes_received > 0);
+
+        let total = &channel.total;
+        assert_eq!(total.incoming_calls, 3);
+        assert_eq!(total.incoming_platform_errors, 1);
+        assert_eq!(total.handler_latency.count, 3);
+        assert_eq!(total.handler_latency.buckets.iter().sum::<u64>(), 3);
+        assert_eq!(total.outgoing_calls, 3);
+        assert_eq!(total.outgoing_platform_errors, 1);
+        assert_eq!(total.outgoing_latency.count, 2);
+        assert_eq!(total.send_errors["Timeout"], 1);
+
+        let add = &channel.methods["add"];
+        assert_eq!(add.incoming_calls, 2);
+        assert_eq!(add.incoming_platform_errors, 0);
+        let multiply = &channel.methods["multiply"];
+        assert_eq!(multiply.outgoing_calls, 2);
+        assert_eq!(multiply.outgoing_latency.count, 1);
+        assert_eq!(multiply.send_errors["Timeout"], 1);
+
+        let value: Value = snapshot.into();
+        assert_eq!(
+            value
+                .pointer("/channels/metrics_calculator/methods/add/incoming_calls")
+                .and_then(Value::as_i64),
+            Some(2)
+        );
+        assert_eq!(
+            value.get("latency_buckets_us"),
+            Some(&Value::I64List(vec![
+                1000, 2000, 5000, 10000, 20000, 50000, 100000, 500000, 1000000, 5000000
+            ]))
+        );
+
+        isolate.exit();
+        silent_isolate.exit();
+    }
+
+    #[test]
+    fn test_metrics_method_limit() {
+        MessageChannel::get().set_metrics_enabled(true);
+        let _handler = Calculator::default().register("metrics_limit");
+        let isolate = MockMessageTransport::get().join_isolate();
+        let isolate = run_until_complete(async move {
+            for i in 0..100 {
+                isolate
+                    .invoke_method("metrics_limit", &format!("method_{i}"), Value::Null)
+                    .await
+                    .unwrap_err();
+            }
+            isolate
+        });
+        let snapshot = MessageChannel::get().metrics();
+        let channel = &snapshot.channels["metrics_limit"];
+        assert_eq!(channel.total.incoming_calls, 100);
+        assert_eq!(channel.methods.len(), 65);
+        assert_eq!(channel.methods[OTHER_KEY].incoming_calls, 36);
+        isolate.exit();
+    }
+}
@@ -0,0 +1,394 @@
+#[cfg(feature = "mock_transport")]
+mod common;
+
+#[cfg(feature = "mock_transport")]
+mod tests {
+    use std::{
+        cell::{Cell, RefCell},
+        future::Future,
+        pin::Pin,
+        rc::Rc,
+        task::Poll,
+        thread,
+        time::Duration,
+    };
+
+    use async_trait::async_trait;
+    use irondash_message_channel::{
+        run_until_complete, AsyncMethodHandler, EventHandler, EventSink, MessageChannel,
+        MethodCall, MethodCallError, MethodCallReply, MethodHandler, MockMessage,
+        MockMessageTransport, PendingReplyId, PlatformResult, RegisteredAsyncMethodHandler,
+        SendMessageError, Value,
+    };
+
+    use crate::common::{error_code, Calculator};
+
+    #[test]
+    fn test_async_method_handler() {
+        let _handler = Calculator::default().register("mock_addition_1");
+        let isolate = MockMessageTransport::get().join_isolate();
+        let res = run_until_complete(async move {
+            let sum = isolate
+                .invoke_method("mock_addition_1", "add", vec![Value::I64(1), Value::I64(2)])
+                .await;
+            let invalid = isolate
+                .invoke_method("mock_addition_1", "subtract", Value::Null)
+                .await;
+            (sum, invalid)
+        });
+        assert_eq!(res.0.unwrap(), Value::I64(3));
+        match res.1 {
+            Err(MethodCallError::PlatformError(err)) => assert_eq!(err.code, "invalid_method"),
+            other => panic!("unexpected result {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_invoker_round_trip() {
+        let _handler = Calculator::default().register("mock_addition_2");
+        let isolate = MockMessageTransport::get().join_isolate();
+        isolate.set_method_handler("mock_addition_2", |method, args| {
+            assert_eq!(method, "multiply");
+            let args: Vec<i64> = args.try_into()?;
+            Ok(args.iter().product::<i64>().into())
+        });
+        let res = run_until_complete(async move {
+            isolate
+                .invoke_method(
+                    "mock_addition_2",
+                    "call_back",
+                    vec![Value::I64(3), Value::I64(4)],
+                )
+                .await
+        });
+        assert_eq!(res.unwrap(), Value::I64(12));
+    }
+
+    #[test]
+    fn test_isolate_exit() {
+        let handler = Calculator::default().register("mock_addition_3");
+        let isolate = MockMessageTransport::get().join_isolate();
+        let isolate_id = isolate.id();
+        let isolate = Rc::new(RefCell::new(Some(isolate)));
+        let isolate_clone = isolate.clone();
+        let res = run_until_complete(async move {
+            let call = {
+                let isolate = isolate_clone.borrow();
+                let isolate = isolate.as_ref().unwrap();
+                isolate.send_message(
+                    "mock_addition_3",
+                    vec![Value::String("call_back".into()), Value::Null].into(),
+                )
+            };
+            // Wait until Rust calls back into the isolate, then shut it down
+            // without replying.
+            loop {
+                irondash_run_loop::RunLoop::current()
+                    .wait(std::time::Duration::from_millis(1))
+                    .await;
+                let isolate = isolate_clone.borrow();
+                let messages = isolate.as_ref().unwrap().take_messages();
+                if let Some(MockMessage::SendMessage { channel, .. }) = messages.first() {
+                    assert_eq!(channel, "mock_addition_3");
+                    break;
+                }
+            }
+            isolate_clone.borrow_mut().take().unwrap().exit();
+            call.await
+        });
+        assert!(matches!(res, Err(SendMessageError::IsolateShutDown)));
+        assert!(isolate.borrow().is_none());
+        run_until_complete(async {});
+        assert_eq!(
+            *handler.handler().destroyed_isolates.borrow(),
+            vec![isolate_id]
+        );
+    }
+
+    #[test]
+    fn test_no_channel() {
+        let isolate = MockMessageTransport::get().join_isolate();
+        let res =
+            run_until_complete(
+                async move { isolate.send_message("mock_missing", 10.into()).await },
+            );
+        match res {
+            Err(SendMessageError::ChannelNotFound { channel }) => {
+                assert_eq!(channel, "mock_missing")
+            }
+            other => panic!("unexpected result {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_malformed_message() {
+        let isolate = MockMessageTransport::get().join_isolate();
+        let res = run_until_complete(async move {
+            isolate.send_raw_message(vec![10.into(), Value::Null]).await
+        });
+        match res {
+            Err(SendMessageError::MessageRefused) => {}
+            other => panic!("unexpected result {other:?}"),
+        }
+    }
+
+    fn assert_malformed_call_reply(reply: Result<Value, SendMessageError>) {
+        let reply: Vec<Value> = reply.unwrap().try_into().unwrap();
+        assert_eq!(reply[0], Value::String("err".into()));
+        assert_eq!(reply[1], Value::String("malformed_message".into()));
+    }
+
+    #[test]
+    fn test_malformed_method_call() {
+        let _handler = Calculator::default().register("mock_malformed_call_1");
+        let _slow = Slow {}.register("mock_malformed_call_2");
+        let isolate = MockMessageTransport::get().join_isolate();
+        let (res1, res2) = run_until_complete(async move {
+            // Message is decoded fine but is not [method, args] list.
+            let res1 = isolate
+                .send_raw_message(vec!["mock_malformed_call_1".into(), 10.into()])
+                .await;
+            let res2 = isolate
+                .send_raw_message(vec!["mock_malformed_call_2".into(), 10.into()])
+                .await;
+            (res1, res2)
+        });
+        assert_malformed_call_reply(res1);
+        assert_malformed_call_reply(res2);
+    }
+
+    #[test]
+    fn test_malformed_reply() {
+        let handler = Calculator::default().register("mock_malformed_reply");
+        let isolate = MockMessageTransport::get().join_isolate();
+        let isolate_id = isolate.id();
+        isolate.set_handler("mock_malformed_reply", |_| Value::I64(10));
+        let invoker = handler.handler().invoker.clone();
+        let res = run_until_complete(async move {
+            invoker
+                .call_method(isolate_id, "multiply", Value::Null)
+                .await
+        });
+        assert_eq!(error_code(res), "invalid_reply");
+    }
+
+    #[test]
+    fn test_call_method_timeout() {
+        let handler = Calculator::default().register("mock_addition_4");
+        let isolate = MockMessageTransport::get().join_isolate();
+        let isolate_id = isolate.id();
+        let invoker = handler.handler().invoker.clone();
+        // Isolate has no handler for the channel so it will never reply.
+        let res = run_until_complete(async move {
+            invoker
+                .call_method_with_timeout(
+                    isolate_id,
+                    "multiply",
+                    Value::Null,
+                    Duration::from_millis(10),
+                )
+                .await
+        });
+        assert!(matches!(
+            res,
+            Err(MethodCallError::SendError(SendMessageError::Timeout))
+        ));
+        let messages = isolate.take_messages();
+        let reply_id = match messages.first() {
+            Some(MockMessage::SendMessage { reply_id, .. }) => *reply_id,
+            other => panic!("unexpected message {other:?}"),
+        };
+        assert!(!MessageChannel::get().cancel_reply(PendingReplyId(reply_id)));
+    }
+
+    #[test]
+    fn test_call_method_cancel_on_drop() {
+        let handler = Calculator::default().register("mock_addition_5");
+        let isolate = MockMessageTransport::get().join_isolate();
+        let isolate_id = isolate.id();
+        let invoker = handler.handler().invoker.clone();
+        run_until_complete(async move {
+            let mut call = Box::pin(invoker.call_method(isolate_id, "multiply", Value::Null));
+            // Poll once to send the message, then drop the future.
+            std::future::poll_fn(|cx| {
+                assert!(Pin::new(&mut call).poll(cx).is_pending());
+                Poll::Ready(())
+            })
+            .await;
+        });
+        let messages = isolate.take_messages();
+        let reply_id = match messages.first() {
+            Some(MockMessage::SendMessage { reply_id, .. }) => *reply_id,
+            other => panic!("unexpected message {other:?}"),
+        };
+        assert!(!MessageChannel::get().cancel_reply(PendingReplyId(reply_id)));
+    }
+
+    #[test]
+    fn test_cancel_reply() {
+        let isolate = MockMessageTransport::get().join_isolate();
+        let called = Rc::new(Cell::new(false));
+        let called_clone = called.clone();
+        let id = MessageChannel::get()
+            .send_message_cancellable(isolate.id(), "mock_cancel", Value::Null, move |_| {
+                called_clone.set(true);
+            })
+            .unwrap();
+        assert!(MessageChannel::get().cancel_reply(id));
+        assert!(!MessageChannel::get().cancel_reply(id));
+        run_until_complete(async move {
+            isolate.reply(id.0, Value::Null);
+            irondash_run_loop::RunLoop::current()
+                .wait(Duration::from_millis(10))
+                .await;
+        });
+        assert!(!called.get());
+    }
+
+    struct Pending {
+        calls: RefCell<Vec<MethodCall>>,
+        dropped: Rc<Cell<usize>>,
+    }
+
+    struct DropCounter(Rc<Cell<usize>>);
+
+    impl Drop for DropCounter {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl AsyncMethodHandler for Pending {
+        async fn on_method_call(&self, call: MethodCall) -> PlatformResult {
+            let _counter = DropCounter(self.dropped.clone());
+            self.calls.borrow_mut().push(call);
+            std::future::pending().await
+        }
+    }
+
+    fn pending_handler(channel: &str) -> (RegisteredAsyncMethodHandler<Pending>, Rc<Cell<usize>>) {
+        let dropped = Rc::new(Cell::new(0));
+        let handler = Pending {
+            calls: RefCell::new(Vec::new()),
+            dropped: dropped.clone(),
+        }
+        .register(channel);
+        (handler, dropped)
+    }
+
+    async fn wait_a_bit() {
+        irondash_run_loop::RunLoop::current()
+            .wait(Duration::from_millis(10))
+            .await;
+    }
+
+    #[test]
+    fn test_cancel_on_isolate_exit() {
+        let (handler, dropped) = pending_handler("mock_pending_1");
+        let isolate = MockMessageTransport::get().join_isolate();
+        run_until_complete(async move {
+            let _call = isolate.send_message(
+                "mock_pending_1",
+                vec![Value::String("wait".into()), Value::Null].into(),
+            );
+            wait_a_bit().await;
+            isolate.exit();
+            wait_a_bit().await;
+        });
+        assert_eq!(dropped.get(), 1);
+        let calls = handler.handler().calls.take();
+        assert_eq!(calls.len(), 1);
+        assert!(calls[0].cancellation_token.is_cancelled());
+    }
+
+    #[test]
+    fn test_cancel_message() {
+        let (handler, dropped) = pending_handler("mock_pending_2");
+        let isolate = MockMessageTransport::get().join_isolate();
+        let isolate = run_until_complete(async move {
+            let message: Value = vec![Value::String("wait".into()), Value::Null].into();
+            let (first, _) = isolate.send_cancellable_message("mock_pending_2", message.clone());
+            let _second = isolate.send_message("mock_pending_2", message);
+            wait_a_bit().await;
+            isolate.cancel_message(first);
+            wait_a_bit().await;
+            isolate
+        });
+        assert_eq!(dropped.get(), 1);
+        let calls = handler.handler().calls.take();
+        assert_eq!(calls.len(), 2);
+        assert!(calls[0].cancellation_token.is_cancelled());
+        assert!(!calls[1].cancellation_token.is_cancelled());
+        isolate.exit();
+    }
+
+    struct Slow {}
+
+    impl MethodHandler for Slow {
+        fn on_method_call(&self, call: MethodCall, reply: MethodCallReply) {
+            thread::spawn(move || {
+                reply.send_ok(call.args);
+            });
+        }
+    }
+
+    #[test]
+    fn test_method_handler_reply_from_thread() {
+        let _handler = Slow {}.register("mock_slow");
+        let isolate = MockMessageTransport::get().join_isolate();
+        let res =
+            run_until_complete(
+                async move { isolate.invoke_method("mock_slow", "echo", "Hello").await },
+            );
+        assert_eq!(res.unwrap(), Value::String("Hello".into()));
+    }
+
+    struct Events {
+        sinks: Vec<EventSink>,
+        unregistered: Rc<Cell<usize>>,
+    }
+
+    impl EventHandler for Events {
+        fn register_event_sink(&mut self, sink: EventSink, listen_argument: Value) {
+            sink.post_message(listen_argument).unwrap();
+            self.sinks.push(sink);
+        }
+
+        fn unregister_event_sink(&mut self, _sink_id: i64) {
+            self.unregistered.set(self.unregistered.get() + 1);
+        }
+    }
+
+    #[test]
+    fn test_event_channel() {
+        let unregistered = Rc::new(Cell::new(0));
+        let _channel = Events {
+            sinks: Vec::new(),
+            unregistered: unregistered.clone(),
+        }
+        .register("mock_events");
+        let isolate = MockMessageTransport::get().join_isolate();
+        let isolate = run_until_complete(async move {
+            isolate
+                .invoke_method(
+                    "mock_events",
+                    "listen",
+                    vec![Value::I64(1), Value::String("first".into())],
+                )
+                .await
+                .unwrap();
+            isolate
+        });
+        assert_eq!(
+            isolate.take_messages(),
+            vec![MockMessage::PostMessage {
+                channel: "mock_events".into(),
+                message: vec![Value::I64(1), Value::String("first".into())].into(),
+            }]
+        );
+        isolate.exit();
+        run_until_complete(async {});
+        assert_eq!(unregistered.get(), 1);
+    }
+}
@@ -0,0 +1,146 @@
+#[cfg(feature = "mock_transport")]
+mod tests {
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use irondash_message_channel::{
+        run_until_complete, Frame, FrameDirection, FrameKind, IsolateId, MessageChannel,
+        MessageChannelObserver, MethodCall, MethodCallReply, MethodHandler, MockMessageTransport,
+        Value,
+    };
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Recorded {
+        direction: FrameDirection,
+        kind: FrameKind,
+        isolate: IsolateId,
+        channel: Option<String>,
+        reply_id: Option<i64>,
+        payload_size: usize,
+        has_latency: bool,
+    }
+
+    struct Recorder {
+        isolate: IsolateId,
+        frames: Mutex<Vec<Recorded>>,
+    }
+
+    impl MessageChannelObserver for Recorder {
+        fn on_frame(&self, frame: &Frame) {
+            // Observer is global and other tests may run in parallel; Only
+            // record frames of the isolate that this recorder observes.
+            if frame.isolate != self.isolate {
+                return;
+            }
+            self.frames.lock().unwrap().push(Recorded {
+                direction: frame.direction,
+                kind: frame.kind,
+                isolate: frame.isolate,
+                channel: frame.channel.map(|c| c.to_owned()),
+                reply_id: frame.reply_id,
+                payload_size: frame.payload_size,
+                has_latency: frame.latency.is_some(),
+            });
+        }
+    }
+
+    struct Echo {}
+
+    impl MethodHandler for Echo {
+        fn on_method_call(&self, call: MethodCall, reply: MethodCallReply) {
+            reply.send_ok(call.args);
+        }
+    }
+
+    #[test]
+    fn test_observer() {
+        let _handler = Echo {}.register("observer_echo");
+        let isolate = MockMessageTransport::get().join_isolate();
+        let isolate_id = isolate.id();
+        let recorder = Arc::new(Recorder {
+            isolate: isolate_id,
+            frames: Mutex::new(Vec::new()),
+        });
+        MessageChannel::get().set_observer(Some(recorder.clone()));
+        let isolate = run_until_complete(async move {
+            isolate
+                .invoke_method("observer_echo", "echo", "abc")
+                .await
+                .unwrap();
+            MessageChannel::get()
+                .post_message(isolate_id, "observer_post", Value::I64(1))
+                .unwrap();
+            MessageChannel::get().send_message_with_timeout(
+                isolate_id,
+                "observer_timeout",
+                Value::Null,
+                Duration::from_millis(1),
+                |_| {},
+            );
+            irondash_run_loop::RunLoop::current()
+                .wait(Duration::from_millis(10))
+                .await;
+            MessageChannel::get().send_message(isolate_id, "observer_exit", Value::Null, |_| {});
+            isolate
+        });
+        isolate.exit();
+        MessageChannel::get().set_observer(None);
+
+        let frames = recorder.frames.lock().unwrap().clone();
+        let frame =
+            |direction, kind, channel: &str, reply_id, payload_size, has_latency| Recorded {
+                direction,
+                kind,
+                isolate: isolate_id,
+                channel: Some(channel.into()),
+                reply_id,
+                payload_size,
+                has_latency,
+            };
+        use FrameDirection::*;
+        use FrameKind::*;
+        // Method call message is ["echo", "abc"]; reply is ["ok", "abc"]
+        assert_eq!(
+            frames,
+            vec![
+                frame(Incoming, Message, "observer_echo", Some(1), 7, false),
+                frame(Outgoing, Reply, "observer_echo", Some(1), 5, true),
+                frame(Outgoing, PostMessage, "observer_post", None, 8, false),
+                frame(
+                    Outgoing,
+                    Message,
+                    "observer_timeout",
+                    frames[3].reply_id,
+                    1,
+                    false
+                ),
+                frame(
+                    Local,
+                    Timeout,
+                    "observer_timeout",
+                    frames[3].reply_id,
+                    0,
+                    true
+                ),
+                frame(
+                    Outgoing,
+                    Message,
+                    "observer_exit",
+                    frames[5].reply_id,
+                    1,
+                    false
+                ),
+                frame(
+                    Local,
+                    IsolateExit,
+                    "observer_exit",
+                    frames[5].reply_id,
+                    0,
+                    true
+                ),
+            ]
+        );
+    }
+}
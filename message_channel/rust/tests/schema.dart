@@ -0,0 +1,125 @@
+// Generated by irondash_message_channel. Do not edit.
+
+import 'dart:typed_data';
+
+class Point {
+  const Point(this.value0, this.value1);
+
+  factory Point.fromValue(Object? value) {
+    final list = value as List;
+    return Point(
+      (list[0] as double),
+      (list[1] as double),
+    );
+  }
+
+  final double value0;
+
+  final double value1;
+
+  Object? toValue() => [value0, value1];
+}
+
+sealed class Shape {
+  const Shape();
+
+  factory Shape.fromValue(Object? value) {
+    final map = value as Map;
+    final tag = map['t'];
+    if (tag == 'empty') return const ShapeEmpty();
+    if (tag == 'circle') return ShapeCircle._fromContent(map['c']);
+    if (tag == 'rect') return ShapeRect._fromContent(map['c']);
+    throw ArgumentError.value(value, 'value', 'Unknown Shape');
+  }
+
+  Object? toValue();
+}
+
+class ShapeEmpty extends Shape {
+  const ShapeEmpty();
+
+  @override
+  Object? toValue() => {'t': 'empty'};
+}
+
+class ShapeCircle extends Shape {
+  const ShapeCircle(this.value);
+
+  factory ShapeCircle._fromContent(Object? value) {
+    return ShapeCircle(
+      (value as double),
+    );
+  }
+
+  final double value;
+
+  @override
+  Object? toValue() => {'t': 'circle', 'c': value};
+}
+
+class ShapeRect extends Shape {
+  const ShapeRect({required this.topLeft, required this.bottomRight});
+
+  factory ShapeRect._fromContent(Object? value) {
+    final map = value as Map;
+    return ShapeRect(
+      topLeft: Point.fromValue(map['topLeft']),
+      bottomRight: Point.fromValue(map['bottomRight']),
+    );
+  }
+
+  final Point topLeft;
+
+  final Point bottomRight;
+
+  @override
+  Object? toValue() => {'t': 'rect', 'c': {'topLeft': topLeft.toValue(), 'bottomRight': bottomRight.toValue()}};
+}
+
+class Request {
+  const Request({required this.url, this.maxSize, this.userAgent, required this.headers, required this.body, required this.shapes});
+
+  factory Request.fromValue(Object? value) {
+    final map = value as Map;
+    return Request(
+      url: (map['url'] as String),
+      maxSize: (map['max'] == null ? null : (map['max'] as int)),
+      userAgent: (map['userAgent'] == null ? null : (map['userAgent'] as String)),
+      headers: (map['headers'] as Map).map<String, String>((k0, v0) => MapEntry((k0 as String), (v0 as String))),
+      body: (map['body'] as Uint8List),
+      shapes: (map['shapes'] as List).map<Shape>((e0) => Shape.fromValue(e0)).toList(),
+    );
+  }
+
+  final String url;
+
+  final int? maxSize;
+
+  final String? userAgent;
+
+  final Map<String, String> headers;
+
+  final Uint8List body;
+
+  final List<Shape> shapes;
+
+  Object? toValue() => {'url': url, 'max': maxSize, if (userAgent != null) 'userAgent': userAgent, 'headers': headers, 'body': body, 'shapes': shapes.map((e0) => e0.toValue()).toList()};
+}
+
+enum Color {
+  red('Red'),
+  green('verde');
+
+  const Color(this._key);
+
+  final String _key;
+
+  static Color fromValue(Object? value) {
+    for (final v in values) {
+      if (v._key == value) return v;
+    }
+    throw ArgumentError.value(value, 'value', 'Unknown Color');
+  }
+
+  Object? toValue() => _key;
+}
@@ -0,0 +1,268 @@
+#[cfg(feature = "irondash_message_channel_derive")]
+mod tests {
+    use std::collections::HashMap;
+
+    use irondash_message_channel::{
+        FieldSchema, FieldsSchema, IntoValue, SchemaRegistry, TryFromValue, TypeSchema, Value,
+        ValueSchema, ValueType, VariantSchema,
+    };
+
+    #[derive(IntoValue, TryFromValue, ValueSchema)]
+    #[irondash(rename_all = "camelCase")]
+    struct Request {
+        url: String,
+        #[irondash(rename = "max")]
+        max_size: Option<i64>,
+        #[irondash(skip_if_empty)]
+        user_agent: Option<String>,
+        #[irondash(skip)]
+        _internal: i64,
+        headers: HashMap<String, String>,
+        body: Vec<u8>,
+        shapes: Vec<Shape>,
+    }
+
+    #[derive(IntoValue, TryFromValue, ValueSchema)]
+    #[irondash(tag = "t", content = "c", rename_all = "snake_case")]
+    enum Shape {
+        Empty,
+        Circle(f64),
+        #[irondash(rename_all = "camelCase")]
+        Rect {
+            top_left: Point,
+            bottom_right: Point,
+        },
+    }
+
+    #[derive(IntoValue, TryFromValue, ValueSchema)]
+    struct Point(f64, f64);
+
+    #[derive(IntoValue, TryFromValue, ValueSchema)]
+    enum Color {
+        Red,
+        #[irondash(rename = "verde")]
+        Green,
+        #[irondash(skip)]
+        _Blue,
+    }
+
+    #[derive(IntoValue, TryFromValue, ValueSchema)]
+    #[irondash(into = "String", from = "String")]
+    struct Email(String);
+
+    impl From<Email> for String {
+        fn from(email: Email) -> Self {
+            email.0
+        }
+    }
+
+    impl From<String> for Email {
+        fn from(value: String) -> Self {
+            Email(value)
+        }
+    }
+
+    mod email_value {
+        use irondash_message_channel::{TryFromError, Value};
+
+        pub fn to_value(email: super::Email) -> Value {
+            email.into()
+        }
+
+        pub fn try_from_value(value: Value) -> Result<super::Email, TryFromError> {
+            value.try_into()
+        }
+    }
+
+    #[derive(IntoValue, TryFromValue, ValueSchema)]
+    struct Contact {
+        email: Email,
+        #[irondash(with = "email_value")]
+        backup: Email,
+    }
+
+    #[derive(IntoValue, TryFromValue, ValueSchema)]
+    #[irondash(repr = "int")]
+    enum Mode {
+        A = 1,
+        B = 5,
+    }
+
+    fn field(name: &str, key: &str, ty: ValueType) -> FieldSchema {
+        FieldSchema {
+            name: name.into(),
+            key: key.into(),
+            ty,
+            skip_if_empty: false,
+            default: false,
+            flatten: false,
+        }
+    }
+
+    #[test]
+    fn test_schema() {
+        let mut registry = SchemaRegistry::new();
+        registry.add::<Request>().add::<Color>().add::<Request>();
+        let types = registry.types();
+        let names: Vec<&str> = types.iter().map(|t| t.name()).collect();
+        // Dependencies come first.
+        assert_eq!(names, vec!["Point", "Shape", "Request", "Color"]);
+
+        assert_eq!(
+            types[0],
+            TypeSchema::Struct {
+                name: "Point".into(),
+                fields: FieldsSchema::Unnamed(vec![ValueType::Double, ValueType::Double]),
+            }
+        );
+        assert_eq!(
+            types[1],
+            TypeSchema::Enum {
+                name: "Shape".into(),
+                tag: Some("t".into()),
+                content: Some("c".into()),
+                untagged: false,
+                variants: vec![
+                    VariantSchema {
+                        name: "Empty".into(),
+                        key: "empty".into(),
+                        fields: FieldsSchema::Unit,
+                        discriminant: None,
+                    },
+                    VariantSchema {
+                        name: "Circle".into(),
+                        key: "circle".into(),
+                        fields: FieldsSchema::Unnamed(vec![ValueType::Double]),
+                        discriminant: None,
+                    },
+                    VariantSchema {
+                        name: "Rect".into(),
+                        key: "rect".into(),
+                        fields: FieldsSchema::Named(vec![
+                            field("top_left", "topLeft", ValueType::Named("Point".into())),
+                            field(
+                                "bottom_right",
+                                "bottomRight",
+                                ValueType::Named("Point".into())
+                            ),
+                        ]),
+                        discriminant: None,
+                    },
+                ],
+            }
+        );
+        let mut user_agent = field(
+            "user_agent",
+            "userAgent",
+            ValueType::Optional(Box::new(ValueType::String)),
+        );
+        user_agent.skip_if_empty = true;
+        assert_eq!(
+            types[2],
+            TypeSchema::Struct {
+                name: "Request".into(),
+                fields: FieldsSchema::Named(vec![
+                    field("url", "url", ValueType::String),
+                    field(
+                        "max_size",
+                        "max",
+                        ValueType::Optional(Box::new(ValueType::Int))
+                    ),
+                    user_agent,
+                    field(
+                        "headers",
+                        "headers",
+                        ValueType::Map(Box::new(ValueType::String), Box::new(ValueType::String))
+                    ),
+                    field("body", "body", ValueType::Uint8List),
+                    field(
+                        "shapes",
+                        "shapes",
+                        ValueType::List(Box::new(ValueType::Named("Shape".into())))
+                    ),
+                ]),
+            }
+        );
+    }
+
+    #[test]
+    fn test_schema_matches_value() {
+        // Map keys in schema must match keys produced by IntoValue.
+        let request = Request {
+            url: "url".into(),
+            max_size: None,
+            user_agent: Some("agent".into()),
+            _internal: 0,
+            headers: HashMap::new(),
+            body: Vec::new(),
+            shapes: Vec::new(),
+        };
+        let value: Value = request.into();
+        let mut keys: Vec<String> = value
+            .as_map()
+            .unwrap()
+            .iter()
+            .map(|(k, _)| k.as_str().unwrap().to_owned())
+            .collect();
+        keys.sort();
+        let mut registry = SchemaRegistry::new();
+        registry.add::<Request>();
+        let mut expected: Vec<String> = match registry.types().last().unwrap() {
+            TypeSchema::Struct {
+                fields: FieldsSchema::Named(fields),
+                ..
+            } => fields.iter().map(|f| f.key.clone()).collect(),
+            other => panic!("unexpected schema {other:?}"),
+        };
+        expected.sort();
+        assert_eq!(keys, expected);
+    }
+
+    #[test]
+    fn test_dart() {
+        let mut registry = SchemaRegistry::new();
+        registry.add::<Request>().add::<Color>();
+        let dart = registry.to_dart();
+        let expected = include_str!("schema.dart");
+        assert_eq!(dart, expected);
+    }
+
+    #[test]
+    fn test_value_type() {
+        assert_eq!(
+            <Option<Vec<Color>>>::value_type(),
+            ValueType::Optional(Box::new(ValueType::List(Box::new(ValueType::Named(
+                "Color".into()
+            )))))
+        );
+        assert_eq!(Email::value_type(), ValueType::String);
+    }
+
+    #[test]
+    fn test_repr_int() {
+        let mut registry = SchemaRegistry::new();
+        registry.add::<Mode>();
+        let discriminants: Vec<Option<i64>> = match &registry.types()[0] {
+            TypeSchema::Enum { variants, .. } => variants.iter().map(|v| v.discriminant).collect(),
+            other => panic!("unexpected schema {other:?}"),
+        };
+        assert_eq!(discriminants, vec![Some(1), Some(5)]);
+    }
+
+    #[test]
+    fn test_conversions() {
+        let mut registry = SchemaRegistry::new();
+        registry.add::<Contact>();
+        assert_eq!(
+            registry.types(),
+            &[TypeSchema::Struct {
+                name: "Contact".into(),
+                fields: FieldsSchema::Named(vec![
+                    field("email", "email", ValueType::String),
+                    // Type converted through `with` module is opaque.
+                    field("backup", "backup", ValueType::Any),
+                ]),
+            }]
+        );
+    }
+}
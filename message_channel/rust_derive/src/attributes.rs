@@ -1,6 +1,6 @@
 use proc_macro2::{Ident, Span};
 use proc_macro_error::{Diagnostic, Level};
-use syn::{spanned::Spanned, Attribute, Lit, Meta, NestedMeta, Path};
+use syn::{spanned::Spanned, Attribute, Lit, Meta, MetaNameValue, NestedMeta, Path, Type};
 
 use crate::case::RenameRule;
 
@@ -15,6 +15,17 @@ pub const SKIP_IF_EMPTY: Symbol = Symbol("skip_if_empty");
 pub const DEFAULT: Symbol = Symbol("default");
 pub const TAG: Symbol = Symbol("tag");
 pub const CONTENT: Symbol = Symbol("content");
+pub const FLATTEN: Symbol = Symbol("flatten");
+pub const WITH: Symbol = Symbol("with");
+pub const FROM: Symbol = Symbol("from");
+pub const INTO: Symbol = Symbol("into");
+pub const TRY_FROM: Symbol = Symbol("try_from");
+pub const UNTAGGED: Symbol = Symbol("untagged");
+pub const REPR: Symbol = Symbol("repr");
+pub const DENY_UNKNOWN_FIELDS: Symbol = Symbol("deny_unknown_fields");
+pub const ALIAS: Symbol = Symbol("alias");
+pub const BOXED: Symbol = Symbol("boxed");
+pub const TRY_INTO: Symbol = Symbol("try_into");
 
 impl PartialEq<Symbol> for Ident {
     fn eq(&self, word: &Symbol) -> bool {
@@ -68,11 +79,37 @@ pub struct StringWithSpan {
     pub span: Span,
 }
 
-#[derive(Debug, Default)]
+/// Container attributes delegating conversion to another type.
+#[derive(Default)]
+pub struct ConversionAttributes {
+    pub from: Option<Type>,
+    pub into: Option<Type>,
+    pub try_from: Option<Type>,
+    /// Also implement `TryFrom<Value>` for `Box<Self>`.
+    pub boxed: bool,
+}
+
+impl ConversionAttributes {
+    /// Type used to represent the container in `Value`, if any.
+    pub fn value_type(&self) -> Option<&Type> {
+        self.into
+            .as_ref()
+            .or(self.from.as_ref())
+            .or(self.try_from.as_ref())
+    }
+}
+
+#[derive(Default)]
 pub struct EnumAttributes {
     pub tag: Option<StringWithSpan>,
     pub content: Option<StringWithSpan>,
     pub rename_all: RenameRule,
+    pub conversion: ConversionAttributes,
+    pub untagged: bool,
+    /// Span of `deny_unknown_fields` attribute, if present.
+    pub deny_unknown_fields: Option<Span>,
+    /// Fieldless enum represented by its discriminant (`repr = "int"`).
+    pub repr_int: Option<Span>,
 }
 
 #[derive(Debug, Default)]
@@ -82,17 +119,32 @@ pub struct EnumVariantAttribute {
     pub skip: bool,
 }
 
-#[derive(Debug, Default)]
+#[derive(Default)]
 pub struct StructAttributes {
     pub rename_all: RenameRule,
+    pub conversion: ConversionAttributes,
+    pub deny_unknown_fields: bool,
 }
 
 #[derive(Debug, Default)]
+pub struct MethodAttributes {
+    pub rename: Option<StringWithSpan>,
+    pub skip: bool,
+}
+
+#[derive(Default)]
 pub struct FieldAttributes {
     pub rename: Option<StringWithSpan>,
     pub skip: bool,
     pub skip_if_empty: bool,
     pub default: bool,
+    pub flatten: bool,
+    pub with: Option<Path>,
+    /// Field is converted into `Value` using `TryFrom`, which makes the
+    /// whole conversion fallible.
+    pub try_into: Option<Span>,
+    /// Additional names accepted when converting from `Value`.
+    pub aliases: Vec<StringWithSpan>,
 }
 
 fn str_from_lit(lit: &Lit, span: Option<Span>) -> StringWithSpan {
@@ -107,6 +159,62 @@ fn str_from_lit(lit: &Lit, span: Option<Span>) -> StringWithSpan {
     }
 }
 
+fn type_from_lit(lit: &Lit) -> Type {
+    let str = str_from_lit(lit, None);
+    match syn::parse_str::<Type>(&str.value) {
+        Ok(ty) => ty,
+        Err(e) => Diagnostic::spanned(str.span, Level::Error, e.to_string()).abort(),
+    }
+}
+
+fn path_from_lit(lit: &Lit) -> Path {
+    let str = str_from_lit(lit, None);
+    match syn::parse_str::<Path>(&str.value) {
+        Ok(path) => path,
+        Err(e) => Diagnostic::spanned(str.span, Level::Error, e.to_string()).abort(),
+    }
+}
+
+/// Parses `from`, `into` and `try_from` attributes. Returns `false` for other
+/// attributes.
+fn parse_conversion_attribute(nv: &MetaNameValue, res: &mut ConversionAttributes) -> bool {
+    if nv.path == FROM {
+        res.from = Some(type_from_lit(&nv.lit));
+    } else if nv.path == INTO {
+        res.into = Some(type_from_lit(&nv.lit));
+    } else if nv.path == TRY_FROM {
+        res.try_from = Some(type_from_lit(&nv.lit));
+    } else {
+        return false;
+    }
+    if res.from.is_some() && res.try_from.is_some() {
+        Diagnostic::spanned(
+            nv.span(),
+            Level::Error,
+            "from and try_from attributes can not be used together".into(),
+        )
+        .abort();
+    }
+    true
+}
+
+/// Parses only `from`, `into` and `try_from` container attributes.
+pub fn parse_conversion_attributes(attrs: &[Attribute]) -> ConversionAttributes {
+    let mut res = ConversionAttributes::default();
+    for m in extract_irondash_meta(attrs) {
+        match m {
+            Meta::NameValue(nv) => {
+                parse_conversion_attribute(&nv, &mut res);
+            }
+            Meta::Path(path) if path == BOXED => {
+                res.boxed = true;
+            }
+            _ => {}
+        }
+    }
+    res
+}
+
 fn rename_rule_from_lit(lit: &Lit) -> RenameRule {
     match &lit {
         Lit::Str(str) => {
@@ -138,16 +246,56 @@ pub fn parse_enum_attributes(attrs: &[Attribute]) -> EnumAttributes {
                     res.content = Some(str_from_lit(&nv.lit, Some(nv.span())));
                 } else if nv.path == RENAME_ALL {
                     res.rename_all = rename_rule_from_lit(&nv.lit);
-                } else {
+                } else if nv.path == REPR {
+                    let repr = str_from_lit(&nv.lit, Some(nv.span()));
+                    if repr.value != "int" {
+                        Diagnostic::spanned(
+                            repr.span,
+                            Level::Error,
+                            "only repr = \"int\" is supported".into(),
+                        )
+                        .abort();
+                    }
+                    res.repr_int = Some(repr.span);
+                } else if !parse_conversion_attribute(nv, &mut res.conversion) {
                     Diagnostic::spanned(nv.span(), Level::Error, "unknown attribute".into())
                         .abort();
                 }
             }
+            Meta::Path(path) if path == UNTAGGED => {
+                res.untagged = true;
+            }
+            Meta::Path(path) if path == DENY_UNKNOWN_FIELDS => {
+                res.deny_unknown_fields = Some(path.span());
+            }
+            Meta::Path(path) if path == BOXED => {
+                res.conversion.boxed = true;
+            }
             _ => {
                 Diagnostic::spanned(m.span(), Level::Error, "unknown attribute".into()).abort();
             }
         }
     }
+    if let Some(tag) = &res.tag {
+        if res.untagged || res.repr_int.is_some() {
+            Diagnostic::spanned(
+                tag.span,
+                Level::Error,
+                "tag attribute can not be used together with 'untagged' or 'repr'".into(),
+            )
+            .abort();
+        }
+    }
+    if let Some(repr) = res.repr_int {
+        if res.untagged {
+            Diagnostic::spanned(
+                repr,
+                Level::Error,
+                "repr attribute can not be used together with 'untagged'".into(),
+            )
+            .abort();
+        }
+    }
     if let Some(content) = &res.content {
         if res.tag.is_none() {
             Diagnostic::spanned(
@@ -199,11 +347,17 @@ pub fn parse_struct_attributes(attrs: &[Attribute]) -> StructAttributes {
             Meta::NameValue(nv) => {
                 if nv.path == RENAME_ALL {
                     res.rename_all = rename_rule_from_lit(&nv.lit);
-                } else {
+                } else if !parse_conversion_attribute(nv, &mut res.conversion) {
                     Diagnostic::spanned(nv.span(), Level::Error, "unknown attribute".into())
                         .abort();
                 }
             }
+            Meta::Path(path) if path == DENY_UNKNOWN_FIELDS => {
+                res.deny_unknown_fields = true;
+            }
+            Meta::Path(path) if path == BOXED => {
+                res.conversion.boxed = true;
+            }
             _ => {
                 Diagnostic::spanned(m.span(), Level::Error, "Unknown attribute".into()).abort();
             }
@@ -220,6 +374,10 @@ pub fn parse_field_attributes(attrs: &[Attribute]) -> FieldAttributes {
             Meta::NameValue(nv) => {
                 if nv.path == RENAME {
                     res.rename = Some(str_from_lit(&nv.lit, Some(nv.span())))
+                } else if nv.path == WITH {
+                    res.with = Some(path_from_lit(&nv.lit));
+                } else if nv.path == ALIAS {
+                    res.aliases.push(str_from_lit(&nv.lit, Some(nv.span())));
                 } else {
                     Diagnostic::spanned(nv.span(), Level::Error, "unknown attribute".into()).emit();
                 }
@@ -231,6 +389,71 @@ pub fn parse_field_attributes(attrs: &[Attribute]) -> FieldAttributes {
                     res.skip = true;
                 } else if path == SKIP_IF_EMPTY {
                     res.skip_if_empty = true;
+                } else if path == FLATTEN {
+                    res.flatten = true;
+                } else if path == TRY_INTO {
+                    res.try_into = Some(path.span());
+                } else {
+                    Diagnostic::spanned(path.span(), Level::Error, "unknown attribute".into())
+                        .emit();
+                }
+            }
+            _ => {
+                Diagnostic::spanned(m.span(), Level::Error, "unknown attribute".into()).emit();
+            }
+        }
+    }
+    if res.flatten {
+        if let Some(rename) = res.rename.iter().chain(res.aliases.iter()).next() {
+            Diagnostic::spanned(
+                rename.span,
+                Level::Error,
+                "rename and alias can not be used together with flatten".into(),
+            )
+            .emit();
+        }
+    }
+    if let (Some(span), Some(_)) = (res.try_into, &res.with) {
+        Diagnostic::spanned(
+            span,
+            Level::Error,
+            "try_into can not be used together with with".into(),
+        )
+        .emit();
+    }
+    res
+}
+
+pub fn parse_handler_attributes(args: &[NestedMeta]) -> StructAttributes {
+    let mut res = StructAttributes::default();
+    for arg in args {
+        match arg {
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path == RENAME_ALL => {
+                res.rename_all = rename_rule_from_lit(&nv.lit);
+            }
+            _ => {
+                Diagnostic::spanned(arg.span(), Level::Error, "unknown attribute".into()).abort();
+            }
+        }
+    }
+    res
+}
+
+pub fn parse_method_attributes(attrs: &[Attribute]) -> MethodAttributes {
+    let mut res = MethodAttributes::default();
+    let meta = extract_irondash_meta(attrs);
+    for m in &meta {
+        match m {
+            Meta::NameValue(nv) => {
+                if nv.path == RENAME {
+                    res.rename = Some(str_from_lit(&nv.lit, Some(nv.span())))
+                } else {
+                    Diagnostic::spanned(nv.span(), Level::Error, "unknown attribute".into()).emit();
+                }
+            }
+            Meta::Path(path) => {
+                if path == SKIP {
+                    res.skip = true;
                 } else {
                     Diagnostic::spanned(path.span(), Level::Error, "unknown attribute".into())
                         .emit();
@@ -1,17 +1,104 @@
 use proc_macro2::{Ident, TokenStream};
 use proc_macro_error::{Diagnostic, Level};
 use quote::{format_ident, quote};
-use syn::{Attribute, DataEnum, DataStruct, FieldsNamed, Variant};
+use syn::{spanned::Spanned, Attribute, DataEnum, DataStruct, FieldsNamed, Type, Variant};
 
 use crate::{
     attributes::{
         parse_enum_attributes, parse_enum_variant_attributes, parse_field_attributes,
-        parse_struct_attributes, EnumAttributes, StringWithSpan, StructAttributes,
+        parse_struct_attributes, EnumAttributes, FieldAttributes, StringWithSpan, StructAttributes,
     },
     case::RenameRule,
-    rename_field, rename_variant,
+    check_repr_int_enum, rename_field, rename_variant,
 };
 
+/// Converts the value to `ty` first and then to `Value`.
+fn into_via(ty: &Type) -> TokenStream {
+    quote! {
+        let __ns_value: #ty = __ns_value.into();
+        __ns_value.into()
+    }
+}
+
+/// Returns whether any field is marked with `try_into`, in which case
+/// `TryFrom<T> for Value` is implemented instead of `From<T>`.
+pub fn has_try_into_fields(data: &syn::Data) -> bool {
+    let fields: Vec<&syn::Field> = match data {
+        syn::Data::Struct(s) => s.fields.iter().collect(),
+        syn::Data::Enum(e) => e.variants.iter().flat_map(|v| v.fields.iter()).collect(),
+        syn::Data::Union(_) => Vec::new(),
+    };
+    fields
+        .into_iter()
+        .any(|f| parse_field_attributes(&f.attrs).try_into.is_some())
+}
+
+/// `u64` and `usize` only implement `TryFrom` for `Value`. Point user to
+/// `try_into` instead of failing with unsatisfied `Into<Value>` bound.
+fn check_unsigned_field(field: &syn::Field, attributes: &FieldAttributes) {
+    if attributes.skip || attributes.try_into.is_some() || attributes.with.is_some() {
+        return;
+    }
+    if let Type::Path(path) = &field.ty {
+        if let Some(ident) = path.path.get_ident() {
+            if ident == "u64" || ident == "usize" {
+                Diagnostic::spanned(
+                    field.ty.span(),
+                    Level::Error,
+                    format!(
+                        "{ident} can not be converted into Value infallibly; \
+                        mark the field with #[irondash(try_into)]"
+                    ),
+                )
+                .emit();
+            }
+        }
+    }
+}
+
+/// Converts the field into `Value`. Fields marked with `try_into` return early
+/// with the conversion error located at `segment`.
+fn field_value(
+    field_access: &TokenStream,
+    attributes: &FieldAttributes,
+    segment: Option<TokenStream>,
+) -> TokenStream {
+    if let Some(with) = &attributes.with {
+        return quote! { #with::to_value(#field_access) };
+    }
+    if attributes.try_into.is_none() {
+        return quote! { #field_access.into() };
+    }
+    let map_err = match segment {
+        Some(segment) => quote! {
+            .map_err(|e| ::core::convert::Into::<::irondash_message_channel::TryFromError>::into(e)
+                .at(::irondash_message_channel::PathSegment::#segment))
+        },
+        None => quote! {
+            .map_err(::core::convert::Into::<::irondash_message_channel::TryFromError>::into)
+        },
+    };
+    quote! {
+        <::irondash_message_channel::Value as ::core::convert::TryFrom<_>>::try_from(#field_access)
+            #map_err?
+    }
+}
+
+fn unnamed_field_values(fields: &syn::FieldsUnnamed, accesses: &[TokenStream]) -> Vec<TokenStream> {
+    fields
+        .unnamed
+        .iter()
+        .zip(accesses)
+        .enumerate()
+        .map(|(index, (field, access))| {
+            let attributes = parse_field_attributes(&field.attrs);
+            check_unsigned_field(field, &attributes);
+            let segment = (fields.unnamed.len() > 1).then(|| quote! { Index(#index) });
+            field_value(access, &attributes, segment)
+        })
+        .collect()
+}
+
 fn insert_fields(
     target: &Ident,
     prefix: Option<Ident>,
@@ -23,6 +110,7 @@ fn insert_fields(
         field: TokenStream,
     }
     let mut fields = Vec::<Field>::new();
+    let mut flattened = Vec::<TokenStream>::new();
 
     for field in &fields_named.named {
         let ident = field.ident.clone().unwrap();
@@ -30,25 +118,38 @@ fn insert_fields(
         if attributes.skip {
             continue;
         }
-        let string = rename_field(
-            &format!("{ident}"),
-            rename_rule,
-            &attributes.rename.map(|a| a.value),
-        );
+        check_unsigned_field(field, &attributes);
         let field_access = if let Some(prefix) = &prefix {
             quote! { #prefix.#ident }
         } else {
             quote! { #ident }
         };
+        if attributes.flatten {
+            let value = field_value(&field_access, &attributes, None);
+            flattened.push(quote! {
+                ::irondash_message_channel::derive_internal::flatten_into(&mut #target, #value);
+            });
+            continue;
+        }
+        let string = rename_field(
+            &format!("{ident}"),
+            rename_rule,
+            &attributes.rename.as_ref().map(|a| a.value.clone()),
+        );
+        let value = field_value(
+            &field_access,
+            &attributes,
+            Some(quote! { Field(::std::borrow::Cow::Borrowed(#string)) }),
+        );
         let token_stream = if attributes.skip_if_empty {
             quote! {
                 if (&&::irondash_message_channel::derive_internal::Wrap(& #field_access)).is_none() == false {
-                    #target.push( ( #string.into(), #field_access.into() ) );
+                    #target.push( ( #string.into(), #value ) );
                 }
             }
         } else {
             quote! {
-                #target.push( ( #string.into(), #field_access.into() ) );
+                #target.push( ( #string.into(), #value ) );
             }
         };
         fields.push(Field {
@@ -66,6 +167,9 @@ fn insert_fields(
         #(
             #fields
         )*
+        #(
+            #flattened
+        )*
     }
 }
 
@@ -83,6 +187,15 @@ impl FromEnum {
     }
 
     pub fn process(self, data: DataEnum) -> TokenStream {
+        if let Some(into) = &self.attributes.conversion.into {
+            return into_via(into);
+        }
+        if self.attributes.repr_int.is_some() {
+            check_repr_int_enum(&data);
+            return quote! {
+                ::irondash_message_channel::Value::I64(__ns_value as i64)
+            };
+        }
         let variants: Vec<TokenStream> = data
             .variants
             .into_iter()
@@ -121,6 +234,9 @@ impl FromEnum {
                 };
                 let insert = insert_fields(&target, None, &fields, &attributes.rename_all);
                 let epilogue = match (&self.attributes.tag, &self.attributes.content) {
+                    (None, None) if self.attributes.untagged => quote! {
+                        ::irondash_message_channel::Value::Map(#target.into())
+                    },
                     (None, None) => quote! {
                         let __ns_value = ::irondash_message_channel::Value::Map(#target.into());
                         #create_vec;
@@ -172,22 +288,28 @@ impl FromEnum {
                 let idents: Vec<Ident> = (0..fields.unnamed.len())
                     .map(|i| format_ident!("v{}", i))
                     .collect();
-                let value = if idents.len() == 1 {
-                    let name = idents.first().unwrap();
-                    quote! {
-                        #name.into()
-                    }
+                let accesses: Vec<TokenStream> = idents.iter().map(|i| quote! { #i }).collect();
+                let values = unnamed_field_values(&fields, &accesses);
+                let value = if values.len() == 1 {
+                    values.into_iter().next().unwrap()
                 } else {
                     quote! {
                         {
                             let mut __ns_vec = ::std::vec::Vec::<::irondash_message_channel::Value>::new();
                             #(
-                                __ns_vec.push(#idents.into());
+                                __ns_vec.push(#values);
                             )*
                             ::irondash_message_channel::Value::List(__ns_vec)
                         }
                     }
                 };
+                if self.attributes.untagged {
+                    return Some(quote! {
+                        #ident ( #( #idents, )* ) => {
+                            #value
+                        }
+                    });
+                }
                 let insert = if let (Some(tag), Some(content)) =
                     (&self.attributes.tag, &self.attributes.content)
                 {
@@ -212,6 +334,11 @@ impl FromEnum {
                 })
             }
             syn::Fields::Unit => {
+                if self.attributes.untagged {
+                    return Some(quote! {
+                        #ident => ::irondash_message_channel::Value::Null
+                    });
+                }
                 let result = if let Some(tag) = &self.attributes.tag {
                     // { 'tag': 'enumName' }
                     let tag = &tag.value;
@@ -259,6 +386,9 @@ impl FromStruct {
     }
 
     pub fn process(self, data: DataStruct) -> TokenStream {
+        if let Some(into) = &self.attributes.conversion.into {
+            return into_via(into);
+        }
         match data.fields {
             syn::Fields::Named(fields) => {
                 let target = format_ident!("__ns_vec");
@@ -275,19 +405,19 @@ impl FromStruct {
                 }
             }
             syn::Fields::Unnamed(fields) => {
-                let idents: Vec<syn::Index> =
-                    (0..fields.unnamed.len()).map(syn::Index::from).collect();
+                let accesses: Vec<TokenStream> = (0..fields.unnamed.len())
+                    .map(syn::Index::from)
+                    .map(|i| quote! { __ns_value.#i })
+                    .collect();
+                let values = unnamed_field_values(&fields, &accesses);
 
-                if idents.len() == 1 {
-                    let name = idents.first().unwrap();
-                    quote! {
-                        __ns_value.#name.into()
-                    }
+                if values.len() == 1 {
+                    values.into_iter().next().unwrap()
                 } else {
                     quote! {
                         let mut __ns_vec = ::std::vec::Vec::<::irondash_message_channel::Value>::new();
                         #(
-                            __ns_vec.push(__ns_value.#idents.into());
+                            __ns_vec.push(#values);
                         )*
                         ::irondash_message_channel::Value::List(__ns_vec)
                     }
@@ -0,0 +1,180 @@
+use proc_macro2::TokenStream;
+use proc_macro_error::{Diagnostic, Level};
+use quote::{format_ident, quote};
+use syn::{spanned::Spanned, FnArg, ImplItem, ImplItemMethod, ItemImpl, Pat, Type};
+
+use crate::{
+    attributes::{parse_method_attributes, StructAttributes, IRONDASH},
+    rename_field,
+};
+
+/// Methods of `AsyncMethodHandler` that are forwarded to inherent methods
+/// with same name if present in the impl block.
+const FORWARDED_METHODS: &[&str] = &["assign_weak_self", "assign_invoker", "on_isolate_destroyed"];
+
+pub struct Handler {
+    attributes: StructAttributes,
+}
+
+fn is_isolate_id(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .map(|s| s.ident == "IsolateId")
+            .unwrap_or(false),
+        _ => false,
+    }
+}
+
+impl Handler {
+    pub fn new(attributes: StructAttributes) -> Self {
+        Self { attributes }
+    }
+
+    fn process_method(&self, method: &ImplItemMethod) -> TokenStream {
+        let ident = &method.sig.ident;
+        let attributes = parse_method_attributes(&method.attrs);
+        let name = rename_field(
+            &format!("{ident}"),
+            &self.attributes.rename_all,
+            &attributes.rename.map(|a| a.value),
+        );
+        let mut has_receiver = false;
+        let mut has_args = false;
+        let mut conversions = Vec::<TokenStream>::new();
+        let mut params = Vec::<TokenStream>::new();
+        for input in &method.sig.inputs {
+            match input {
+                FnArg::Receiver(receiver) => {
+                    if receiver.reference.is_none() || receiver.mutability.is_some() {
+                        Diagnostic::spanned(
+                            receiver.span(),
+                            Level::Error,
+                            "handler methods must take &self".into(),
+                        )
+                        .abort();
+                    }
+                    has_receiver = true;
+                }
+                FnArg::Typed(typed) => {
+                    if is_isolate_id(&typed.ty) {
+                        params.push(quote! { __irondash_call.isolate });
+                    } else if has_args {
+                        Diagnostic::spanned(
+                            typed.span(),
+                            Level::Error,
+                            "handler methods can only have one argument besides IsolateId".into(),
+                        )
+                        .abort();
+                    } else {
+                        has_args = true;
+                        let ty = &typed.ty;
+                        let arg = match typed.pat.as_ref() {
+                            Pat::Ident(pat) => format_ident!("__irondash_{}", pat.ident),
+                            _ => format_ident!("__irondash_args"),
+                        };
+                        conversions.push(quote! {
+                            let #arg: #ty = ::irondash_message_channel::derive_internal::convert_args(
+                                __irondash_call.args,
+                            )?;
+                        });
+                        params.push(quote! { #arg });
+                    }
+                }
+            }
+        }
+        if !has_receiver {
+            Diagnostic::spanned(
+                method.sig.span(),
+                Level::Error,
+                "handler methods must take &self".into(),
+            )
+            .abort();
+        }
+        quote! {
+            #name => {
+                #(#conversions)*
+                ::irondash_message_channel::IntoPlatformResult::into_platform_result(
+                    self.#ident(#(#params),*).await,
+                )
+            }
+        }
+    }
+
+    pub fn process(&self, mut item: ItemImpl) -> TokenStream {
+        if let Some((_, path, _)) = &item.trait_ {
+            Diagnostic::spanned(
+                path.span(),
+                Level::Error,
+                "#[handler] must be used on inherent impl block".into(),
+            )
+            .abort();
+        }
+        let mut arms = Vec::<TokenStream>::new();
+        let mut forwarded = Vec::<TokenStream>::new();
+        for impl_item in &mut item.items {
+            if let ImplItem::Method(method) = impl_item {
+                let name = method.sig.ident.to_string();
+                if method.sig.asyncness.is_some() {
+                    if !parse_method_attributes(&method.attrs).skip {
+                        arms.push(self.process_method(method));
+                    }
+                } else if FORWARDED_METHODS.contains(&name.as_str()) {
+                    let ident = &method.sig.ident;
+                    forwarded.push(match name.as_str() {
+                        "assign_weak_self" => quote! {
+                            fn assign_weak_self(&self, weak_self: ::std::rc::Weak<Self>) {
+                                self.#ident(weak_self)
+                            }
+                        },
+                        "assign_invoker" => quote! {
+                            fn assign_invoker(
+                                &self,
+                                invoker: ::irondash_message_channel::AsyncMethodInvoker,
+                            ) {
+                                self.#ident(invoker)
+                            }
+                        },
+                        _ => quote! {
+                            fn on_isolate_destroyed(
+                                &self,
+                                isolate: ::irondash_message_channel::IsolateId,
+                            ) {
+                                self.#ident(isolate)
+                            }
+                        },
+                    });
+                }
+                method.attrs.retain(|a| a.path != IRONDASH);
+            }
+        }
+
+        let self_ty = &item.self_ty;
+        let (impl_generics, _, where_clause) = item.generics.split_for_impl();
+
+        quote! {
+            #item
+
+            #[::irondash_message_channel::derive_internal::async_trait(?Send)]
+            impl #impl_generics ::irondash_message_channel::AsyncMethodHandler for #self_ty #where_clause {
+                async fn on_method_call(
+                    &self,
+                    __irondash_call: ::irondash_message_channel::MethodCall,
+                ) -> ::irondash_message_channel::PlatformResult {
+                    match __irondash_call.method.as_str() {
+                        #(#arms)*
+                        _ => Err(::irondash_message_channel::PlatformError {
+                            code: "invalid_method".into(),
+                            message: Some(format!("Unknown Method: {}", __irondash_call.method)),
+                            detail: ::irondash_message_channel::Value::Null,
+                        }),
+                    }
+                }
+
+                #(#forwarded)*
+            }
+        }
+    }
+}
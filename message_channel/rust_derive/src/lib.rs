@@ -1,15 +1,20 @@
 use case::RenameRule;
 use proc_macro_error::{proc_macro_error, Diagnostic, Level};
-use syn::DeriveInput;
+use syn::{spanned::Spanned, AttributeArgs, DeriveInput, ItemImpl};
 
 use quote::quote;
 
 mod attributes;
 mod case;
 mod from;
+mod handler;
+mod schema;
 mod try_into;
 
+use attributes::{parse_conversion_attributes, parse_handler_attributes};
 use from::*;
+use handler::Handler;
+use schema::*;
 use try_into::*;
 
 #[proc_macro_derive(IntoValue, attributes(irondash))]
@@ -17,6 +22,8 @@ use try_into::*;
 pub fn into_value(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let ast = syn::parse_macro_input!(input as DeriveInput);
     let name = ast.ident;
+    let fallible =
+        parse_conversion_attributes(&ast.attrs).into.is_none() && has_try_into_fields(&ast.data);
     let token_stream = match ast.data {
         syn::Data::Struct(s) => FromStruct::new(name.clone(), ast.attrs).process(s),
         syn::Data::Enum(e) => FromEnum::new(name.clone(), ast.attrs).process(e),
@@ -32,12 +39,26 @@ pub fn into_value(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 
     let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
 
-    let tokens = quote! {
-        #[automatically_derived]
-        impl #impl_generics From<#name #ty_generics> for ::irondash_message_channel::Value #where_clause {
-            fn from(__ns_value: #name #ty_generics) -> Self {
-                use ::irondash_message_channel::derive_internal::IsNone;
-                #token_stream
+    // Fields marked with `#[irondash(try_into)]` can fail to convert.
+    let tokens = if fallible {
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics core::convert::TryFrom<#name #ty_generics> for ::irondash_message_channel::Value #where_clause {
+                type Error = ::irondash_message_channel::TryFromError;
+                fn try_from(__ns_value: #name #ty_generics) -> Result<Self, Self::Error> {
+                    use ::irondash_message_channel::derive_internal::IsNone;
+                    Ok({ #token_stream })
+                }
+            }
+        }
+    } else {
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics From<#name #ty_generics> for ::irondash_message_channel::Value #where_clause {
+                fn from(__ns_value: #name #ty_generics) -> Self {
+                    use ::irondash_message_channel::derive_internal::IsNone;
+                    #token_stream
+                }
             }
         }
     };
@@ -49,6 +70,7 @@ pub fn into_value(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 pub fn try_from_value(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let ast = syn::parse_macro_input!(input as DeriveInput);
     let name = ast.ident;
+    let boxed = parse_conversion_attributes(&ast.attrs).boxed;
     let token_stream = match ast.data {
         syn::Data::Struct(s) => TryIntoStruct::new(name.clone(), ast.attrs).process(s),
         syn::Data::Enum(e) => TryIntoEnum::new(name.clone(), ast.attrs).process(e),
@@ -64,19 +86,126 @@ pub fn try_from_value(input: proc_macro::TokenStream) -> proc_macro::TokenStream
 
     let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
 
+    // Opt-in (`#[irondash(boxed)]`) to not conflict with existing impls.
+    // Allows recursive types through `Box<Self>` fields.
+    let boxed = boxed.then(|| {
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics core::convert::TryFrom<::irondash_message_channel::Value> for ::std::boxed::Box<#name #ty_generics> #where_clause {
+                type Error = ::irondash_message_channel::TryFromError;
+                fn try_from(__ns_value: ::irondash_message_channel::Value) -> Result<Self, Self::Error> {
+                    <#name #ty_generics as core::convert::TryFrom<::irondash_message_channel::Value>>::try_from(__ns_value)
+                        .map(::std::boxed::Box::new)
+                }
+            }
+        }
+    });
+
     let tokens = quote! {
         #[automatically_derived]
         impl #impl_generics core::convert::TryFrom<::irondash_message_channel::Value> for #name #ty_generics #where_clause {
             type Error = ::irondash_message_channel::TryFromError;
             fn try_from(__ns_value: ::irondash_message_channel::Value) -> Result<Self, Self::Error> {
-                use ::irondash_message_channel::derive_internal::Assign;
+                use ::irondash_message_channel::derive_internal::{Assign, AssignFlatten};
                 #token_stream
             }
         }
+
+        #boxed
+    };
+    proc_macro::TokenStream::from(tokens)
+}
+
+/// Implements `ValueSchema`, describing how the type is represented in `Value`
+/// by `IntoValue` and `TryFromValue` derives. Uses the same `irondash`
+/// attributes.
+#[proc_macro_derive(ValueSchema, attributes(irondash))]
+#[proc_macro_error]
+pub fn value_schema(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let ast = syn::parse_macro_input!(input as DeriveInput);
+    let name = ast.ident;
+    if !ast.generics.params.is_empty() {
+        Diagnostic::spanned(
+            ast.generics.span(),
+            Level::Error,
+            "derive(ValueSchema) is not supported for generic types".into(),
+        )
+        .abort();
+    }
+    // Types converted through another type share its representation.
+    if let Some(ty) = parse_conversion_attributes(&ast.attrs).value_type() {
+        let tokens = quote! {
+            #[automatically_derived]
+            impl ::irondash_message_channel::ValueSchema for #name {
+                fn value_type() -> ::irondash_message_channel::ValueType {
+                    <#ty as ::irondash_message_channel::ValueSchema>::value_type()
+                }
+
+                fn register(registry: &mut ::irondash_message_channel::SchemaRegistry) {
+                    <#ty as ::irondash_message_channel::ValueSchema>::register(registry);
+                }
+            }
+        };
+        return proc_macro::TokenStream::from(tokens);
+    }
+    let (schema, types) = match ast.data {
+        syn::Data::Struct(s) => SchemaStruct::new(name.clone(), ast.attrs).process(s),
+        syn::Data::Enum(e) => SchemaEnum::new(name.clone(), ast.attrs).process(e),
+        syn::Data::Union(_) => {
+            Diagnostic::spanned(
+                name.span(),
+                Level::Error,
+                "derive(ValueSchema) is not supported for unions".into(),
+            )
+            .abort();
+        }
+    };
+    let name_string = format!("{name}");
+
+    let tokens = quote! {
+        #[automatically_derived]
+        impl ::irondash_message_channel::ValueSchema for #name {
+            fn value_type() -> ::irondash_message_channel::ValueType {
+                ::irondash_message_channel::ValueType::Named(#name_string.into())
+            }
+
+            fn register(registry: &mut ::irondash_message_channel::SchemaRegistry) {
+                if !registry.begin(#name_string) {
+                    return;
+                }
+                #(
+                    <#types as ::irondash_message_channel::ValueSchema>::register(registry);
+                )*
+                registry.insert(#schema);
+            }
+        }
     };
     proc_macro::TokenStream::from(tokens)
 }
 
+/// Implements `AsyncMethodHandler` for the type. Each `async fn` in the impl
+/// block becomes a method named after the function, subject to optional
+/// `rename_all` rule and `#[irondash(rename = "...")]` attribute. Methods can
+/// be excluded using `#[irondash(skip)]`.
+///
+/// Methods must take `&self` and at most one argument converted from
+/// `Value`. Parameters of type `IsolateId` are injected from the method call.
+/// Return value can be anything implementing `IntoPlatformResult`.
+///
+/// `assign_weak_self`, `assign_invoker` and `on_isolate_destroyed` functions
+/// in the impl block are forwarded to the `AsyncMethodHandler`.
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn handler(
+    attr: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let args = syn::parse_macro_input!(attr as AttributeArgs);
+    let item = syn::parse_macro_input!(item as ItemImpl);
+    let attributes = parse_handler_attributes(&args);
+    proc_macro::TokenStream::from(Handler::new(attributes).process(item))
+}
+
 pub(crate) fn rename_field(
     original: &str,
     rename_rule: &RenameRule,
@@ -98,3 +227,18 @@ pub(crate) fn rename_variant(
     }
     rename_rule.apply_to_variant(original)
 }
+
+/// Enums with `repr = "int"` are converted using `as i64` cast, which
+/// requires all variants to be fieldless.
+pub(crate) fn check_repr_int_enum(data: &syn::DataEnum) {
+    for variant in &data.variants {
+        if !matches!(variant.fields, syn::Fields::Unit) {
+            Diagnostic::spanned(
+                variant.span(),
+                Level::Error,
+                "repr = \"int\" requires all enum variants to be fieldless".into(),
+            )
+            .abort();
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use proc_macro2::{Ident, TokenStream};
+use proc_macro_error::{Diagnostic, Level};
+use quote::quote;
+use syn::{Attribute, DataEnum, DataStruct, Fields, Type};
+
+use crate::{
+    attributes::{
+        parse_enum_attributes, parse_enum_variant_attributes, parse_field_attributes,
+        parse_struct_attributes,
+    },
+    case::RenameRule,
+    check_repr_int_enum, rename_field, rename_variant,
+};
+
+/// Returns expression creating `FieldsSchema` and types of all fields.
+fn fields_schema(fields: &Fields, rename_rule: &RenameRule) -> (TokenStream, Vec<Type>) {
+    let mut types = Vec::<Type>::new();
+    let schema = match fields {
+        Fields::Named(named) => {
+            let mut fields = Vec::<TokenStream>::new();
+            for field in &named.named {
+                let attributes = parse_field_attributes(&field.attrs);
+                if attributes.skip {
+                    continue;
+                }
+                let ident = field.ident.as_ref().unwrap();
+                let name = format!("{ident}");
+                let key = rename_field(&name, rename_rule, &attributes.rename.map(|a| a.value));
+                let ty = &field.ty;
+                // Representation of fields converted through `with` module is
+                // not known.
+                let value_type = match &attributes.with {
+                    Some(_) => quote! { ::irondash_message_channel::ValueType::Any },
+                    None => {
+                        quote! { <#ty as ::irondash_message_channel::ValueSchema>::value_type() }
+                    }
+                };
+                let skip_if_empty = attributes.skip_if_empty;
+                let default = attributes.default;
+                let flatten = attributes.flatten;
+                fields.push(quote! {
+                    ::irondash_message_channel::FieldSchema {
+                        name: #name.into(),
+                        key: #key.into(),
+                        ty: #value_type,
+                        skip_if_empty: #skip_if_empty,
+                        default: #default,
+                        flatten: #flatten,
+                    }
+                });
+                if attributes.with.is_none() {
+                    types.push(ty.clone());
+                }
+            }
+            quote! {
+                ::irondash_message_channel::FieldsSchema::Named(vec![#(#fields),*])
+            }
+        }
+        Fields::Unnamed(unnamed) => {
+            types.extend(unnamed.unnamed.iter().map(|f| f.ty.clone()));
+            quote! {
+                ::irondash_message_channel::FieldsSchema::Unnamed(vec![
+                    #(<#types as ::irondash_message_channel::ValueSchema>::value_type()),*
+                ])
+            }
+        }
+        Fields::Unit => quote! { ::irondash_message_channel::FieldsSchema::Unit },
+    };
+    (schema, types)
+}
+
+fn optional_string(value: Option<String>) -> TokenStream {
+    match value {
+        Some(value) => quote! { ::std::option::Option::Some(#value.into()) },
+        None => quote! { ::std::option::Option::None },
+    }
+}
+
+pub struct SchemaStruct {
+    name: Ident,
+    attrs: Vec<Attribute>,
+}
+
+impl SchemaStruct {
+    pub fn new(name: Ident, attrs: Vec<Attribute>) -> Self {
+        Self { name, attrs }
+    }
+
+    pub fn process(self, data: DataStruct) -> (TokenStream, Vec<Type>) {
+        if let Fields::Unit = data.fields {
+            Diagnostic::spanned(
+                self.name.span(),
+                Level::Error,
+                "unit structs are not supported".into(),
+            )
+            .abort();
+        }
+        let attributes = parse_struct_attributes(&self.attrs);
+        let (fields, types) = fields_schema(&data.fields, &attributes.rename_all);
+        let name = format!("{}", self.name);
+        let schema = quote! {
+            ::irondash_message_channel::TypeSchema::Struct {
+                name: #name.into(),
+                fields: #fields,
+            }
+        };
+        (schema, types)
+    }
+}
+
+pub struct SchemaEnum {
+    name: Ident,
+    attrs: Vec<Attribute>,
+}
+
+impl SchemaEnum {
+    pub fn new(name: Ident, attrs: Vec<Attribute>) -> Self {
+        Self { name, attrs }
+    }
+
+    pub fn process(self, data: DataEnum) -> (TokenStream, Vec<Type>) {
+        let attributes = parse_enum_attributes(&self.attrs);
+        if attributes.repr_int.is_some() {
+            check_repr_int_enum(&data);
+        }
+        let mut variants = Vec::<TokenStream>::new();
+        let mut types = Vec::<Type>::new();
+        for variant in &data.variants {
+            let variant_attributes = parse_enum_variant_attributes(&variant.attrs);
+            if variant_attributes.skip {
+                continue;
+            }
+            let name = format!("{}", variant.ident);
+            let key = rename_variant(
+                &name,
+                &attributes.rename_all,
+                &variant_attributes.rename.map(|a| a.value),
+            );
+            let ident = &variant.ident;
+            let discriminant = match attributes.repr_int {
+                Some(_) => quote! { ::std::option::Option::Some(Self::#ident as i64) },
+                None => quote! { ::std::option::Option::None },
+            };
+            let (fields, variant_types) =
+                fields_schema(&variant.fields, &variant_attributes.rename_all);
+            types.extend(variant_types);
+            variants.push(quote! {
+                ::irondash_message_channel::VariantSchema {
+                    name: #name.into(),
+                    key: #key.into(),
+                    fields: #fields,
+                    discriminant: #discriminant,
+                }
+            });
+        }
+        let name = format!("{}", self.name);
+        let tag = optional_string(attributes.tag.map(|t| t.value));
+        let content = optional_string(attributes.content.map(|c| c.value));
+        let untagged = attributes.untagged;
+        let schema = quote! {
+            ::irondash_message_channel::TypeSchema::Enum {
+                name: #name.into(),
+                tag: #tag,
+                content: #content,
+                untagged: #untagged,
+                variants: vec![#(#variants),*],
+            }
+        };
+        (schema, types)
+    }
+}
@@ -3,18 +3,37 @@ use proc_macro_error::{Diagnostic, Level};
 use quote::quote;
 use syn::{
     spanned::Spanned, Attribute, DataEnum, DataStruct, Fields, FieldsNamed, FieldsUnnamed, Ident,
-    Type,
+    Path, Type,
 };
 
 use crate::{
     attributes::{
         parse_enum_attributes, parse_enum_variant_attributes, parse_field_attributes,
-        parse_struct_attributes, EnumAttributes, StringWithSpan, StructAttributes,
+        parse_struct_attributes, ConversionAttributes, EnumAttributes, StringWithSpan,
+        StructAttributes,
     },
     case::RenameRule,
-    rename_field, rename_variant,
+    check_repr_int_enum, rename_field, rename_variant,
 };
 
+/// Converts the value through type given by `from` or `try_from` attribute.
+fn try_from_via(conversion: &ConversionAttributes) -> Option<TokenStream> {
+    if let Some(from) = &conversion.from {
+        Some(quote! {
+            let __ns_value: #from = ::core::convert::TryInto::try_into(__ns_value)?;
+            return ::core::result::Result::Ok(::core::convert::From::from(__ns_value));
+        })
+    } else {
+        conversion.try_from.as_ref().map(|try_from| {
+            quote! {
+                let __ns_value: #try_from = ::core::convert::TryInto::try_into(__ns_value)?;
+                return <Self as ::core::convert::TryFrom<#try_from>>::try_from(__ns_value)
+                    .map_err(|e| Self::Error::OtherError(e.to_string()));
+            }
+        })
+    }
+}
+
 pub struct TryIntoEnum {
     name: Ident,
     attributes: EnumAttributes,
@@ -29,13 +48,138 @@ impl TryIntoEnum {
     }
 
     pub fn process(self, data: DataEnum) -> TokenStream {
-        if self.attributes.tag.is_none() {
+        if let Some(res) = try_from_via(&self.attributes.conversion) {
+            return res;
+        }
+        if self.attributes.repr_int.is_some() {
+            self.process_repr_int(&data)
+        } else if self.attributes.untagged {
+            self.process_untagged(&data)
+        } else if self.attributes.tag.is_none() {
             self.process_no_tag(&data)
         } else {
             self.process_tag(&data)
         }
     }
 
+    fn process_repr_int(&self, data: &DataEnum) -> TokenStream {
+        check_repr_int_enum(data);
+        if let Some(span) = self.attributes.deny_unknown_fields {
+            Diagnostic::spanned(
+                span,
+                Level::Error,
+                "deny_unknown_fields can not be used together with repr = \"int\"".into(),
+            )
+            .abort();
+        }
+        let variants: Vec<&Ident> = data
+            .variants
+            .iter()
+            .filter(|v| !parse_enum_variant_attributes(&v.attrs).skip)
+            .map(|v| &v.ident)
+            .collect();
+        quote! {
+            let __ns_value: i64 = ::core::convert::TryInto::try_into(__ns_value)?;
+            #(
+                if __ns_value == Self::#variants as i64 {
+                    return ::core::result::Result::Ok(Self::#variants);
+                }
+            )*
+            ::core::result::Result::Err(Self::Error::OtherError(format!("unknown enum value {}", __ns_value)))
+        }
+    }
+
+    /// Tries variants in declaration order and returns the first one that
+    /// converts successfully. Conversion consumes the value, so it is cloned
+    /// for every variant but the last one with fields. If no variant matches,
+    /// error of the last variant with fields is returned.
+    fn process_untagged(&self, data: &DataEnum) -> TokenStream {
+        let variants: Vec<_> = data
+            .variants
+            .iter()
+            .map(|v| (v, parse_enum_variant_attributes(&v.attrs)))
+            .filter(|(_, attributes)| !attributes.skip)
+            .collect();
+        let last_with_fields = variants
+            .iter()
+            .rposition(|(v, _)| !matches!(v.fields, syn::Fields::Unit));
+        if let (Some(span), None) = (self.attributes.deny_unknown_fields, last_with_fields) {
+            Diagnostic::spanned(
+                span,
+                Level::Error,
+                "deny_unknown_fields has no effect on untagged enum without variant fields".into(),
+            )
+            .abort();
+        }
+        let mut tokens = Vec::<TokenStream>::new();
+        for (index, (variant, attributes)) in variants.iter().enumerate() {
+            let ident = &variant.ident;
+            if let syn::Fields::Unit = &variant.fields {
+                if last_with_fields.is_some_and(|last| index > last) {
+                    // The value has been consumed already.
+                    tokens.push(quote! {
+                        if __ns_is_null {
+                            return ::core::result::Result::Ok(Self::#ident);
+                        }
+                    });
+                } else {
+                    tokens.push(quote! {
+                        if let ::irondash_message_channel::Value::Null = __ns_value {
+                            return ::core::result::Result::Ok(Self::#ident);
+                        }
+                    });
+                }
+                continue;
+            }
+            let body = process_struct(
+                variant.span(),
+                &variant.fields,
+                Some(ident),
+                attributes.rename_all,
+                None,
+                self.attributes.deny_unknown_fields.is_some(),
+            );
+            let variant_fn = quote! {
+                let __ns_variant = |__ns_value: ::irondash_message_channel::Value| -> ::core::result::Result<Self, Self::Error> {
+                    #body
+                };
+            };
+            if Some(index) == last_with_fields {
+                tokens.push(quote! {
+                    #variant_fn
+                    let __ns_is_null = matches!(__ns_value, ::irondash_message_channel::Value::Null);
+                    let __ns_error = match __ns_variant(__ns_value) {
+                        ::core::result::Result::Ok(__ns_res) => return ::core::result::Result::Ok(__ns_res),
+                        ::core::result::Result::Err(__ns_error) => __ns_error,
+                    };
+                });
+            } else {
+                tokens.push(quote! {
+                    #variant_fn
+                    if let ::core::result::Result::Ok(__ns_res) = __ns_variant(__ns_value.clone()) {
+                        return ::core::result::Result::Ok(__ns_res);
+                    }
+                });
+            }
+        }
+        let error = match last_with_fields {
+            Some(_) => quote! { __ns_error },
+            None => {
+                let error = format!(
+                    "data did not match any variant of untagged enum {}",
+                    self.name
+                );
+                quote! { Self::Error::OtherError(#error.into()) }
+            }
+        };
+        quote! {
+            #(
+                #tokens
+            )*
+            ::core::result::Result::Err(#error)
+        }
+    }
+
     fn process_tag(&self, data: &DataEnum) -> TokenStream {
         let tag = self.attributes.tag.clone().unwrap().value;
         let (strings, variants) = self.process_variants(data, true);
@@ -115,6 +259,12 @@ impl TryIntoEnum {
         data: &DataEnum,
         allow_unit: bool,
     ) -> (Vec<String>, Vec<TokenStream>) {
+        // Without content the tag is stored alongside the variant fields and
+        // must not be passed to flattened fields.
+        let tag = match &self.attributes.content {
+            Some(_) => None,
+            None => self.attributes.tag.as_ref().map(|t| t.value.as_str()),
+        };
         let mut strings = Vec::<String>::new();
         let mut variants = Vec::<TokenStream>::new();
         for variant in &data.variants {
@@ -132,12 +282,33 @@ impl TryIntoEnum {
                     });
                 }
             } else {
-                variants.push(process_struct(
+                let string = self.variant_ident_to_string(&variant.ident, &attributes.rename);
+                let body = process_struct(
                     variant.span(),
                     &variant.fields,
                     Some(ident),
                     attributes.rename_all,
-                ));
+                    tag,
+                    self.attributes.deny_unknown_fields.is_some(),
+                );
+                // Variant payload is stored under the variant name or content
+                // key; report conversion errors at that key.
+                let key = match (&self.attributes.tag, &self.attributes.content) {
+                    (None, _) => Some(string.clone()),
+                    (Some(_), Some(content)) => Some(content.value.clone()),
+                    (Some(_), None) => None,
+                };
+                variants.push(match key {
+                    Some(key) => quote! {
+                        let __ns_variant = |__ns_value: ::irondash_message_channel::Value| -> ::core::result::Result<Self, Self::Error> {
+                            #body
+                        };
+                        return __ns_variant(__ns_value).map_err(|e| {
+                            e.at(::irondash_message_channel::PathSegment::Field(::std::borrow::Cow::Borrowed(#key)))
+                        });
+                    },
+                    None => body,
+                });
             }
             strings.push(self.variant_ident_to_string(&variant.ident, &attributes.rename));
         }
@@ -184,9 +355,17 @@ fn process_struct(
     fields: &Fields,
     constructor_suffix: Option<&Ident>,
     rename_rule: RenameRule,
+    tag: Option<&str>,
+    deny_unknown_fields: bool,
 ) -> TokenStream {
     match fields {
-        Fields::Named(named) => process_struct_named(named, constructor_suffix, rename_rule),
+        Fields::Named(named) => process_struct_named(
+            named,
+            constructor_suffix,
+            rename_rule,
+            tag,
+            deny_unknown_fields,
+        ),
         Fields::Unnamed(unnamed) => process_struct_unnamed(unnamed, constructor_suffix),
         Fields::Unit => {
             Diagnostic::spanned(span, Level::Error, "unit structs are not supported".into()).abort()
@@ -210,13 +389,14 @@ fn process_struct_unnamed(
         quote! {
             return Ok(#constructor ( {
                 let mut res = std::option::Option::<#ty>::None;
-                (&mut &mut &mut ::irondash_message_channel::derive_internal::WrapMut(&mut res)).assign(__ns_value, false)?;
+                (&mut &mut &mut ::irondash_message_channel::derive_internal::WrapMut(&mut res)).assign(__ns_value, false, None)?;
                 res.unwrap()
             } ));
         }
     } else {
         let rows: Vec<TokenStream> = unnamed.unnamed.iter()
-            .map(|field| {
+            .enumerate()
+            .map(|(index, field)| {
                 let ty= &field.ty;
                 quote! {
                     {
@@ -224,6 +404,7 @@ fn process_struct_unnamed(
                         (&mut &mut &mut ::irondash_message_channel::derive_internal::WrapMut(&mut res)).assign(
                             iter.next().ok_or_else(||Self::Error::OtherError("missing value".into()))?,
                             false,
+                            Some(::irondash_message_channel::PathSegment::Index(#index)),
                         )?;
                         res.unwrap()
                     }
@@ -248,19 +429,69 @@ fn process_struct_unnamed(
     }
 }
 
+/// Assigns `value` to `Option<T>` field `ident`, converting it either through
+/// `TryFrom<Value>` or the `with` module. Conversion errors are reported
+/// at map key `key`, if any.
+fn assign_field(
+    ident: &Ident,
+    with: &Option<Path>,
+    value: TokenStream,
+    skip_if_empty: bool,
+    key: Option<&str>,
+) -> TokenStream {
+    let segment = match key {
+        Some(key) => quote! {
+            Some(::irondash_message_channel::PathSegment::Field(::std::borrow::Cow::Borrowed(#key)))
+        },
+        None => quote! { None },
+    };
+    match with {
+        Some(with) => quote! {
+            ::irondash_message_channel::derive_internal::assign_with(&mut #ident, #with::try_from_value(#value), #segment)?;
+        },
+        None => quote! {
+            (&mut &mut &mut ::irondash_message_channel::derive_internal::WrapMut(&mut #ident)).assign(#value, #skip_if_empty, #segment)?;
+        },
+    }
+}
+
+/// Sets missing `Option` field to `None`.
+fn set_optional_to_none(ident: &Ident, with: &Option<Path>) -> TokenStream {
+    match with {
+        Some(_) => quote! {
+            {
+                use ::irondash_message_channel::derive_internal::SetMissingToNone;
+                (&mut &mut ::irondash_message_channel::derive_internal::WrapMut(&mut #ident)).set_missing_to_none();
+            }
+        },
+        None => quote! {
+            (&mut &mut &mut::irondash_message_channel::derive_internal::WrapMut(&mut #ident)).set_optional_to_none();
+        },
+    }
+}
+
 fn process_struct_named(
     named: &FieldsNamed,
     constructor_suffix: Option<&Ident>,
     rename_rule: RenameRule,
+    tag: Option<&str>,
+    deny_unknown_fields: bool,
 ) -> TokenStream {
     let mut fields = Vec::<Ident>::new();
-    let mut strings = Vec::<String>::new();
+    let mut matches = Vec::<TokenStream>::new();
+    let mut check_duplicate = Vec::<TokenStream>::new();
     let mut types = Vec::<Type>::new();
     let mut err_missing_field = Vec::<String>::new();
-    let mut skip_if_empty = Vec::<bool>::new();
+    let mut assign = Vec::<TokenStream>::new();
+    let mut set_none = Vec::<TokenStream>::new();
 
     let mut skip_fields = Vec::<Ident>::new();
 
+    let mut flatten_fields = Vec::<Ident>::new();
+    let mut flatten_types = Vec::<Type>::new();
+    let mut err_missing_flatten_field = Vec::<String>::new();
+    let mut assign_flatten = Vec::<TokenStream>::new();
+
     let constructor = if let Some(suffix) = constructor_suffix {
         quote! { Self:: #suffix}
     } else {
@@ -275,23 +506,123 @@ fn process_struct_named(
                 skip_fields.push(ident.clone());
                 continue;
             }
+            if attributes.flatten {
+                err_missing_flatten_field.push(format!(
+                    "failed to deserialize flattened field \"{ident}\"."
+                ));
+                assign_flatten.push(match &attributes.with {
+                    Some(_) => assign_field(
+                        ident,
+                        &attributes.with,
+                        quote! { ::irondash_message_channel::Value::Map(__ns_rest.clone().into()) },
+                        false,
+                        None,
+                    ),
+                    None => quote! {
+                        (&mut &mut ::irondash_message_channel::derive_internal::WrapMut(&mut #ident)).assign_flatten(__ns_rest.clone())?;
+                    },
+                });
+                flatten_fields.push(ident.clone());
+                flatten_types.push(field.ty.clone());
+                continue;
+            }
             let string = rename_field(
                 &format!("{ident}"),
                 &rename_rule,
                 &attributes.rename.map(|a| a.value),
             );
             err_missing_field.push(format!("required field \"{string}\" missing in value."));
-            strings.push(string);
+            assign.push(assign_field(
+                ident,
+                &attributes.with,
+                quote! { __ns_e.1 },
+                attributes.skip_if_empty,
+                Some(&string),
+            ));
+            let aliases = attributes.aliases.iter().map(|a| &a.value);
+            matches.push(quote! { __ns_name == #string #(|| __ns_name == #aliases)* });
+            // With aliases the same field may be present under multiple keys.
+            if deny_unknown_fields {
+                let index = fields.len();
+                let error = format!("duplicate field \"{string}\"");
+                check_duplicate.push(quote! {
+                    if ::core::mem::replace(&mut __ns_seen[#index], true) {
+                        let __ns_error = Self::Error::OtherError(#error.into());
+                        return Err(__ns_error.at(::irondash_message_channel::PathSegment::Field(__ns_name.into())));
+                    }
+                });
+            } else {
+                check_duplicate.push(quote! {});
+            }
+            set_none.push(set_optional_to_none(ident, &attributes.with));
             fields.push(ident.clone());
             types.push(field.ty.clone());
-            skip_if_empty.push(attributes.skip_if_empty);
         }
     }
 
+    let skip_tag = tag.map(|tag| {
+        quote! {
+            if __ns_name == #tag {
+                continue;
+            }
+        }
+    });
+    // Entries not matching any field are collected and passed to flattened
+    // fields.
+    let (declare_rest, collect_rest, assign_flatten) = if flatten_fields.is_empty() {
+        if deny_unknown_fields {
+            let deny = quote! {
+                #skip_tag
+                let __ns_error = Self::Error::OtherError(format!("unknown field \"{}\"", __ns_name));
+                return Err(__ns_error.at(::irondash_message_channel::PathSegment::Field(__ns_name.into())));
+            };
+            (quote! {}, deny, quote! {})
+        } else {
+            (quote! {}, quote! {}, quote! {})
+        }
+    } else {
+        if deny_unknown_fields {
+            Diagnostic::spanned(
+                named.span(),
+                Level::Error,
+                "deny_unknown_fields can not be used together with flatten".into(),
+            )
+            .abort();
+        }
+        (
+            quote! {
+                let mut __ns_rest = ::std::vec::Vec::<(::irondash_message_channel::Value, ::irondash_message_channel::Value)>::new();
+            },
+            quote! {
+                #skip_tag
+                __ns_rest.push((__ns_name.into(), __ns_e.1));
+            },
+            quote! {
+                #(
+                    #assign_flatten
+                )*
+            },
+        )
+    };
+
+    let declare_seen = if deny_unknown_fields {
+        let count = fields.len();
+        quote! {
+            let mut __ns_seen = [false; #count];
+        }
+    } else {
+        quote! {}
+    };
+
     quote! {
         #(
             let mut #fields = ::std::option::Option::<#types>::None;
         )*;
+        #declare_seen
+        #(
+            let mut #flatten_fields = ::std::option::Option::<#flatten_types>::None;
+        )*
+        #declare_rest
 
         match __ns_value {
             ::irondash_message_channel::Value::Map(entries) => {
@@ -301,11 +632,13 @@ fn process_struct_named(
                         _ => return Err(Self::Error::OtherError("key value must be a string.".into()))
                     };
                     #(
-                        if __ns_name == #strings {
-                            (&mut &mut &mut ::irondash_message_channel::derive_internal::WrapMut(&mut #fields)).assign(__ns_e.1, #skip_if_empty)?;
+                        if #matches {
+                            #check_duplicate
+                            #assign
                             continue;
                         }
                     )*;
+                    #collect_rest
                 }
             }
             _=> {
@@ -314,13 +647,17 @@ fn process_struct_named(
         }
 
         #(
-            (&mut &mut &mut::irondash_message_channel::derive_internal::WrapMut(&mut #fields)).set_optional_to_none();
-        )*;
+            #set_none
+        )*
+        #assign_flatten
 
         let res = #constructor {
             #(
                 #fields :  #fields.ok_or(Self::Error::OtherError(#err_missing_field.into()))?,
             )*
+            #(
+                #flatten_fields : #flatten_fields.ok_or(Self::Error::OtherError(#err_missing_flatten_field.into()))?,
+            )*
             #(
                 #skip_fields : ::std::default::Default::default(),
             )*
@@ -343,11 +680,16 @@ impl TryIntoStruct {
     }
 
     pub fn process(self, data: DataStruct) -> TokenStream {
+        if let Some(res) = try_from_via(&self.attributes.conversion) {
+            return res;
+        }
         process_struct(
             self.name.span(),
             &data.fields,
             None,
             self.attributes.rename_all,
+            None,
+            self.attributes.deny_unknown_fields,
         )
     }
 }